@@ -34,6 +34,17 @@ pub struct MongoDbConfig {
     pub password: String,
     pub database: String,
 }
+impl MongoDbConfig {
+    pub fn url(&self) -> String {
+        if self.user.is_empty() {
+            return format!("mongodb://{}:{}", self.host, self.port);
+        }
+        format!(
+            "mongodb://{}:{}@{}:{}",
+            self.user, self.password, self.host, self.port
+        )
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PostgresConfig {
@@ -62,6 +73,13 @@ impl PostgresConfig {
 pub struct RedisConfig {
     pub host: String,
     pub port: u16,
+    /// 序列号每次预留的号段长度
+    #[serde(default = "default_seq_step")]
+    pub seq_step: u64,
+}
+
+fn default_seq_step() -> u64 {
+    10000
 }
 impl RedisConfig {
     pub fn url(&self) -> String {
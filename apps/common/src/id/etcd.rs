@@ -0,0 +1,148 @@
+use crate::id::{WorkerId, MAX_WORKER_ID};
+use crate::EtcdConfig;
+use anyhow::{anyhow, bail};
+use etcd_client::{Compare, CompareOp, PutOptions, Txn, TxnOp};
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+const ETCD_WORKER_NAMESPACE: &str = "/lucasim/workers";
+const WORKER_LEASE_TTL_SECOND: i64 = 30;
+const WORKER_KEEPALIVE_INTERVAL_SECOND: u64 = 10;
+/// 单次续约或分配的超时时间, 需要小于续约间隔
+const WORKER_REQUEST_TIMEOUT_SECOND: u64 = 3;
+
+/// 通过etcd租约为`service`分配一个未被占用的worker id, 并在后台续约.
+///
+/// 续约失败时在租约过期之前把worker id标记为失效, 然后重新分配新的worker id
+pub async fn acquire_worker_id(
+    mut client: etcd_client::Client,
+    service: &str,
+) -> anyhow::Result<WorkerId> {
+    let (id, lease_id) = try_acquire(&mut client, service).await?;
+    let worker = WorkerId::fixed(id);
+    tokio::spawn(keep_alive(
+        client,
+        service.to_string(),
+        lease_id,
+        worker.clone(),
+    ));
+    Ok(worker)
+}
+
+pub async fn acquire_worker_id_from_config(
+    config: &EtcdConfig,
+    service: &str,
+) -> anyhow::Result<WorkerId> {
+    let client = etcd_client::Client::connect(&config.hosts, None)
+        .await
+        .map_err(|e| anyhow!("connect to etcd failed: {}", e))?;
+    acquire_worker_id(client, service).await
+}
+
+/// 申请租约并占用第一个空闲的worker id, 返回`(worker id, 租约id)`
+async fn try_acquire(
+    client: &mut etcd_client::Client,
+    service: &str,
+) -> anyhow::Result<(u16, i64)> {
+    let lease_id = client
+        .lease_grant(WORKER_LEASE_TTL_SECOND, None)
+        .await?
+        .id();
+
+    for id in 0..=MAX_WORKER_ID {
+        let key = format!("{}/{}/{}", ETCD_WORKER_NAMESPACE, service, id);
+        // key不存在时才写入, 多个实例同时分配时只有一个成功
+        let txn = Txn::new()
+            .when([Compare::create_revision(key.as_str(), CompareOp::Equal, 0)])
+            .and_then([TxnOp::put(
+                key.as_str(),
+                lease_id.to_string(),
+                Some(PutOptions::new().with_lease(lease_id)),
+            )]);
+        if client.txn(txn).await?.succeeded() {
+            info!("acquired worker id {} for {}", id, service);
+            return Ok((id, lease_id));
+        }
+    }
+
+    let _ = client.lease_revoke(lease_id).await;
+    bail!("no worker id available for {}", service)
+}
+
+/// 定时续约. 下一次续约之前租约可能过期时标记worker id失效, 保证在etcd释放该id之前停止使用,
+/// 之后每个续约间隔尝试重新分配
+async fn keep_alive(
+    mut client: etcd_client::Client,
+    service: String,
+    mut lease_id: i64,
+    worker: WorkerId,
+) {
+    let interval_duration = Duration::from_secs(WORKER_KEEPALIVE_INTERVAL_SECOND);
+    let deadline = Duration::from_secs(WORKER_LEASE_TTL_SECOND as u64) - interval_duration;
+    let timeout = Duration::from_secs(WORKER_REQUEST_TIMEOUT_SECOND);
+    let mut renewed_at = Instant::now();
+    let mut interval = tokio::time::interval(interval_duration);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        if !worker.is_valid() {
+            match tokio::time::timeout(timeout, try_acquire(&mut client, &service)).await {
+                Ok(Ok((id, new_lease_id))) => {
+                    lease_id = new_lease_id;
+                    renewed_at = Instant::now();
+                    worker.reassign(id);
+                }
+                Ok(Err(e)) => warn!("reacquire worker id for {} failed: {:?}", service, e),
+                Err(_) => warn!("reacquire worker id for {} timed out", service),
+            }
+            continue;
+        }
+
+        // 按发出请求的时间计算, etcd从收到请求时重新计时
+        let started = Instant::now();
+        match tokio::time::timeout(timeout, renew(&mut client, lease_id)).await {
+            Ok(Ok(())) => renewed_at = started,
+            Ok(Err(e)) => warn!("renew worker id {} lease failed: {:?}", worker.id(), e),
+            Err(_) => warn!("renew worker id {} lease timed out", worker.id()),
+        }
+        if renewed_at.elapsed() >= deadline {
+            error!("worker id {} lease is about to expire", worker.id());
+            worker.invalidate();
+            // 租约可能仍然有效, 尽量释放, 失败时等待过期
+            let _ = tokio::time::timeout(timeout, client.lease_revoke(lease_id)).await;
+        }
+    }
+}
+
+async fn renew(client: &mut etcd_client::Client, lease_id: i64) -> anyhow::Result<()> {
+    let (mut keeper, mut stream) = client.lease_keep_alive(lease_id).await?;
+    keeper.keep_alive().await?;
+    match stream.message().await? {
+        Some(resp) if resp.ttl() > 0 => Ok(()),
+        Some(_) => bail!("lease {} expired", lease_id),
+        None => bail!("lease keep alive stream closed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires etcd"]
+    async fn test_acquire_worker_id() -> anyhow::Result<()> {
+        let config = EtcdConfig {
+            hosts: vec!["192.168.0.103:2379".to_string()],
+            key: "user.rpc".to_string(),
+            scheme: "".to_string(),
+        };
+        let service = format!("test-{}", nanoid::nanoid!());
+        let first = acquire_worker_id_from_config(&config, &service).await?;
+        let second = acquire_worker_id_from_config(&config, &service).await?;
+        assert_ne!(first.id(), second.id());
+        assert!(first.is_valid());
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Snowflake风格的分布式ID: 41位毫秒时间戳 + 10位worker id + 12位序号, 按生成时间排序.
+//! worker id通过etcd租约分配, 见[`etcd::acquire_worker_id`]
+
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub mod etcd;
+
+/// 时间戳的起点, 2025-01-01T00:00:00Z
+pub const EPOCH_MILLIS: i64 = 1_735_689_600_000;
+
+const WORKER_ID_BITS: u32 = 10;
+const SEQUENCE_BITS: u32 = 12;
+pub const MAX_WORKER_ID: u16 = (1 << WORKER_ID_BITS) - 1;
+const MAX_SEQUENCE: i64 = (1 << SEQUENCE_BITS) - 1;
+
+/// 时钟回拨不超过该值时沿用上一次的时间戳继续分配, 超过时拒绝生成
+const MAX_BACKWARD_MILLIS: i64 = 5;
+
+const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// 62^11 > 2^63, 定长编码后字符串顺序与数值顺序一致
+pub const BASE62_LEN: usize = 11;
+
+/// 分配到的worker id, 租约失效后不能再使用, 否则可能与其他实例重复.
+/// 重新分配后id可能改变, 克隆的实例共享同一个状态
+#[derive(Debug, Clone)]
+pub struct WorkerId {
+    id: Arc<AtomicU16>,
+    valid: Arc<AtomicBool>,
+}
+
+impl WorkerId {
+    /// 固定的worker id, 用于单实例部署和测试
+    pub fn fixed(id: u16) -> Self {
+        Self {
+            id: Arc::new(AtomicU16::new(id)),
+            valid: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    pub fn id(&self) -> u16 {
+        self.id.load(Ordering::Acquire)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.valid.load(Ordering::Acquire)
+    }
+
+    fn invalidate(&self) {
+        self.valid.store(false, Ordering::Release);
+    }
+
+    /// 租约失效后重新分配到新的worker id
+    fn reassign(&self, id: u16) {
+        self.id.store(id, Ordering::Release);
+        self.valid.store(true, Ordering::Release);
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    last_millis: i64,
+    sequence: i64,
+}
+
+#[derive(Debug)]
+pub struct IdGenerator {
+    worker: WorkerId,
+    state: Mutex<State>,
+}
+
+impl IdGenerator {
+    pub fn new(worker: WorkerId) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            worker.id() <= MAX_WORKER_ID,
+            "worker id must be in 0..={}",
+            MAX_WORKER_ID
+        );
+        Ok(Self {
+            worker,
+            state: Mutex::default(),
+        })
+    }
+
+    pub fn worker_id(&self) -> u16 {
+        self.worker.id()
+    }
+
+    /// 生成数字ID, worker id的租约失效或时钟回拨超过限制时返回错误
+    pub fn next_id(&self) -> anyhow::Result<i64> {
+        self.next_id_at(now_millis)
+    }
+
+    /// 生成定长的base62字符串ID
+    pub fn next_string(&self) -> anyhow::Result<String> {
+        self.next_id().map(encode_base62)
+    }
+
+    fn next_id_at(&self, clock: impl Fn() -> i64) -> anyhow::Result<i64> {
+        anyhow::ensure!(
+            self.worker.is_valid(),
+            "worker id {} lease lost",
+            self.worker.id()
+        );
+        let mut state = self.state.lock().unwrap();
+        let mut now = clock();
+        if now < state.last_millis {
+            let backward = state.last_millis - now;
+            anyhow::ensure!(
+                backward <= MAX_BACKWARD_MILLIS,
+                "clock moved backwards by {}ms",
+                backward
+            );
+            now = state.last_millis;
+        }
+
+        if now == state.last_millis {
+            state.sequence = (state.sequence + 1) & MAX_SEQUENCE;
+            // 当前毫秒的序号用完, 等到下一毫秒
+            if state.sequence == 0 {
+                while now <= state.last_millis {
+                    std::thread::yield_now();
+                    now = clock();
+                }
+            }
+        } else {
+            state.sequence = 0;
+        }
+        state.last_millis = now;
+
+        let timestamp = now - EPOCH_MILLIS;
+        anyhow::ensure!(timestamp >= 0, "clock is before epoch");
+        Ok((timestamp << (WORKER_ID_BITS + SEQUENCE_BITS))
+            | ((self.worker.id() as i64) << SEQUENCE_BITS)
+            | state.sequence)
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// ID的生成时间(毫秒)
+pub fn timestamp_millis(id: i64) -> i64 {
+    (id >> (WORKER_ID_BITS + SEQUENCE_BITS)) + EPOCH_MILLIS
+}
+
+/// 编码为定长的base62字符串, `id`不能为负数
+pub fn encode_base62(id: i64) -> String {
+    debug_assert!(id >= 0);
+    let mut value = id as u64;
+    let mut buf = [b'0'; BASE62_LEN];
+    for byte in buf.iter_mut().rev() {
+        *byte = BASE62_ALPHABET[(value % 62) as usize];
+        value /= 62;
+    }
+    String::from_utf8(buf.to_vec()).unwrap()
+}
+
+/// 解析[`encode_base62`]生成的字符串, 格式不正确时返回`None`
+pub fn decode_base62(s: &str) -> Option<i64> {
+    if s.len() != BASE62_LEN {
+        return None;
+    }
+    let mut value: i64 = 0;
+    for byte in s.bytes() {
+        let digit = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'A'..=b'Z' => byte - b'A' + 10,
+            b'a'..=b'z' => byte - b'a' + 36,
+            _ => return None,
+        };
+        value = value.checked_mul(62)?.checked_add(digit as i64)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_next_id() {
+        let generator = IdGenerator::new(WorkerId::fixed(3)).unwrap();
+        let ids: Vec<i64> = (0..10000).map(|_| generator.next_id().unwrap()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!((ids[0] >> SEQUENCE_BITS) & MAX_WORKER_ID as i64, 3);
+        assert!((timestamp_millis(ids[0]) - now_millis()).abs() < 1000);
+
+        assert!(IdGenerator::new(WorkerId::fixed(MAX_WORKER_ID + 1)).is_err());
+    }
+
+    #[test]
+    fn test_clock_backwards() {
+        let generator = IdGenerator::new(WorkerId::fixed(1)).unwrap();
+        let now = EPOCH_MILLIS + 1000;
+        let first = generator.next_id_at(|| now).unwrap();
+
+        // 小幅回拨沿用上一次的时间戳
+        let second = generator.next_id_at(|| now - MAX_BACKWARD_MILLIS).unwrap();
+        assert!(second > first);
+        assert_eq!(timestamp_millis(second), now);
+
+        assert!(generator
+            .next_id_at(|| now - MAX_BACKWARD_MILLIS - 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sequence_overflow() {
+        let generator = IdGenerator::new(WorkerId::fixed(1)).unwrap();
+        let now = Cell::new(EPOCH_MILLIS);
+        let clock = || now.get();
+        let mut last = generator.next_id_at(clock).unwrap();
+        for _ in 0..MAX_SEQUENCE {
+            last = generator.next_id_at(clock).unwrap();
+        }
+        assert_eq!(last & MAX_SEQUENCE, MAX_SEQUENCE);
+
+        // 序号用完后等到下一毫秒
+        let clock = || {
+            now.set(now.get() + 1);
+            now.get()
+        };
+        let next = generator.next_id_at(clock).unwrap();
+        assert!(timestamp_millis(next) > EPOCH_MILLIS);
+        assert_eq!(next & MAX_SEQUENCE, 0);
+    }
+
+    #[test]
+    fn test_lease_lost() {
+        let worker = WorkerId::fixed(1);
+        let generator = IdGenerator::new(worker.clone()).unwrap();
+        assert!(generator.next_id().is_ok());
+        worker.invalidate();
+        assert!(generator.next_id().is_err());
+
+        // 重新分配后使用新的worker id
+        worker.reassign(2);
+        let id = generator.next_id().unwrap();
+        assert_eq!((id >> SEQUENCE_BITS) & MAX_WORKER_ID as i64, 2);
+    }
+
+    #[test]
+    fn test_base62() {
+        assert_eq!(encode_base62(0), "00000000000");
+        assert_eq!(encode_base62(61), "0000000000z");
+        assert_eq!(encode_base62(i64::MAX), "AzL8n0Y58m7");
+        for id in [0, 1, 62, 123_456_789, i64::MAX] {
+            assert_eq!(decode_base62(&encode_base62(id)), Some(id));
+        }
+        assert_eq!(decode_base62("0000000000"), None);
+        assert_eq!(decode_base62("0000000000-"), None);
+        assert_eq!(decode_base62("zzzzzzzzzzz"), None);
+
+        // 字符串顺序与数值顺序一致
+        let generator = IdGenerator::new(WorkerId::fixed(1)).unwrap();
+        let a = generator.next_string().unwrap();
+        let b = generator.next_string().unwrap();
+        assert!(a < b);
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 pub mod config;
+pub mod id;
+pub mod sequence;
 pub mod service_discovery;
 pub mod service_register;
 
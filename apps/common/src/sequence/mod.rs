@@ -0,0 +1,225 @@
+//! 号段模式的序列号分配: 每次从存储中预留`step`个序列号, 在内存中依次分配,
+//! 用完后再预留下一段. 存储中只记录已预留的最大值, 进程退出后未分配的序列号被跳过, 不会重复使用
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+pub mod redis;
+
+/// 持久化已预留的最大序列号
+#[async_trait]
+pub trait SeqStore: Send + Sync + Debug {
+    /// 原子地把`key`的最大值增加`step`, 返回增加后的值
+    async fn reserve(&self, key: &str, step: u64) -> anyhow::Result<i64>;
+}
+
+/// 号段空闲超过该时间后从内存中移除, 未分配的部分被跳过
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// 内存中的号段, 可分配的范围为`[next, max]`
+#[derive(Debug)]
+struct Segment {
+    next: i64,
+    max: i64,
+    last_used: Instant,
+}
+
+impl Default for Segment {
+    fn default() -> Self {
+        Self {
+            next: 0,
+            max: 0,
+            last_used: Instant::now(),
+        }
+    }
+}
+
+/// 按key分配单调递增的序列号, 如会话的消息序号, 用户收件箱的序号.
+///
+/// 同一个key只在一个实例中分配时序列号严格递增; 多个实例分配同一个key时只保证不重复
+#[derive(Debug)]
+pub struct SequenceService {
+    store: Box<dyn SeqStore>,
+    step: u64,
+    segments: DashMap<String, Arc<Mutex<Segment>>>,
+    idle_timeout: Duration,
+    last_evict: std::sync::Mutex<Instant>,
+}
+
+impl SequenceService {
+    pub fn new(store: Box<dyn SeqStore>, step: u64) -> anyhow::Result<Self> {
+        anyhow::ensure!(step > 0, "seq_step must be positive");
+        Ok(Self {
+            store,
+            step,
+            segments: DashMap::new(),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            last_evict: std::sync::Mutex::new(Instant::now()),
+        })
+    }
+
+    /// 修改号段的空闲时间, 默认为10分钟
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// 分配下一个序列号, 从1开始
+    pub async fn next(&self, key: &str) -> anyhow::Result<i64> {
+        let range = self.next_batch(key, 1).await?;
+        Ok(range.0)
+    }
+
+    /// 连续分配`count`个序列号, 返回`(第一个, 最后一个)`; 当前号段不够时丢弃剩余部分
+    pub async fn next_batch(&self, key: &str, count: u64) -> anyhow::Result<(i64, i64)> {
+        anyhow::ensure!(
+            count > 0 && count <= self.step,
+            "count must be in 1..={}",
+            self.step
+        );
+        self.evict_idle();
+        // 先释放DashMap的锁, 预留号段时只阻塞同一个key
+        let segment = self
+            .segments
+            .entry(key.to_string())
+            .or_default()
+            .value()
+            .clone();
+        let mut segment = segment.lock().await;
+
+        let count = count as i64;
+        if segment.next == 0 || segment.max - segment.next + 1 < count {
+            let max = self.store.reserve(key, self.step).await?;
+            segment.next = max - self.step as i64 + 1;
+            segment.max = max;
+        }
+        let first = segment.next;
+        segment.next += count;
+        segment.last_used = Instant::now();
+        Ok((first, first + count - 1))
+    }
+
+    /// 每隔`idle_timeout`移除一次空闲的号段, 避免key只增不减
+    fn evict_idle(&self) {
+        {
+            let mut last_evict = self.last_evict.lock().unwrap();
+            if last_evict.elapsed() < self.idle_timeout {
+                return;
+            }
+            *last_evict = Instant::now();
+        }
+        self.segments.retain(|_, segment| {
+            // 引用计数大于1时有请求正在使用, 移除后同一个key会同时存在两个号段
+            if Arc::strong_count(segment) > 1 {
+                return true;
+            }
+            match segment.try_lock() {
+                Ok(segment) => segment.last_used.elapsed() < self.idle_timeout,
+                Err(_) => true,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct MemorySeqStore {
+        values: std::sync::Mutex<HashMap<String, i64>>,
+        reserved: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SeqStore for Arc<MemorySeqStore> {
+        async fn reserve(&self, key: &str, step: u64) -> anyhow::Result<i64> {
+            self.reserved.fetch_add(1, Ordering::SeqCst);
+            let mut values = self.values.lock().unwrap();
+            let value = values.entry(key.to_string()).or_default();
+            *value += step as i64;
+            Ok(*value)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next() {
+        let store = Arc::new(MemorySeqStore::default());
+        let service = SequenceService::new(Box::new(store.clone()), 3).unwrap();
+
+        for expected in 1..=7 {
+            assert_eq!(service.next("conv").await.unwrap(), expected);
+        }
+        assert_eq!(store.reserved.load(Ordering::SeqCst), 3);
+        // 不同的key互不影响
+        assert_eq!(service.next("inbox").await.unwrap(), 1);
+
+        // 重启后从下一段开始, 上一段未分配的8, 9被跳过
+        let service = SequenceService::new(Box::new(store.clone()), 3).unwrap();
+        assert_eq!(service.next("conv").await.unwrap(), 10);
+
+        assert!(SequenceService::new(Box::new(store), 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_evict_idle() {
+        let store = Arc::new(MemorySeqStore::default());
+        let service = SequenceService::new(Box::new(store), 3)
+            .unwrap()
+            .with_idle_timeout(Duration::ZERO);
+
+        assert_eq!(service.next("conv").await.unwrap(), 1);
+        assert_eq!(service.next("inbox").await.unwrap(), 1);
+        assert_eq!(service.segments.len(), 1);
+        // 被移除的号段中未分配的2, 3被跳过
+        assert_eq!(service.next("conv").await.unwrap(), 4);
+        assert_eq!(service.segments.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_next_batch() {
+        let store = Arc::new(MemorySeqStore::default());
+        let service = SequenceService::new(Box::new(store), 5).unwrap();
+
+        assert_eq!(service.next_batch("conv", 3).await.unwrap(), (1, 3));
+        // 剩余2个不够, 预留下一段
+        assert_eq!(service.next_batch("conv", 3).await.unwrap(), (6, 8));
+        assert_eq!(service.next_batch("conv", 2).await.unwrap(), (9, 10));
+        assert!(service.next_batch("conv", 0).await.is_err());
+        assert!(service.next_batch("conv", 6).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_next() {
+        let store = Arc::new(MemorySeqStore::default());
+        let service = Arc::new(SequenceService::new(Box::new(store), 10).unwrap());
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    let mut seqs = Vec::new();
+                    for _ in 0..25 {
+                        seqs.push(service.next("conv").await.unwrap());
+                    }
+                    seqs
+                })
+            })
+            .collect();
+        let mut seqs = Vec::new();
+        for task in tasks {
+            let task_seqs = task.await.unwrap();
+            // 每个任务拿到的序列号递增
+            assert!(task_seqs.windows(2).all(|pair| pair[0] < pair[1]));
+            seqs.extend(task_seqs);
+        }
+        seqs.sort();
+        assert_eq!(seqs, (1..=200).collect::<Vec<_>>());
+    }
+}
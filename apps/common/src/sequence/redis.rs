@@ -0,0 +1,58 @@
+use crate::sequence::{SeqStore, SequenceService};
+use crate::RedisConfig;
+use async_trait::async_trait;
+use redis::AsyncCommands;
+
+const SEQ_KEY: &str = "seq";
+
+/// 使用Redis的INCRBY预留号段, Redis需要开启持久化, 否则重启后序列号会重复
+#[derive(Debug, Clone)]
+pub struct RedisSeqStore {
+    client: redis::Client,
+}
+
+impl RedisSeqStore {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl SeqStore for RedisSeqStore {
+    async fn reserve(&self, key: &str, step: u64) -> anyhow::Result<i64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let max = conn.incr(format!("{}:{}", SEQ_KEY, key), step).await?;
+        Ok(max)
+    }
+}
+
+impl SequenceService {
+    /// 使用`redis.seq_step`作为号段长度, 为0时返回错误
+    pub fn from_config(config: &RedisConfig) -> anyhow::Result<Self> {
+        let client = redis::Client::open(config.url())?;
+        Self::new(Box::new(RedisSeqStore::new(client)), config.seq_step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires redis"]
+    async fn test_redis_sequence() -> anyhow::Result<()> {
+        let config = RedisConfig {
+            host: "192.168.0.103".to_string(),
+            port: 6379,
+            seq_step: 100,
+        };
+        let key = format!("test:{}", nanoid::nanoid!());
+        let service = SequenceService::from_config(&config)?;
+        assert_eq!(service.next(&key).await?, 1);
+        assert_eq!(service.next(&key).await?, 2);
+
+        let service = SequenceService::from_config(&config)?;
+        assert_eq!(service.next(&key).await?, 101);
+        Ok(())
+    }
+}
@@ -1,12 +1,15 @@
 use crate::config::Config;
+use crate::media::{new_object_store, ObjectStore};
 use common::service_discovery::etcd::EtcdServiceDiscovery;
 use std::sync::Arc;
 use user_rpc::pb::user::user_service_client::UserServiceClient;
 
 #[derive(Clone, Debug)]
 pub struct AppState {
+    pub config: Config,
     pub service_discovery: Arc<EtcdServiceDiscovery>,
     pub user_rpc: UserServiceClient<tonic::transport::Channel>,
+    pub object_store: Arc<dyn ObjectStore>,
 }
 
 async fn init_etcd_discovery(config: &Config) -> EtcdServiceDiscovery {
@@ -43,8 +46,10 @@ impl AppState {
 
 
         Self {
+            config: config.clone(),
             service_discovery: Arc::new(discovery),
             user_rpc,
+            object_store: new_object_store(&config.media),
         }
     }
 }
@@ -0,0 +1,64 @@
+use crate::app_state::AppState;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::Deserialize;
+
+/// 与user-rpc签发的token中的claims一致
+#[derive(Debug, Deserialize)]
+pub struct Claims {
+    pub user_id: String,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+/// 已登录的用户, 从`Authorization: Bearer <token>`中解析
+#[derive(Debug)]
+pub struct AuthUser {
+    pub user_id: String,
+    /// 原始token, 转发给user-rpc做会话校验
+    pub token: String,
+}
+
+impl AuthUser {
+    /// 构造携带当前用户token的rpc请求
+    pub fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        if let Ok(value) = format!("Bearer {}", self.token).parse() {
+            request.metadata_mut().insert("authorization", value);
+        }
+        request
+    }
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let unauthorized = |msg: &str| (StatusCode::UNAUTHORIZED, msg.to_string());
+
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| unauthorized("missing bearer token"))?;
+
+        let data = jsonwebtoken::decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(state.config.jwt.secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|e| unauthorized(&e.to_string()))?;
+
+        Ok(AuthUser {
+            user_id: data.claims.user_id,
+            token: token.to_string(),
+        })
+    }
+}
@@ -7,6 +7,10 @@ pub struct Config {
     pub listen_on: String,
     pub user_rpc: RpcConfig,
     pub jwt: JwtConfig,
+    #[serde(default)]
+    pub media: MediaConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,4 +18,101 @@ pub struct RpcConfig {
     pub etcd: EtcdConfig,
 }
 
+/// 媒体文件的存储方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectStoreKind {
+    #[default]
+    Local,
+    S3,
+}
+
+/// 头像等媒体文件的上传配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MediaConfig {
+    pub store: ObjectStoreKind,
+    /// 返回给客户端的地址前缀, 为空时只返回对象的key
+    pub base_url: String,
+    /// 上传文件的最大字节数
+    pub max_size: usize,
+    /// 图片的最大宽高, 防止解码超大图片
+    pub max_dimension: u32,
+    /// 缩略图的最大边长
+    pub thumbnail_size: u32,
+    pub local: LocalStoreConfig,
+    pub s3: S3Config,
+}
+
+impl Default for MediaConfig {
+    fn default() -> Self {
+        Self {
+            store: ObjectStoreKind::Local,
+            base_url: "/media".to_string(),
+            max_size: 5 * 1024 * 1024,
+            max_dimension: 4096,
+            thumbnail_size: 128,
+            local: LocalStoreConfig::default(),
+            s3: S3Config::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LocalStoreConfig {
+    /// 保存文件的目录
+    pub root: String,
+}
+
+impl Default for LocalStoreConfig {
+    fn default() -> Self {
+        Self {
+            root: "data/media".to_string(),
+        }
+    }
+}
+
+/// S3兼容的对象存储, 使用path-style地址访问
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct S3Config {
+    /// 只支持http, 例如`http://127.0.0.1:9000`
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://127.0.0.1:9000".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "lucas-im".to_string(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            timeout_ms: 10000,
+        }
+    }
+}
+
+/// 个人数据导出的下载配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// 下载链接的地址前缀, 对应`GET /exports/{export_id}`路由
+    pub base_url: String,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "/exports".to_string(),
+        }
+    }
+}
+
 impl LoadableConfig for Config {}
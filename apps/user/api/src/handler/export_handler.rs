@@ -0,0 +1,178 @@
+use crate::app_state::AppState;
+use crate::auth::AuthUser;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, State};
+use axum::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+use user_rpc::pb::user::{
+    DataExport, DataExportStatus, DownloadDataExportRequest, GetDataExportRequest,
+    RequestDataExportRequest,
+};
+
+#[derive(Debug, Serialize)]
+pub struct DataExportResponse {
+    pub id: String,
+    /// pending, running, ready, failed, expired
+    pub status: String,
+    pub file_size: i64,
+    pub create_time: i64,
+    pub expire_time: i64,
+    /// 可以下载时返回, 有效期至`expire_time`
+    pub download_url: Option<String>,
+    pub error: String,
+}
+
+impl DataExportResponse {
+    fn new(export: DataExport, base_url: &str) -> Self {
+        let status = DataExportStatus::try_from(export.status)
+            .unwrap_or_default()
+            .as_str_name()
+            .trim_start_matches("DATA_EXPORT_STATUS_")
+            .to_lowercase();
+        let download_url = (!export.download_token.is_empty()).then(|| {
+            format!(
+                "{}/{}?token={}",
+                base_url.trim_end_matches('/'),
+                export.id,
+                export.download_token
+            )
+        });
+        Self {
+            id: export.id,
+            status,
+            file_size: export.file_size,
+            create_time: export.create_time,
+            expire_time: export.expire_time,
+            download_url,
+            error: export.error,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    pub token: String,
+}
+
+fn rpc_error(status: tonic::Status) -> (StatusCode, String) {
+    let code = match status.code() {
+        tonic::Code::InvalidArgument => StatusCode::BAD_REQUEST,
+        tonic::Code::NotFound => StatusCode::NOT_FOUND,
+        // 下载链接已过期
+        tonic::Code::PermissionDenied => StatusCode::GONE,
+        _ => StatusCode::BAD_GATEWAY,
+    };
+    (code, status.message().to_string())
+}
+
+/// 申请导出个人数据, 导出由后台任务完成, 进行中或最近导出过时返回已有的导出
+pub async fn request_data_export_handler(
+    auth: AuthUser,
+    State(mut app_state): State<AppState>,
+) -> Result<Json<DataExportResponse>, (StatusCode, String)> {
+    let request = auth.request(RequestDataExportRequest {
+        user_id: auth.user_id.clone(),
+    });
+    let export = app_state
+        .user_rpc
+        .request_data_export(request)
+        .await
+        .map_err(rpc_error)?
+        .into_inner()
+        .export
+        .unwrap_or_default();
+    Ok(Json(DataExportResponse::new(
+        export,
+        &app_state.config.export.base_url,
+    )))
+}
+
+/// 查询导出状态, 完成后返回下载链接
+pub async fn get_data_export_handler(
+    auth: AuthUser,
+    Path(export_id): Path<String>,
+    State(mut app_state): State<AppState>,
+) -> Result<Json<DataExportResponse>, (StatusCode, String)> {
+    let request = auth.request(GetDataExportRequest {
+        user_id: auth.user_id.clone(),
+        export_id,
+    });
+    let export = app_state
+        .user_rpc
+        .get_data_export(request)
+        .await
+        .map_err(rpc_error)?
+        .into_inner()
+        .export
+        .unwrap_or_default();
+    Ok(Json(DataExportResponse::new(
+        export,
+        &app_state.config.export.base_url,
+    )))
+}
+
+/// 下载导出的压缩包, 凭链接中的token下载, 不需要登录
+pub async fn download_data_export_handler(
+    Path(export_id): Path<String>,
+    Query(query): Query<DownloadQuery>,
+    State(mut app_state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let stream = app_state
+        .user_rpc
+        .download_data_export(DownloadDataExportRequest {
+            export_id: export_id.clone(),
+            download_token: query.token,
+        })
+        .await
+        .map_err(rpc_error)?
+        .into_inner()
+        .map(|chunk| {
+            chunk
+                .map(|chunk| Bytes::from(chunk.data))
+                .map_err(axum::Error::new)
+        });
+
+    let disposition = format!("attachment; filename=\"lucas-im-export-{}.zip\"", export_id);
+    Ok((
+        [
+            (CONTENT_TYPE, "application/zip".to_string()),
+            (CONTENT_DISPOSITION, disposition),
+            (CACHE_CONTROL, "no-store".to_string()),
+        ],
+        Body::from_stream(stream),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_url() {
+        let export = DataExport {
+            id: "id".to_string(),
+            status: DataExportStatus::Ready as i32,
+            download_token: "token".to_string(),
+            ..Default::default()
+        };
+        let response = DataExportResponse::new(export.clone(), "/exports/");
+        assert_eq!(response.status, "ready");
+        assert_eq!(
+            response.download_url.as_deref(),
+            Some("/exports/id?token=token")
+        );
+
+        let export = DataExport {
+            status: DataExportStatus::Pending as i32,
+            download_token: String::new(),
+            ..export
+        };
+        let response = DataExportResponse::new(export, "/exports");
+        assert_eq!(response.status, "pending");
+        assert_eq!(response.download_url, None);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::app_state::AppState;
+use crate::auth::AuthUser;
+use crate::media::avatar::{process_avatar, AvatarError};
+use crate::media::{check_key, object_url};
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Serialize;
+use tracing::error;
+use user_rpc::pb::user::{UpdateUserProfileRequest, User};
+
+#[derive(Debug, Serialize)]
+pub struct UploadAvatarResponse {
+    pub avatar: String,
+    pub thumbnail: String,
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    error!("media error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "internal error".to_string(),
+    )
+}
+
+/// 上传头像, 请求体为图片内容, `Content-Type`为图片类型
+///
+/// 保存原图和缩略图后把`User.avatar`更新为原图地址
+pub async fn upload_avatar_handler(
+    auth: AuthUser,
+    State(mut app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<UploadAvatarResponse>, (StatusCode, String)> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let config = app_state.config.media.clone();
+    let avatar = tokio::task::spawn_blocking(move || process_avatar(body, &content_type, &config))
+        .await
+        .map_err(internal_error)?
+        .map_err(|e| match e {
+            AvatarError::UnsupportedType(_) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, e.to_string()),
+            AvatarError::TooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()),
+            AvatarError::InvalidImage(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+        })?;
+
+    // 相同内容的图片已经上传过时不再重复保存, 先写缩略图, 原图存在即表示两者都已保存
+    let store = &app_state.object_store;
+    if !store.exists(&avatar.key).await.map_err(internal_error)? {
+        store
+            .put(&avatar.thumbnail_key, avatar.thumbnail, "image/png")
+            .await
+            .map_err(internal_error)?;
+        store
+            .put(&avatar.key, avatar.data, avatar.content_type)
+            .await
+            .map_err(internal_error)?;
+    }
+
+    let base_url = &app_state.config.media.base_url;
+    let url = object_url(base_url, &avatar.key);
+    let request = auth.request(UpdateUserProfileRequest {
+        user_id: auth.user_id.clone(),
+        user: Some(User {
+            avatar: url.clone(),
+            ..Default::default()
+        }),
+        update_mask: Some(prost_types::FieldMask {
+            paths: vec!["avatar".to_string()],
+        }),
+    });
+    app_state
+        .user_rpc
+        .update_user_profile(request)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.message().to_string()))?;
+
+    Ok(Json(UploadAvatarResponse {
+        avatar: url,
+        thumbnail: object_url(base_url, &avatar.thumbnail_key),
+    }))
+}
+
+/// 下载对象, 用于本地存储或没有配置CDN的部署
+pub async fn get_media_handler(
+    Path(key): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if check_key(&key).is_err() {
+        return Err((StatusCode::NOT_FOUND, "not found".to_string()));
+    }
+    let object = app_state
+        .object_store
+        .get(&key)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "not found".to_string()))?;
+    Ok(([(CONTENT_TYPE, object.content_type)], object.data))
+}
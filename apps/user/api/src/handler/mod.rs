@@ -1 +1,15 @@
+use std::net::SocketAddr;
 
+pub mod export_handler;
+pub mod media_handler;
+pub mod password_handler;
+pub mod user_handler;
+
+/// 转发客户端IP给user-rpc, 用于限流
+pub(crate) fn with_client_ip<T>(message: T, addr: SocketAddr) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    if let Ok(ip) = addr.ip().to_string().parse() {
+        request.metadata_mut().insert("x-real-ip", ip);
+    }
+    request
+}
@@ -0,0 +1,54 @@
+use crate::app_state::AppState;
+use crate::handler::with_client_ip;
+use axum::extract::{ConnectInfo, State};
+use axum::Json;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use user_rpc::pb::user::{ResetPasswordRequest, SendPasswordResetCodeRequest};
+
+#[derive(Debug, Deserialize)]
+pub struct SendPasswordResetCodeBody {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordBody {
+    pub email: String,
+    pub code: String,
+    pub password: String,
+}
+
+pub async fn send_password_reset_code_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(mut app_state): State<AppState>,
+    Json(body): Json<SendPasswordResetCodeBody>,
+) -> Result<(), String> {
+    let request = with_client_ip(SendPasswordResetCodeRequest { email: body.email }, addr);
+    app_state
+        .user_rpc
+        .send_password_reset_code(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn reset_password_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(mut app_state): State<AppState>,
+    Json(body): Json<ResetPasswordBody>,
+) -> Result<(), String> {
+    let request = with_client_ip(
+        ResetPasswordRequest {
+            email: body.email,
+            code: body.code,
+            password: body.password,
+        },
+        addr,
+    );
+    app_state
+        .user_rpc
+        .reset_password(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
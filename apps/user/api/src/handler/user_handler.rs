@@ -0,0 +1,72 @@
+use crate::app_state::AppState;
+use crate::auth::AuthUser;
+use axum::extract::State;
+use axum::Json;
+use serde::Deserialize;
+use user_rpc::pb::user::{UpdateUserProfileRequest, User};
+
+/// 只修改请求中出现的字段
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateProfileBody {
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub gender: Option<String>,
+    pub age: Option<i32>,
+    pub signature: Option<String>,
+    pub address: Option<String>,
+    pub region: Option<String>,
+    pub birthday: Option<i64>,
+}
+
+impl UpdateProfileBody {
+    fn into_user_and_mask(self) -> (User, Vec<String>) {
+        let paths = [
+            ("name", self.name.is_some()),
+            ("avatar", self.avatar.is_some()),
+            ("gender", self.gender.is_some()),
+            ("age", self.age.is_some()),
+            ("signature", self.signature.is_some()),
+            ("address", self.address.is_some()),
+            ("region", self.region.is_some()),
+            ("birthday", self.birthday.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, present)| *present)
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+        let user = User {
+            name: self.name.unwrap_or_default(),
+            avatar: self.avatar.unwrap_or_default(),
+            gender: self.gender.unwrap_or_default(),
+            age: self.age.unwrap_or_default(),
+            signature: self.signature.unwrap_or_default(),
+            address: self.address,
+            region: self.region,
+            birthday: self.birthday,
+            ..Default::default()
+        };
+        (user, paths)
+    }
+}
+
+pub async fn update_profile_handler(
+    auth: AuthUser,
+    State(mut app_state): State<AppState>,
+    Json(body): Json<UpdateProfileBody>,
+) -> Result<Json<User>, String> {
+    let (user, paths) = body.into_user_and_mask();
+    let request = auth.request(UpdateUserProfileRequest {
+        user_id: auth.user_id.clone(),
+        user: Some(user),
+        update_mask: Some(prost_types::FieldMask { paths }),
+    });
+
+    let resp = app_state
+        .user_rpc
+        .update_user_profile(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let user = resp.into_inner().user.unwrap_or_default();
+    Ok(Json(user))
+}
@@ -1,15 +1,23 @@
 use std::net::SocketAddr;
 use axum::extract::{Path, State};
 use axum::{Json, Router};
-use axum::routing::get;
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, patch, post, put};
 use tracing::info;
 use user_rpc::pb::user::{FindUserRequest, User};
 use crate::app_state::AppState;
+use crate::auth::AuthUser;
 use crate::config::Config;
+use crate::handler::export_handler::{download_data_export_handler, get_data_export_handler, request_data_export_handler};
+use crate::handler::media_handler::{get_media_handler, upload_avatar_handler};
+use crate::handler::password_handler::{reset_password_handler, send_password_reset_code_handler};
+use crate::handler::user_handler::update_profile_handler;
 
+pub mod auth;
 pub mod config;
 pub mod handler;
 pub mod logic;
+pub mod media;
 pub mod service_context;
 
 pub mod app_state;
@@ -26,17 +34,31 @@ pub async fn start_server(config: Config) {
 }
 
 fn app_routes(state: AppState) -> Router {
-    Router::new().route("/{user_id}", get(get_user_by_id)).with_state(state)
+    // 超过限制时直接返回413, 不读取完整的请求体
+    let avatar_limit = DefaultBodyLimit::max(state.config.media.max_size);
+    Router::new()
+        .route("/{user_id}", get(get_user_by_id))
+        .route("/me", patch(update_profile_handler))
+        .route("/me/avatar", put(upload_avatar_handler).layer(avatar_limit))
+        .route("/media/{*key}", get(get_media_handler))
+        .route("/me/exports", post(request_data_export_handler))
+        .route("/me/exports/{export_id}", get(get_data_export_handler))
+        .route("/exports/{export_id}", get(download_data_export_handler))
+        .route("/password/reset-code", post(send_password_reset_code_handler))
+        .route("/password/reset", post(reset_password_handler))
+        .with_state(state)
 
 }
 pub async fn get_user_by_id(
+    auth: AuthUser,
     Path((user_id)): Path<(String)>,
     State(mut app_state): State<AppState>,
 ) -> Result<Json<Vec<User>>, String> {
-    let user = app_state.user_rpc.find_user(FindUserRequest{
+    // user-rpc要求登录后才能查询用户
+    let user = app_state.user_rpc.find_user(auth.request(FindUserRequest{
         user_id: vec![user_id],
         ..Default::default()
-    }).await.map_err(|e| e.to_string())?;
+    })).await.map_err(|e| e.to_string())?;
     let user = user.into_inner().users;
     Ok(Json(user))
 }
\ No newline at end of file
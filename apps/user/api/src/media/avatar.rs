@@ -0,0 +1,175 @@
+use crate::config::MediaConfig;
+use bytes::Bytes;
+use image::{ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+
+/// 允许上传的图片类型
+const ALLOWED_TYPES: &[(&str, ImageFormat)] = &[
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/png", ImageFormat::Png),
+    ("image/gif", ImageFormat::Gif),
+    ("image/webp", ImageFormat::WebP),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AvatarError {
+    UnsupportedType(String),
+    TooLarge(usize),
+    InvalidImage(String),
+}
+
+impl Display for AvatarError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AvatarError::UnsupportedType(content_type) => {
+                write!(f, "unsupported content type: {}", content_type)
+            }
+            AvatarError::TooLarge(max_size) => write!(f, "file is larger than {} bytes", max_size),
+            AvatarError::InvalidImage(msg) => write!(f, "invalid image, {}", msg),
+        }
+    }
+}
+
+/// 校验并生成缩略图后的头像
+#[derive(Debug, Clone)]
+pub struct Avatar {
+    /// 按内容哈希生成, 相同的图片只保存一份
+    pub key: String,
+    pub content_type: &'static str,
+    pub data: Bytes,
+    pub thumbnail_key: String,
+    pub thumbnail: Bytes,
+}
+
+/// 校验图片类型和大小, 生成PNG格式的缩略图
+///
+/// 解码图片比较耗时, 需要在阻塞线程中调用
+pub fn process_avatar(
+    data: Bytes,
+    content_type: &str,
+    config: &MediaConfig,
+) -> Result<Avatar, AvatarError> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let Some((content_type, format)) = ALLOWED_TYPES
+        .iter()
+        .find(|(allowed, _)| *allowed == mime)
+        .copied()
+    else {
+        return Err(AvatarError::UnsupportedType(mime));
+    };
+    if data.len() > config.max_size {
+        return Err(AvatarError::TooLarge(config.max_size));
+    }
+    // 以文件内容为准, 不能只相信客户端声明的类型
+    match image::guess_format(&data) {
+        Ok(guessed) if guessed == format => {}
+        _ => {
+            return Err(AvatarError::InvalidImage(format!(
+                "content is not {}",
+                content_type
+            )))
+        }
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(config.max_dimension);
+    limits.max_image_height = Some(config.max_dimension);
+    let mut reader = ImageReader::with_format(Cursor::new(&data), format);
+    reader.limits(limits);
+    let image = reader
+        .decode()
+        .map_err(|e| AvatarError::InvalidImage(e.to_string()))?;
+
+    let mut thumbnail = Vec::new();
+    image
+        .thumbnail(config.thumbnail_size, config.thumbnail_size)
+        .write_to(&mut Cursor::new(&mut thumbnail), ImageFormat::Png)
+        .map_err(|e| AvatarError::InvalidImage(e.to_string()))?;
+
+    let hash: String = Sha256::digest(&data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let ext = format.extensions_str().first().copied().unwrap_or("bin");
+    Ok(Avatar {
+        key: format!("avatar/{}.{}", hash, ext),
+        content_type,
+        data,
+        thumbnail_key: format!("avatar/{}_thumb.png", hash),
+        thumbnail: Bytes::from(thumbnail),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+
+    fn png(width: u32, height: u32) -> Bytes {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+        let mut data = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        Bytes::from(data)
+    }
+
+    #[test]
+    fn test_process_avatar() {
+        let config = MediaConfig::default();
+        let data = png(400, 200);
+
+        let avatar = process_avatar(data.clone(), "image/png", &config).unwrap();
+        assert!(avatar.key.starts_with("avatar/"));
+        assert!(avatar.key.ends_with(".png"));
+        assert_eq!(avatar.data, data);
+        let thumbnail = image::load_from_memory(&avatar.thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (128, 64));
+
+        // 相同内容得到相同的key
+        let again = process_avatar(data.clone(), "Image/PNG; charset=binary", &config).unwrap();
+        assert_eq!(again.key, avatar.key);
+        assert_ne!(
+            process_avatar(png(10, 10), "image/png", &config)
+                .unwrap()
+                .key,
+            avatar.key
+        );
+    }
+
+    #[test]
+    fn test_process_avatar_rejects() {
+        let config = MediaConfig {
+            max_size: 1024,
+            max_dimension: 64,
+            ..Default::default()
+        };
+        assert_eq!(
+            process_avatar(png(10, 10), "image/svg+xml", &config).unwrap_err(),
+            AvatarError::UnsupportedType("image/svg+xml".to_string())
+        );
+        assert!(matches!(
+            process_avatar(png(10, 10), "image/jpeg", &config),
+            Err(AvatarError::InvalidImage(_))
+        ));
+        assert!(matches!(
+            process_avatar(Bytes::from_static(b"not an image"), "image/png", &config),
+            Err(AvatarError::InvalidImage(_))
+        ));
+        assert!(matches!(
+            process_avatar(png(100, 10), "image/png", &config),
+            Err(AvatarError::InvalidImage(_))
+        ));
+        assert_eq!(
+            process_avatar(Bytes::from(vec![0; 2048]), "image/png", &config).unwrap_err(),
+            AvatarError::TooLarge(1024)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::media::{check_key, Object, ObjectStore};
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// 保存在本地目录的对象存储, 适合单机部署和开发环境
+#[derive(Debug, Clone)]
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+/// 本地文件不保存类型, 按扩展名推断
+fn content_type_by_key(key: &str) -> &'static str {
+    let ext = key.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    match ext.to_ascii_lowercase().as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // 先写临时文件再重命名, 避免读到写了一半的文件
+        let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+        tokio::fs::write(&tmp, &data).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Object>> {
+        let path = self.path(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Some(Object {
+                data: Bytes::from(data),
+                content_type: content_type_by_key(key).to_string(),
+            })),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> anyhow::Result<bool> {
+        let path = self.path(key)?;
+        Ok(tokio::fs::try_exists(&path).await?)
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_object_store() {
+        let root = std::env::temp_dir().join(format!("user-api-media-{}", std::process::id()));
+        let store = LocalObjectStore::new(&root);
+        let key = "avatar/abc.png";
+
+        assert!(!store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap(), None);
+        store
+            .put(key, Bytes::from_static(b"png"), "image/png")
+            .await
+            .unwrap();
+        assert!(store.exists(key).await.unwrap());
+        let object = store.get(key).await.unwrap().unwrap();
+        assert_eq!(object.data, Bytes::from_static(b"png"));
+        assert_eq!(object.content_type, "image/png");
+
+        store.delete(key).await.unwrap();
+        store.delete(key).await.unwrap();
+        assert!(!store.exists(key).await.unwrap());
+        assert!(store.get("../secret").await.is_err());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+//! 头像等媒体文件的处理与存储
+
+pub mod avatar;
+pub mod local;
+pub mod s3;
+
+use crate::config::{MediaConfig, ObjectStoreKind};
+use anyhow::bail;
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// 读取到的对象
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub data: Bytes,
+    pub content_type: String,
+}
+
+/// 对象存储, key由`/`分隔, 只包含字母/数字/`-`/`_`/`.`
+#[async_trait]
+pub trait ObjectStore: Send + Sync + Debug {
+    /// 写入对象, 已存在时覆盖
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> anyhow::Result<()>;
+
+    /// 读取对象, 不存在时返回`None`
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Object>>;
+
+    async fn exists(&self, key: &str) -> anyhow::Result<bool>;
+
+    /// 删除对象, 不存在时不报错
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+pub fn new_object_store(config: &MediaConfig) -> Arc<dyn ObjectStore> {
+    match config.store {
+        ObjectStoreKind::Local => Arc::new(local::LocalObjectStore::new(&config.local.root)),
+        ObjectStoreKind::S3 => Arc::new(s3::S3ObjectStore::new(config.s3.clone())),
+    }
+}
+
+/// 检查key是否合法, 防止访问存储目录之外的文件
+pub fn check_key(key: &str) -> anyhow::Result<()> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if !valid {
+        bail!("invalid object key: {}", key);
+    }
+    Ok(())
+}
+
+/// 返回给客户端的对象地址
+pub fn object_url(base_url: &str, key: &str) -> String {
+    if base_url.is_empty() {
+        return key.to_string();
+    }
+    format!("{}/{}", base_url.trim_end_matches('/'), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_key() {
+        assert!(check_key("avatar/abc_thumb.png").is_ok());
+        assert!(check_key("").is_err());
+        assert!(check_key("/etc/passwd").is_err());
+        assert!(check_key("avatar/../../etc/passwd").is_err());
+        assert!(check_key("avatar//a.png").is_err());
+        assert!(check_key("avatar/a b.png").is_err());
+    }
+
+    #[test]
+    fn test_object_url() {
+        assert_eq!(object_url("", "avatar/a.png"), "avatar/a.png");
+        assert_eq!(object_url("/media/", "avatar/a.png"), "/media/avatar/a.png");
+        assert_eq!(
+            object_url("https://cdn.example.com", "avatar/a.png"),
+            "https://cdn.example.com/avatar/a.png"
+        );
+    }
+}
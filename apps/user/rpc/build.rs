@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::process::Command;
 
 pub trait BuilderExt {
@@ -12,16 +13,23 @@ impl BuilderExt for tonic_build::Builder {
     }
 }
 fn main() {
+    // 服务反射使用的描述文件, 不需要提交
+    let descriptor_path =
+        PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("user_descriptor.bin");
     tonic_build::configure()
         .out_dir("src/pb")
+        .file_descriptor_set_path(descriptor_path)
         .field_attribute("User.password", "#[serde(skip_serializing)]")
-        .field_attribute("User.salt", "#[serde(skip_serializing)]")
         .with_serde(&["User"])
-        .compile_protos(&["protos/user.proto"], &["protos"])
+        .compile_protos(
+            &["protos/user.proto", "protos/reflection.proto"],
+            &["protos"],
+        )
         .unwrap();
 
     // execute cargo fmt command
     Command::new("cargo").arg("fmt").output().unwrap();
 
     println!("cargo: rerun-if-changed=apps/user/rpc/protos/user.proto");
+    println!("cargo: rerun-if-changed=apps/user/rpc/protos/reflection.proto");
 }
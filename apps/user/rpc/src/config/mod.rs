@@ -1,4 +1,6 @@
+use common::id::MAX_WORKER_ID;
 use common::{EtcdConfig, JwtConfig, LoadableConfig, MongoDbConfig, PostgresConfig, RedisConfig};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -10,6 +12,520 @@ pub struct Config {
     pub postgres: PostgresConfig,
     pub redis: RedisConfig,
     pub jwt: JwtConfig,
+    /// 用户数据的存储方式
+    #[serde(default)]
+    pub user_store: UserStoreKind,
+    #[serde(default)]
+    pub migration: MigrationConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub password: PasswordConfig,
+    #[serde(default)]
+    pub user_cache: UserCacheConfig,
+    #[serde(default)]
+    pub account: AccountConfig,
+    #[serde(default)]
+    pub sms: SmsConfig,
+    #[serde(default)]
+    pub mail: MailConfig,
+    #[serde(default)]
+    pub two_factor: TwoFactorConfig,
+    #[serde(default)]
+    pub oidc: OidcConfig,
+    #[serde(default)]
+    pub presence: PresenceConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub blocklist: BlocklistConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub id: IdConfig,
 }
 
 impl LoadableConfig for Config {}
+
+/// 验证码位数的范围, 超过19位时`10^n`溢出u64
+const CODE_LENGTH_RANGE: std::ops::RangeInclusive<u32> = 4..=10;
+
+impl Config {
+    /// 检查配置的取值, 启动时调用
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !CODE_LENGTH_RANGE.contains(&self.rate_limit.code_length) {
+            anyhow::bail!(
+                "rate_limit.code_length must be in {:?}, got {}",
+                CODE_LENGTH_RANGE,
+                self.rate_limit.code_length
+            );
+        }
+        // 后台任务的间隔为0时`tokio::time::interval`会panic
+        let intervals = [
+            ("account.purge_interval", self.account.purge_interval),
+            (
+                "presence.heartbeat_interval",
+                self.presence.heartbeat_interval,
+            ),
+            (
+                "server.health_check_interval",
+                self.server.health_check_interval,
+            ),
+            ("export.poll_interval", self.export.poll_interval),
+        ];
+        for (name, value) in intervals {
+            if value == 0 {
+                anyhow::bail!("{} must be greater than 0", name);
+            }
+        }
+        if let Some(worker_id) = self.id.fallback_worker_id {
+            if worker_id > MAX_WORKER_ID {
+                anyhow::bail!(
+                    "id.fallback_worker_id must be in 0..={}, got {}",
+                    MAX_WORKER_ID,
+                    worker_id
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 用户数据的存储方式, 两步验证/第三方绑定/安全事件仍然保存在Postgres中
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStoreKind {
+    #[default]
+    Postgres,
+    Mongodb,
+}
+
+/// 数据库迁移配置
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MigrationConfig {
+    /// 启动时自动执行未执行的迁移, 关闭时只检查数据库结构是否兼容
+    pub auto_migrate: bool,
+}
+
+/// 登录与验证码的限流/防爆破配置, 窗口单位均为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// 验证码位数
+    pub code_length: u32,
+    /// 同一账号在窗口内最多登录次数
+    pub login_per_account: u64,
+    /// 同一IP在窗口内最多登录次数
+    pub login_per_ip: u64,
+    pub login_window: u64,
+    /// 同一账号/邮箱在窗口内最多发送验证码次数
+    pub send_code_per_target: u64,
+    /// 同一IP在窗口内最多发送验证码次数
+    pub send_code_per_ip: u64,
+    pub send_code_window: u64,
+    /// 连续失败多少次后锁定(登录密码错误或验证码错误)
+    pub max_failed_attempts: u64,
+    /// 失败计数的统计窗口
+    pub failed_attempts_window: u64,
+    /// 锁定时长
+    pub lockout_duration: u64,
+    /// 可信的代理(如user-api)地址, 只有来自这些地址的请求才读取`x-real-ip`/`x-forwarded-for`,
+    /// 否则使用连接的对端地址
+    pub trusted_proxies: Vec<IpNet>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            code_length: 6,
+            login_per_account: 10,
+            login_per_ip: 50,
+            login_window: 60,
+            send_code_per_target: 1,
+            send_code_per_ip: 10,
+            send_code_window: 60,
+            max_failed_attempts: 5,
+            failed_attempts_window: 900,
+            lockout_duration: 900,
+            trusted_proxies: vec![],
+        }
+    }
+}
+
+/// Argon2id密码哈希参数, 调高后旧密码会在登录时自动重新哈希
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordConfig {
+    /// 内存开销, 单位KiB
+    pub memory_kib: u32,
+    /// 迭代次数
+    pub iterations: u32,
+    /// 并行度
+    pub parallelism: u32,
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        Self {
+            memory_kib: argon2::Params::DEFAULT_M_COST,
+            iterations: argon2::Params::DEFAULT_T_COST,
+            parallelism: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// 用户信息读缓存配置, 时间单位为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct UserCacheConfig {
+    pub enabled: bool,
+    pub ttl: u64,
+    /// 空值缓存的过期时间
+    pub null_ttl: u64,
+    /// 过期时间的随机抖动上限
+    pub jitter: u64,
+    /// 延迟双删的延迟毫秒数, 0表示不做第二次删除
+    pub delayed_delete_ms: u64,
+}
+
+impl Default for UserCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: 3600,
+            null_ttl: 60,
+            jitter: 300,
+            delayed_delete_ms: 500,
+        }
+    }
+}
+
+/// 宽限期结束后对已注销账号的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeMode {
+    /// 清空个人信息, 保留用户id供历史消息等引用
+    Anonymize,
+    /// 直接删除记录
+    Delete,
+}
+
+/// 账号注销配置, 时间单位为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AccountConfig {
+    /// 注销后可以恢复的宽限期
+    pub deactivation_grace_period: u64,
+    /// 清理任务的执行间隔
+    pub purge_interval: u64,
+    /// 每批清理的账号数量
+    pub purge_batch_size: i64,
+    pub purge_mode: PurgeMode,
+}
+
+impl Default for AccountConfig {
+    fn default() -> Self {
+        Self {
+            deactivation_grace_period: 30 * 24 * 60 * 60,
+            purge_interval: 60 * 60,
+            purge_batch_size: 100,
+            purge_mode: PurgeMode::Anonymize,
+        }
+    }
+}
+
+/// 短信发送方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmsSenderKind {
+    /// 只打印日志并保存在内存中, 用于开发和测试
+    Mock,
+    /// 追加写入到文件
+    File,
+}
+
+/// 短信验证码配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SmsConfig {
+    /// 手机号没有国际区号时使用的默认区号
+    pub default_country_code: String,
+    pub sender: SmsSenderKind,
+    /// `sender`为`file`时写入的文件
+    pub file_path: String,
+}
+
+impl Default for SmsConfig {
+    fn default() -> Self {
+        Self {
+            default_country_code: "86".to_string(),
+            sender: SmsSenderKind::Mock,
+            file_path: "sms.log".to_string(),
+        }
+    }
+}
+
+/// 邮件发送方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MailSenderKind {
+    /// 只打印日志并保存在内存中, 用于开发和测试
+    Mock,
+    /// 追加写入到文件
+    File,
+}
+
+/// 邮件验证码配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MailConfig {
+    pub sender: MailSenderKind,
+    /// `sender`为`file`时写入的文件
+    pub file_path: String,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            sender: MailSenderKind::Mock,
+            file_path: "mail.log".to_string(),
+        }
+    }
+}
+
+/// 两步验证(TOTP)配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TwoFactorConfig {
+    /// 验证器App中显示的发行方
+    pub issuer: String,
+    /// 密码校验通过后, 完成两步验证的时限(秒)
+    pub challenge_ttl: u64,
+    /// 允许的时间步误差
+    pub skew: i64,
+    /// 恢复码数量
+    pub recovery_code_count: usize,
+}
+
+impl Default for TwoFactorConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "Lucas-IM".to_string(),
+            challenge_ttl: 300,
+            skew: 1,
+            recovery_code_count: 10,
+        }
+    }
+}
+
+/// 第三方OIDC身份提供方配置
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OidcProviderConfig {
+    /// 提供方名称, 如`google`, 请求中使用该名称选择提供方
+    pub name: String,
+    /// ID token中的`iss`
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+    /// 授权后回调的地址, 由客户端接收授权码后调用登录/绑定接口
+    pub redirect_uri: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+}
+
+/// 启动时会打印配置, 不输出client_secret
+impl std::fmt::Debug for OidcProviderConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OidcProviderConfig")
+            .field("name", &self.name)
+            .field("issuer", &self.issuer)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"***")
+            .field("authorization_endpoint", &self.authorization_endpoint)
+            .field("token_endpoint", &self.token_endpoint)
+            .field("jwks_uri", &self.jwks_uri)
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "email".to_string(),
+        "profile".to_string(),
+    ]
+}
+
+/// 第三方登录配置, 时间单位为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    pub providers: Vec<OidcProviderConfig>,
+    /// 授权请求state的有效期
+    pub state_ttl: u64,
+    /// 公钥集合的缓存时间, 遇到未知的kid时会提前刷新
+    pub jwks_cache_ttl: u64,
+    /// 请求提供方接口的超时毫秒数
+    pub http_timeout_ms: u64,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![],
+            state_ttl: 600,
+            jwks_cache_ttl: 3600,
+            http_timeout_ms: 5000,
+        }
+    }
+}
+
+/// 在线状态配置, 时间单位为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PresenceConfig {
+    /// 客户端心跳间隔
+    pub heartbeat_interval: u64,
+    /// 超过该时间没有心跳的设备视为离线
+    pub ttl: u64,
+    /// 允许的平台, 按平台统计在线人数时使用
+    pub platforms: Vec<String>,
+    /// 批量查询的最大用户数
+    pub max_batch_size: usize,
+    /// WatchUsers一次订阅的最大用户数
+    #[serde(default = "default_max_watch_users")]
+    pub max_watch_users: usize,
+}
+
+fn default_max_watch_users() -> usize {
+    500
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: 30,
+            ttl: 90,
+            platforms: ["ios", "android", "web", "desktop"]
+                .map(String::from)
+                .to_vec(),
+            max_batch_size: 200,
+            max_watch_users: default_max_watch_users(),
+        }
+    }
+}
+
+/// rpc服务配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// 开启服务反射, 供grpcurl等工具使用
+    pub reflection: bool,
+    /// 检查Postgres/Redis是否可用的间隔, 单位为秒
+    pub health_check_interval: u64,
+    /// 单次检查的超时时间, 单位为毫秒
+    pub health_check_timeout_ms: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            reflection: true,
+            health_check_interval: 5,
+            health_check_timeout_ms: 2000,
+        }
+    }
+}
+
+/// 黑名单配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BlocklistConfig {
+    /// 每个用户最多拉黑的人数, 拉黑关系检查时会读取全部记录
+    pub max_size: i64,
+}
+
+impl Default for BlocklistConfig {
+    fn default() -> Self {
+        Self { max_size: 1000 }
+    }
+}
+
+/// 个人数据导出配置, 时间单位为秒
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// 保存压缩包的目录, 部署多个实例时需要使用共享目录
+    pub dir: String,
+    /// 下载有效期, 过期后删除压缩包
+    pub link_ttl: u64,
+    /// 进行中或在该时间内导出完成时返回已有的导出, 不重复导出
+    pub min_interval: u64,
+    /// 后台任务检查待处理导出的间隔
+    pub poll_interval: u64,
+    /// 超过该时间还没有完成的导出视为处理的实例已退出, 重新导出
+    pub job_timeout: u64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            dir: "data/exports".to_string(),
+            link_ttl: 3 * 24 * 60 * 60,
+            min_interval: 24 * 60 * 60,
+            poll_interval: 5,
+            job_timeout: 10 * 60,
+        }
+    }
+}
+
+/// 分布式ID配置
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct IdConfig {
+    /// 从etcd分配worker id失败时使用的固定worker id, 为空时启动失败.
+    /// 只能用于单实例部署, 多个实例使用相同的worker id会生成重复的id
+    pub fallback_worker_id: Option<u16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let config = Config::load("etc/user.yml");
+        assert!(config.validate().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.rate_limit.code_length = 20;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config.clone();
+        invalid.account.purge_interval = 0;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = config;
+        invalid.id.fallback_worker_id = Some(MAX_WORKER_ID + 1);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_debug_hides_client_secret() {
+        let provider: OidcProviderConfig = serde_yaml::from_str(
+            "name: google\nissuer: https://accounts.google.com\nclient_id: id\n\
+             client_secret: top-secret\nauthorization_endpoint: a\ntoken_endpoint: t\n\
+             jwks_uri: j\nredirect_uri: r",
+        )
+        .unwrap();
+        let debug = format!("{:?}", provider);
+        assert!(debug.contains("client_id: \"id\""));
+        assert!(!debug.contains("top-secret"));
+    }
+}
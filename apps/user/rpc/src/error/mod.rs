@@ -19,6 +19,20 @@ pub enum ErrorKind {
     // 未知错误
     Unknown,
     InvalidEmail,
+    // 无效手机号
+    InvalidPhone,
+    // 无效密码
+    InvalidPassword,
+    // 参数不合法
+    InvalidArgument,
+    // 无效或已失效的token
+    InvalidToken,
+    // 请求过于频繁或账号被临时锁定
+    TooManyRequests,
+    // 账号已被封禁
+    AccountBanned,
+    // 没有操作权限
+    PermissionDenied,
 }
 
 #[derive(Debug)]
@@ -26,6 +40,8 @@ pub struct Error {
     kind: ErrorKind,
     details: Option<String>,
     source: Option<Box<dyn StdError + Send + Sync>>,
+    // 建议客户端重试前等待的秒数
+    retry_after: Option<u64>,
 }
 
 impl Error {}
@@ -40,6 +56,7 @@ impl Error {
             kind,
             source: Some(Box::new(source)),
             details: Some(details.into()),
+            retry_after: None,
         }
     }
 
@@ -49,6 +66,7 @@ impl Error {
             kind,
             source: None,
             details: Some(details.into()),
+            retry_after: None,
         }
     }
 
@@ -57,6 +75,10 @@ impl Error {
         Self::with_details(ErrorKind::InternalError, details)
     }
 
+    pub fn user_not_found(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::UserNotFound, details)
+    }
+
     pub fn invalid_code(details: impl Into<String>) -> Self {
         Self::with_details(ErrorKind::InvalidCode, details)
     }
@@ -69,9 +91,40 @@ impl Error {
         Self::with_details(ErrorKind::InvalidEmail, details)
     }
 
+    pub fn invalid_phone(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::InvalidPhone, details)
+    }
+
+    pub fn invalid_argument(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::InvalidArgument, details)
+    }
+
+    pub fn invalid_token(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::InvalidToken, details)
+    }
+
+    pub fn invalid_password(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::InvalidPassword, details)
+    }
+
     pub fn invalid_account_or_password(details: impl Into<String>) -> Self {
         Self::with_details(ErrorKind::InvalidAccountOrPassword, details)
     }
+
+    pub fn account_banned(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::AccountBanned, details)
+    }
+
+    pub fn permission_denied(details: impl Into<String>) -> Self {
+        Self::with_details(ErrorKind::PermissionDenied, details)
+    }
+
+    pub fn too_many_requests(details: impl Into<String>, retry_after: u64) -> Self {
+        Self {
+            retry_after: Some(retry_after),
+            ..Self::with_details(ErrorKind::TooManyRequests, details)
+        }
+    }
 }
 
 impl From<sqlx::Error> for Error {
@@ -85,6 +138,12 @@ impl From<sqlx::Error> for Error {
     }
 }
 
+impl From<mongodb::error::Error> for Error {
+    fn from(value: mongodb::error::Error) -> Self {
+        Self::new(ErrorKind::DbError, value.to_string(), value)
+    }
+}
+
 impl From<redis::RedisError> for Error {
     fn from(value: redis::RedisError) -> Self {
         Self::new(ErrorKind::RedisError, value.to_string(), value)
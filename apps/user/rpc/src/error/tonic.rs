@@ -2,6 +2,9 @@ use crate::error::{Error, ErrorKind};
 use tonic::Status;
 use tracing::info;
 
+/// 限流时通过该metadata告知客户端需要等待的秒数
+pub const RETRY_AFTER_METADATA_KEY: &str = "retry-after";
+
 impl From<Error> for tonic::Status {
     fn from(value: Error) -> Self {
         let code = match value.kind {
@@ -11,16 +14,28 @@ impl From<Error> for tonic::Status {
             | ErrorKind::RedisError
             | ErrorKind::Unknown
             | ErrorKind::InternalError => tonic::Code::Internal,
-            ErrorKind::InvalidCode | ErrorKind::InvalidAccount | ErrorKind::InvalidEmail => {
-                tonic::Code::InvalidArgument
-            }
+            ErrorKind::InvalidCode
+            | ErrorKind::InvalidAccount
+            | ErrorKind::InvalidEmail
+            | ErrorKind::InvalidPhone
+            | ErrorKind::InvalidPassword
+            | ErrorKind::InvalidArgument => tonic::Code::InvalidArgument,
+            ErrorKind::TooManyRequests => tonic::Code::ResourceExhausted,
+            ErrorKind::InvalidToken => tonic::Code::Unauthenticated,
+            ErrorKind::AccountBanned | ErrorKind::PermissionDenied => tonic::Code::PermissionDenied,
         };
 
         let kind = format!("{:?}", value.kind);
         let details = value.details.unwrap_or_default();
         info!("details: {:?}", details);
 
-        Status::with_details(code, &kind, details.into())
+        let mut status = Status::with_details(code, &kind, details.into());
+        if let Some(retry_after) = value.retry_after {
+            status
+                .metadata_mut()
+                .insert(RETRY_AFTER_METADATA_KEY, retry_after.into());
+        }
+        status
     }
 }
 
@@ -28,6 +43,9 @@ impl From<tonic::Status> for Error {
     fn from(value: Status) -> Self {
         let kind = match value.message() {
             "NotFound" => ErrorKind::NotFound,
+            "TooManyRequests" => ErrorKind::TooManyRequests,
+            "AccountBanned" => ErrorKind::AccountBanned,
+            "PermissionDenied" => ErrorKind::PermissionDenied,
             "UnknownError" => ErrorKind::Unknown,
             _ => ErrorKind::InternalError,
         };
@@ -37,10 +55,17 @@ impl From<tonic::Status> for Error {
             Err(_) => None,
         };
 
+        let retry_after = value
+            .metadata()
+            .get(RETRY_AFTER_METADATA_KEY)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+
         Self {
             kind,
             details,
             source: None,
+            retry_after,
         }
     }
 }
@@ -0,0 +1,86 @@
+//! 用户资料和在线状态的变化通知, 用于WatchUsers
+
+pub(crate) mod redis;
+
+use crate::error::Error;
+use crate::service_context::ServiceContext;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// 发布变化通知的Redis频道, 在线状态的变化由RedisCache在脚本中直接发布
+pub const USER_EVENTS_CHANNEL: &str = "user_events";
+
+/// 本实例内等待分发的通知数量, 订阅者消费过慢时丢弃最早的通知
+pub const EVENT_BUFFER_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserChangeKind {
+    Profile,
+    Presence,
+}
+
+/// 变化通知只包含用户id, 订阅者按自己的权限重新读取最新的数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserChange {
+    pub kind: UserChangeKind,
+    pub user_id: String,
+}
+
+impl UserChange {
+    pub fn new(kind: UserChangeKind, user_id: impl Into<String>) -> Self {
+        Self {
+            kind,
+            user_id: user_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait UserEventBus: Send + Sync + Debug {
+    /// 发布通知, 所有实例的订阅者都会收到
+    async fn publish(&self, change: &UserChange) -> Result<(), Error>;
+
+    /// 订阅本实例收到的通知
+    fn subscribe(&self) -> broadcast::Receiver<UserChange>;
+}
+
+/// 只在本实例内分发的实现, 用于测试
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct LocalEventBus {
+    sender: broadcast::Sender<UserChange>,
+}
+
+#[cfg(test)]
+impl LocalEventBus {
+    /// 与其他组件共用`sender`, 例如MemoryCache发布在线状态的变化
+    pub fn new(sender: broadcast::Sender<UserChange>) -> Self {
+        Self { sender }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl UserEventBus for LocalEventBus {
+    async fn publish(&self, change: &UserChange) -> Result<(), Error> {
+        // 没有订阅者时发送失败, 可以忽略
+        let _ = self.sender.send(change.clone());
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<UserChange> {
+        self.sender.subscribe()
+    }
+}
+
+/// 发布通知, 失败时只记录日志, 不影响已经完成的修改
+pub async fn notify(svc: &ServiceContext, kind: UserChangeKind, user_id: &str) {
+    let change = UserChange::new(kind, user_id);
+    if let Err(e) = svc.user_events.publish(&change).await {
+        warn!("publish user change {:?} failed: {:?}", change, e);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::error::Error;
+use crate::event::{UserChange, UserEventBus, EVENT_BUFFER_SIZE, USER_EVENTS_CHANNEL};
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tracing::{info, warn};
+
+/// 断开后重新订阅的间隔
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 基于Redis发布订阅的实现, 每个实例只保持一个订阅连接, 收到的通知在实例内分发
+#[derive(Debug, Clone)]
+pub struct RedisEventBus {
+    client: redis::Client,
+    sender: broadcast::Sender<UserChange>,
+}
+
+impl RedisEventBus {
+    /// 启动后台订阅任务, 需要在tokio运行时中调用
+    pub fn new(client: redis::Client) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        tokio::spawn(subscribe_loop(client.clone(), sender.clone()));
+        Self { client, sender }
+    }
+}
+
+async fn subscribe_loop(client: redis::Client, sender: broadcast::Sender<UserChange>) {
+    loop {
+        if let Err(e) = forward(&client, &sender).await {
+            warn!("subscribe {} failed: {:?}", USER_EVENTS_CHANNEL, e);
+        }
+        tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+    }
+}
+
+/// 把频道中的通知转发到本实例的订阅者, 连接断开时返回
+async fn forward(
+    client: &redis::Client,
+    sender: &broadcast::Sender<UserChange>,
+) -> Result<(), Error> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(USER_EVENTS_CHANNEL).await?;
+    info!("subscribed to {}", USER_EVENTS_CHANNEL);
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload: String = message.get_payload()?;
+        match serde_json::from_str::<UserChange>(&payload) {
+            // 没有订阅者时发送失败, 可以忽略
+            Ok(change) => {
+                let _ = sender.send(change);
+            }
+            Err(e) => warn!("invalid user change {}: {}", payload, e),
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl UserEventBus for RedisEventBus {
+    async fn publish(&self, change: &UserChange) -> Result<(), Error> {
+        let payload = serde_json::to_string(change)
+            .map_err(|e| Error::internal_with_details(e.to_string()))?;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.publish::<_, _, ()>(USER_EVENTS_CHANNEL, payload)
+            .await?;
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<UserChange> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,309 @@
+//! 个人数据导出: 收集用户的数据写入ZIP压缩包, 由user-api凭下载凭证提供下载
+
+use crate::config::ExportConfig;
+use crate::error::Error;
+use crate::pb::user::{self, DataExportStatus, PresenceStatus};
+use crate::repo::DataExport;
+use crate::service_context::ServiceContext;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::fmt::Debug;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 分页读取安全事件和黑名单时每页的数量
+const PAGE_SIZE: i64 = 100;
+
+/// 其他业务的数据(消息, 联系人等), 由对应的服务接入时实现, 导出时写入`{name}.json`
+#[async_trait]
+pub trait ExportSource: Sync + Send + Debug {
+    /// 压缩包中的文件名, 不含扩展名
+    fn name(&self) -> &str;
+    async fn export(&self, user_id: &str) -> Result<Value, Error>;
+}
+
+/// 压缩包中的一个JSON文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportFile {
+    pub name: String,
+    pub content: Value,
+}
+
+impl ExportFile {
+    fn new(name: &str, content: Value) -> Self {
+        Self {
+            name: format!("{}.json", name),
+            content,
+        }
+    }
+}
+
+/// 导出的压缩包路径
+pub fn archive_path(config: &ExportConfig, id: &str) -> PathBuf {
+    Path::new(&config.dir).join(format!("{}.zip", id))
+}
+
+/// 下载凭证: 导出id和下载有效期的HMAC, 不需要保存, 有效期变化后旧凭证失效
+pub fn download_token(secret: &str, id: &str, expire_time: i64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(format!("{}.{}", id, expire_time).as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 校验下载凭证, 比较全部字节, 避免提前返回泄露时间差
+pub fn verify_download_token(secret: &str, id: &str, expire_time: i64, token: &str) -> bool {
+    let expected = download_token(secret, id, expire_time);
+    expected.len() == token.len()
+        && expected
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// 下载有效期内可以下载
+pub fn is_downloadable(export: &DataExport, now: i64) -> bool {
+    export.status == DataExportStatus::Ready && export.expire_time > now
+}
+
+/// 转换为返回给用户的导出状态, 可以下载时附带下载凭证
+pub fn to_message(svc: &ServiceContext, export: DataExport) -> user::DataExport {
+    let download_token = if is_downloadable(&export, chrono::Utc::now().timestamp_millis()) {
+        download_token(&svc.config.jwt.secret, &export.id, export.expire_time)
+    } else {
+        String::new()
+    };
+    user::DataExport {
+        status: export.status as i32,
+        file_size: export.file_size,
+        create_time: export.create_time,
+        expire_time: export.expire_time,
+        download_token,
+        error: export.error,
+        id: export.id,
+    }
+}
+
+/// 生成导出的压缩包, 返回文件大小
+pub async fn run(svc: &ServiceContext, export: &DataExport) -> Result<i64, Error> {
+    let files = collect(svc, export).await?;
+    let path = archive_path(&svc.config.export, &export.id);
+    tokio::task::spawn_blocking(move || write_archive(&path, &files))
+        .await
+        .map_err(|e| Error::internal_with_details(format!("export task failed, {}", e)))?
+}
+
+/// 收集用户在本服务和接入的其他业务中的数据
+pub async fn collect(svc: &ServiceContext, export: &DataExport) -> Result<Vec<ExportFile>, Error> {
+    let user_id = export.user_id.as_str();
+    let Some(user) = svc.user_repo.find_by_id(user_id).await? else {
+        return Err(Error::user_not_found("user not found"));
+    };
+
+    let mut files = vec![
+        ExportFile::new(
+            "manifest",
+            json!({
+                "export_id": export.id,
+                "user_id": user_id,
+                "request_time": export.create_time,
+                "generate_time": chrono::Utc::now().timestamp_millis(),
+            }),
+        ),
+        // 密码不会被序列化
+        ExportFile::new("profile", json!(user)),
+        ExportFile::new("privacy_settings", privacy_settings(svc, user_id).await?),
+        ExportFile::new("sessions", sessions(svc, user_id).await?),
+        ExportFile::new("security_events", security_events(svc, user_id).await?),
+        ExportFile::new(
+            "external_identities",
+            external_identities(svc, user_id).await?,
+        ),
+        ExportFile::new("two_factor", two_factor(svc, user_id).await?),
+        ExportFile::new("blocked_users", blocked_users(svc, user_id).await?),
+    ];
+    for source in &svc.export_sources {
+        files.push(ExportFile::new(
+            source.name(),
+            source.export(user_id).await?,
+        ));
+    }
+    Ok(files)
+}
+
+async fn privacy_settings(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let privacy = svc.privacy_repo.find(user_id).await?;
+    Ok(json!({
+        "searchable_by_phone": privacy.searchable_by_phone,
+        "searchable_by_email": privacy.searchable_by_email,
+        "searchable_by_account": privacy.searchable_by_account,
+        "profile_visibility": privacy.profile_visibility.as_str_name(),
+        "friend_request": privacy.friend_request.as_str_name(),
+    }))
+}
+
+/// 在线的设备平台和会话的注销时间, token本身不保存
+async fn sessions(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let presence = svc
+        .cache
+        .get_users_presence(&[user_id.to_string()])
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let revoked_at = svc.cache.get_user_sessions_revoked_at(user_id).await?;
+    Ok(json!({
+        "status": PresenceStatus::try_from(presence.status)
+            .unwrap_or_default()
+            .as_str_name(),
+        "last_seen": presence.last_seen,
+        "online_platforms": presence.platforms,
+        "sessions_revoked_at": revoked_at,
+    }))
+}
+
+async fn security_events(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let mut events = vec![];
+    let mut before_id = None;
+    loop {
+        let page = svc
+            .security_event_repo
+            .list(user_id, before_id, PAGE_SIZE)
+            .await?;
+        before_id = page.last().map(|event| event.id);
+        let done = (page.len() as i64) < PAGE_SIZE;
+        events.extend(page.into_iter().map(|event| {
+            json!({
+                "event_type": event.event_type.as_str_name(),
+                "account": event.account,
+                "ip": event.ip,
+                "device": event.device,
+                "reason": event.reason,
+                "create_time": event.create_time,
+            })
+        }));
+        if done {
+            return Ok(Value::Array(events));
+        }
+    }
+}
+
+async fn external_identities(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let identities = svc.identity_repo.find_by_user(user_id).await?;
+    Ok(identities
+        .into_iter()
+        .map(|identity| {
+            json!({
+                "provider": identity.provider,
+                "subject": identity.subject,
+                "email": identity.email,
+                "create_time": identity.create_time,
+            })
+        })
+        .collect())
+}
+
+/// 只导出是否开启, 密钥和恢复码不导出
+async fn two_factor(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let totp = svc.totp_repo.find(user_id).await?;
+    Ok(json!({ "enabled": totp.is_some_and(|totp| totp.enabled) }))
+}
+
+async fn blocked_users(svc: &ServiceContext, user_id: &str) -> Result<Value, Error> {
+    let mut blocks = vec![];
+    let mut before_id = None;
+    loop {
+        let page = svc.block_repo.list(user_id, before_id, PAGE_SIZE).await?;
+        before_id = page.last().map(|block| block.id);
+        let done = (page.len() as i64) < PAGE_SIZE;
+        blocks.extend(page.into_iter().map(|block| {
+            json!({
+                "user_id": block.blocked_user_id,
+                "create_time": block.create_time,
+            })
+        }));
+        if done {
+            return Ok(Value::Array(blocks));
+        }
+    }
+}
+
+/// 写入压缩包, 先写临时文件再重命名, 下载时不会读到写了一半的文件. 返回文件大小
+pub fn write_archive(path: &Path, files: &[ExportFile]) -> Result<i64, Error> {
+    let internal = |e: &dyn std::fmt::Display| {
+        Error::internal_with_details(format!("write archive {:?} failed, {}", path, e))
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| internal(&e))?;
+    }
+    let tmp_path = path.with_extension("zip.tmp");
+    let file = std::fs::File::create(&tmp_path).map_err(|e| internal(&e))?;
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(file);
+    for file in files {
+        writer
+            .start_file(file.name.as_str(), options)
+            .map_err(|e| internal(&e))?;
+        let content = serde_json::to_vec_pretty(&file.content).map_err(|e| internal(&e))?;
+        writer.write_all(&content).map_err(|e| internal(&e))?;
+    }
+    let file = writer.finish().map_err(|e| internal(&e))?;
+    file.sync_all().map_err(|e| internal(&e))?;
+    let size = file.metadata().map_err(|e| internal(&e))?.len();
+    std::fs::rename(&tmp_path, path).map_err(|e| internal(&e))?;
+    Ok(size as i64)
+}
+
+/// 删除压缩包, 文件不存在时忽略
+pub async fn remove_archive(config: &ExportConfig, id: &str) -> std::io::Result<()> {
+    match tokio::fs::remove_file(archive_path(config, id)).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_download_token() {
+        let token = download_token("secret", "id", 1000);
+        assert_eq!(token.len(), 64);
+        assert!(verify_download_token("secret", "id", 1000, &token));
+        assert!(!verify_download_token("secret", "id", 2000, &token));
+        assert!(!verify_download_token("other", "id", 1000, &token));
+        assert!(!verify_download_token("secret", "id", 1000, &token[1..]));
+    }
+
+    #[test]
+    fn test_write_archive() {
+        let dir = std::env::temp_dir().join(nanoid::nanoid!());
+        let path = dir.join("export.zip");
+        let files = vec![ExportFile::new("profile", json!({ "name": "lucas" }))];
+        let size = write_archive(&path, &files).unwrap();
+        assert_eq!(size as u64, std::fs::metadata(&path).unwrap().len());
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("profile.json")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["name"], "lucas");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+//! 好友关系由好友服务维护, 用户服务只通过`FriendChecker`查询
+
+use crate::error::Error;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// 查询好友关系, 决定能否看到好友可见的资料
+#[async_trait]
+pub trait FriendChecker: Sync + Send + Debug {
+    /// 返回`candidates`中与`user_id`互为好友的用户id
+    async fn filter_friends(
+        &self,
+        user_id: &str,
+        candidates: &[String],
+    ) -> Result<HashSet<String>, Error>;
+}
+
+/// 未接入好友服务时使用, 所有人都按陌生人处理
+#[derive(Debug, Default)]
+pub struct NoFriendChecker;
+
+#[async_trait]
+impl FriendChecker for NoFriendChecker {
+    async fn filter_friends(
+        &self,
+        _user_id: &str,
+        _candidates: &[String],
+    ) -> Result<HashSet<String>, Error> {
+        Ok(HashSet::new())
+    }
+}
@@ -0,0 +1,61 @@
+//! 依赖服务的可用性检查, 结果通过grpc.health.v1协议对外提供
+
+use crate::error::Error;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::fmt::Debug;
+
+#[async_trait]
+pub trait HealthCheck: Send + Sync + Debug {
+    /// 依赖的名称, 用于日志
+    fn name(&self) -> &'static str;
+
+    async fn check(&self) -> Result<(), Error>;
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgresHealthCheck {
+    pool: PgPool,
+}
+
+impl PostgresHealthCheck {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for PostgresHealthCheck {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn check(&self) -> Result<(), Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisHealthCheck {
+    client: redis::Client,
+}
+
+impl RedisHealthCheck {
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for RedisHealthCheck {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn check(&self) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::cmd("PING").query_async::<String>(&mut conn).await?;
+        Ok(())
+    }
+}
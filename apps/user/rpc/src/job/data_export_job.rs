@@ -0,0 +1,84 @@
+use crate::export;
+use crate::service_context::ServiceContext;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// 每批标记过期的导出数量
+const EXPIRE_BATCH_SIZE: i64 = 100;
+
+/// 定时生成待处理的个人数据导出, 并删除超过下载有效期的压缩包
+pub async fn data_export_job(svc: Arc<ServiceContext>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(svc.config.export.poll_interval));
+
+    loop {
+        interval.tick().await;
+        process_exports(&svc).await;
+        expire_exports(&svc).await;
+    }
+}
+
+/// 逐个领取并生成待处理的导出, 直到没有待处理的导出
+pub(crate) async fn process_exports(svc: &ServiceContext) {
+    let config = &svc.config.export;
+    loop {
+        // 每次只领取一个, 避免领取后排队等待的导出被其他实例当作超时重新领取
+        let stale_before = chrono::Utc::now().timestamp_millis() - config.job_timeout as i64 * 1000;
+        let export = match svc.data_export_repo.claim(stale_before, 1).await {
+            Ok(exports) => match exports.into_iter().next() {
+                Some(export) => export,
+                None => return,
+            },
+            Err(e) => {
+                error!("claim data exports failed: {:?}", e);
+                return;
+            }
+        };
+
+        let result = match export::run(svc, &export).await {
+            Ok(file_size) => {
+                info!(
+                    "data export {} of user {} is ready",
+                    export.id, export.user_id
+                );
+                let expire_time =
+                    chrono::Utc::now().timestamp_millis() + config.link_ttl as i64 * 1000;
+                svc.data_export_repo
+                    .mark_ready(&export.id, file_size, expire_time)
+                    .await
+            }
+            Err(e) => {
+                // 失败原因可能包含内部信息, 只记录在日志中
+                error!("data export {} failed: {:?}", export.id, e);
+                svc.data_export_repo
+                    .mark_failed(&export.id, "export failed, please try again later")
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            error!("update data export {} failed: {:?}", export.id, e);
+        }
+    }
+}
+
+/// 标记超过下载有效期的导出并删除压缩包
+pub(crate) async fn expire_exports(svc: &ServiceContext) {
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        let ids = match svc.data_export_repo.expire(now, EXPIRE_BATCH_SIZE).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                error!("expire data exports failed: {:?}", e);
+                return;
+            }
+        };
+        for id in &ids {
+            if let Err(e) = export::remove_archive(&svc.config.export, id).await {
+                error!("remove expired data export {} failed: {:?}", id, e);
+            }
+        }
+        if (ids.len() as i64) < EXPIRE_BATCH_SIZE {
+            return;
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::health::HealthCheck;
+use crate::pb::user::admin_user_service_server::AdminUserServiceServer;
+use crate::pb::user::user_service_server::UserServiceServer;
+use crate::server::admin::AdminUserRpcServer;
+use crate::server::UserRpcServer;
+use crate::service_context::ServiceContext;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+use tracing::{info, warn};
+
+/// 定时检查依赖服务, 全部可用时服务状态为SERVING, 否则为NOT_SERVING
+pub async fn health_check_job(svc: Arc<ServiceContext>, reporter: HealthReporter) {
+    let config = svc.config.server.clone();
+    let timeout = Duration::from_millis(config.health_check_timeout_ms);
+    let mut interval = tokio::time::interval(Duration::from_secs(config.health_check_interval));
+    let mut last = None;
+
+    loop {
+        interval.tick().await;
+
+        let mut healthy = true;
+        for check in &svc.health_checks {
+            if let Err(e) = run_check(check.as_ref(), timeout).await {
+                warn!("health check {} failed: {}", check.name(), e);
+                healthy = false;
+            }
+        }
+        let status = if healthy {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        if last != Some(status) {
+            info!("serving status changed to {:?}", status);
+            last = Some(status);
+        }
+
+        // 空字符串表示整个服务器的状态
+        reporter.set_service_status("", status).await;
+        reporter
+            .set_service_status(
+                <UserServiceServer<UserRpcServer> as tonic::server::NamedService>::NAME,
+                status,
+            )
+            .await;
+        reporter
+            .set_service_status(
+                <AdminUserServiceServer<AdminUserRpcServer> as tonic::server::NamedService>::NAME,
+                status,
+            )
+            .await;
+    }
+}
+
+async fn run_check(check: &dyn HealthCheck, timeout: Duration) -> Result<(), String> {
+    match tokio::time::timeout(timeout, check.check()).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(format!("{:?}", e)),
+        Err(_) => Err("timeout".to_string()),
+    }
+}
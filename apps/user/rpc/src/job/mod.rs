@@ -0,0 +1,4 @@
+pub(crate) mod data_export_job;
+pub(crate) mod health_check_job;
+pub(crate) mod presence_expire_job;
+pub(crate) mod purge_account_job;
@@ -0,0 +1,29 @@
+use crate::service_context::ServiceContext;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+
+/// 每批移除的最大用户数
+const EXPIRE_BATCH_SIZE: usize = 1000;
+
+/// 定时移除心跳已过期的在线用户, 发布离线通知供WatchUsers推送
+pub async fn presence_expire_job(svc: Arc<ServiceContext>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(svc.config.presence.heartbeat_interval));
+
+    loop {
+        interval.tick().await;
+
+        // 多个实例同时执行时由脚本保证每个用户只移除一次
+        loop {
+            match svc.cache.expire_presence(EXPIRE_BATCH_SIZE).await {
+                Ok(expired) if expired.len() < EXPIRE_BATCH_SIZE => break,
+                Ok(_) => {}
+                Err(e) => {
+                    error!("expire presence failed: {:?}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
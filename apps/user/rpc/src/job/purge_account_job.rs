@@ -0,0 +1,80 @@
+use crate::export;
+use crate::service_context::ServiceContext;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// 定时清理超过宽限期的已注销账号
+pub async fn purge_account_job(svc: Arc<ServiceContext>) {
+    let config = svc.config.account.clone();
+    let mut interval = tokio::time::interval(Duration::from_secs(config.purge_interval));
+
+    loop {
+        interval.tick().await;
+
+        let grace_period = config.deactivation_grace_period as i64 * 1000;
+        let before = chrono::Utc::now().timestamp_millis() - grace_period;
+        // 分批清理, 直到没有需要清理的账号
+        loop {
+            match svc
+                .user_repo
+                .purge_deleted(before, config.purge_batch_size, config.purge_mode)
+                .await
+            {
+                Ok(ids) => {
+                    if !ids.is_empty() {
+                        info!("purged {} deactivated accounts: {:?}", ids.len(), ids);
+                    }
+                    // 两步验证密钥, 第三方身份绑定, 安全事件, 隐私设置, 黑名单和导出的数据属于敏感信息, 一并清除
+                    for id in &ids {
+                        if let Err(e) = svc.totp_repo.delete(id).await {
+                            error!("delete totp of purged account {} failed: {:?}", id, e);
+                        }
+                        if let Err(e) = svc.identity_repo.delete_by_user(id).await {
+                            error!("delete identities of purged account {} failed: {:?}", id, e);
+                        }
+                        if let Err(e) = svc.privacy_repo.delete(id).await {
+                            error!("delete privacy of purged account {} failed: {:?}", id, e);
+                        }
+                        if let Err(e) = svc.block_repo.delete_by_user(id).await {
+                            error!("delete blocks of purged account {} failed: {:?}", id, e);
+                        }
+                        match svc.data_export_repo.delete_by_user(id).await {
+                            Ok(export_ids) => {
+                                for export_id in &export_ids {
+                                    if let Err(e) =
+                                        export::remove_archive(&svc.config.export, export_id).await
+                                    {
+                                        error!(
+                                            "remove data export {} of purged account {} failed: {:?}",
+                                            export_id, id, e
+                                        );
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                error!(
+                                    "delete data exports of purged account {} failed: {:?}",
+                                    id, e
+                                )
+                            }
+                        }
+                        if let Err(e) = svc.security_event_repo.delete_by_user(id).await {
+                            error!(
+                                "delete security events of purged account {} failed: {:?}",
+                                id, e
+                            );
+                        }
+                    }
+                    if (ids.len() as i64) < config.purge_batch_size {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("purge deactivated accounts failed: {:?}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod config;
+pub mod migration;
 pub mod pb;
 mod server;
 pub use server::UserRpcServer;
@@ -7,5 +8,13 @@ pub(crate) mod logic;
 pub(crate) mod service_context;
 
 pub(crate) mod error;
+pub(crate) mod event;
+pub(crate) mod export;
+pub(crate) mod friend;
+pub(crate) mod health;
+pub(crate) mod job;
+pub(crate) mod mail;
+pub(crate) mod oidc;
 pub(crate) mod repo;
+pub(crate) mod sms;
 pub(crate) mod utils;
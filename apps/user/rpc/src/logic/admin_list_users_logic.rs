@@ -0,0 +1,52 @@
+use crate::logic::search_users_logic::{decode_cursor, encode_cursor};
+use crate::pb::user::{AdminListUsersRequest, AdminListUsersResponse, UserRole};
+use crate::repo::{SearchCursor, UserFilter};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub async fn admin_list_users_logic(
+    svc: &ServiceContext,
+    request: Request<AdminListUsersRequest>,
+) -> Result<Response<AdminListUsersResponse>, Status> {
+    let operator = jwt::authorize(svc, &request, &[UserRole::Admin, UserRole::Support]).await?;
+    let req = request.into_inner();
+    info!(
+        "admin list users, operator: {}, request: {:?}",
+        operator.id, req
+    );
+
+    let limit = match req.limit {
+        n if n <= 0 => DEFAULT_LIMIT,
+        n => (n as i64).min(MAX_LIMIT),
+    };
+    let cursor = if req.cursor.is_empty() {
+        None
+    } else {
+        Some(decode_cursor(&req.cursor)?)
+    };
+    let filter = UserFilter {
+        keyword: Some(req.keyword.trim().to_string()).filter(|keyword| !keyword.is_empty()),
+        role: req.role.map(|_| req.role()),
+        banned: req.banned,
+    };
+
+    let mut users = svc.user_repo.list(&filter, cursor, limit).await?;
+
+    let next_cursor = match users.last() {
+        Some(last) if users.len() as i64 == limit => encode_cursor(&SearchCursor {
+            create_time: last.create_time,
+            id: last.id.clone(),
+        }),
+        _ => String::new(),
+    };
+    for user in users.iter_mut() {
+        user.password.clear();
+    }
+
+    Ok(Response::new(AdminListUsersResponse { users, next_cursor }))
+}
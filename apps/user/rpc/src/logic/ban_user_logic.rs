@@ -0,0 +1,45 @@
+use crate::error::Error;
+use crate::pb::user::{BanUserRequest, BanUserResponse, SecurityEventType, UserRole};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn ban_user_logic(
+    svc: &ServiceContext,
+    request: Request<BanUserRequest>,
+) -> Result<Response<BanUserResponse>, Status> {
+    let operator = jwt::authorize(svc, &request, &[UserRole::Admin]).await?;
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!(
+        "ban user, operator: {}, user_id: {}, reason: {}, until: {}",
+        operator.id, req.user_id, req.reason, req.until
+    );
+
+    if req.user_id == operator.id {
+        return Err(Error::invalid_argument("can not ban yourself").into());
+    }
+    if req.until != 0 && req.until <= chrono::Utc::now().timestamp_millis() {
+        return Err(Error::invalid_argument("until is in the past").into());
+    }
+    let Some(user) = svc.user_repo.find_by_id(&req.user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+
+    svc.user_repo.ban(&user.id, &req.reason, req.until).await?;
+    // 封禁后已签发的token全部失效, 设备全部下线
+    svc.cache.revoke_user_sessions(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::AccountBanned,
+        Some(&user.id),
+        &user.account,
+        &req.reason,
+    )
+    .await;
+
+    Ok(Response::new(BanUserResponse {}))
+}
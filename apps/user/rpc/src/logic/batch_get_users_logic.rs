@@ -0,0 +1,51 @@
+use crate::error::Error;
+use crate::pb::user::{BatchGetUsersRequest, BatchGetUsersResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::view;
+use std::collections::{HashMap, HashSet};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const MAX_BATCH_SIZE: usize = 100;
+
+pub async fn batch_get_users_logic(
+    svc: &ServiceContext,
+    request: Request<BatchGetUsersRequest>,
+) -> Result<Response<BatchGetUsersResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let viewer = view::viewer(svc, &request).await?;
+    let req = request.into_inner();
+
+    if req.user_ids.len() > MAX_BATCH_SIZE {
+        return Err(Error::invalid_argument(format!(
+            "at most {} user ids per request",
+            MAX_BATCH_SIZE
+        ))
+        .into());
+    }
+    let requested = req.view();
+    let paths = req.read_mask.map(|mask| mask.paths).unwrap_or_default();
+    let mask = view::parse_read_mask(&paths)?;
+
+    // 去重并保持请求中的顺序
+    let mut seen = HashSet::new();
+    let ids: Vec<String> = req
+        .user_ids
+        .into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect();
+    if ids.is_empty() {
+        return Ok(Response::new(BatchGetUsersResponse::default()));
+    }
+
+    let mut users = svc.user_repo.find_by_ids(ids.clone()).await?;
+    let order: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (id.as_str(), index))
+        .collect();
+    users.sort_by_key(|user| order.get(user.id.as_str()).copied());
+
+    let users = view::filter_users(svc, viewer.as_ref(), users, requested, mask.as_ref()).await?;
+    Ok(Response::new(BatchGetUsersResponse { users }))
+}
@@ -0,0 +1,44 @@
+use crate::error::Error;
+use crate::pb::user::{BlockUserRequest, BlockUserResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn block_user_logic(
+    svc: &ServiceContext,
+    request: Request<BlockUserRequest>,
+) -> Result<Response<BlockUserResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    if req.blocked_user_id.is_empty() {
+        return Err(Error::invalid_argument("blocked_user_id is empty").into());
+    }
+    if user_id == req.blocked_user_id {
+        return Err(Error::invalid_argument("can not block yourself").into());
+    }
+    if svc
+        .user_repo
+        .find_by_id(&req.blocked_user_id)
+        .await?
+        .is_none()
+    {
+        return Err(Error::user_not_found("user not found").into());
+    }
+
+    // 拉黑关系检查时会读取全部记录, 需要限制数量
+    let blocked = svc.block_repo.find_blocked_ids(&user_id).await?;
+    if blocked.contains(&req.blocked_user_id) {
+        return Ok(Response::new(BlockUserResponse {}));
+    }
+    if blocked.len() as i64 >= svc.config.blocklist.max_size {
+        return Err(Error::permission_denied("blocklist is full").into());
+    }
+
+    svc.block_repo
+        .insert(&user_id, &req.blocked_user_id)
+        .await?;
+    Ok(Response::new(BlockUserResponse {}))
+}
@@ -0,0 +1,39 @@
+use crate::error::Error;
+use crate::pb::user::{
+    CanSendFriendRequestRequest, CanSendFriendRequestResponse, FriendRequestPolicy,
+};
+use crate::service_context::ServiceContext;
+use crate::utils::{block, jwt};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn can_send_friend_request_logic(
+    svc: &ServiceContext,
+    request: Request<CanSendFriendRequestRequest>,
+) -> Result<Response<CanSendFriendRequestResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    // 发起方必须是调用方本人, 管理员可以代为检查
+    let from_user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().from_user_id).await?;
+    let req = request.into_inner();
+
+    if from_user_id == req.to_user_id {
+        return Err(Error::invalid_argument("can not send friend request to yourself").into());
+    }
+    if svc.user_repo.find_by_id(&req.to_user_id).await?.is_none() {
+        return Err(Error::user_not_found("user not found").into());
+    }
+
+    // 任意一方拉黑了对方时不能发送
+    if block::relation(svc, &from_user_id, &req.to_user_id)
+        .await?
+        .any()
+    {
+        return Ok(Response::new(CanSendFriendRequestResponse {
+            allowed: false,
+        }));
+    }
+
+    let privacy = svc.privacy_repo.find(&req.to_user_id).await?;
+    let allowed = privacy.friend_request == FriendRequestPolicy::Everyone;
+    Ok(Response::new(CanSendFriendRequestResponse { allowed }))
+}
@@ -0,0 +1,80 @@
+use crate::error::Error;
+use crate::pb::user::{ChangePasswordRequest, ChangePasswordResponse, SecurityEventType};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::jwt::{self, gen_token};
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn change_password_logic(
+    svc: &ServiceContext,
+    request: Request<ChangePasswordRequest>,
+) -> Result<Response<ChangePasswordResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("change password, user_id: {}", user_id);
+
+    if req.new_password.is_empty() {
+        return Err(Status::from(Error::invalid_password("password is empty")));
+    }
+    if req.new_password == req.old_password {
+        return Err(Status::from(Error::invalid_password(
+            "new password is the same as the old one",
+        )));
+    }
+
+    // 旧密码错误次数过多时锁定
+    let lockout_key = format!("change_password:{}", user_id);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+    // 手机号或第三方登录注册的用户没有密码, 需要通过找回密码设置
+    if user.password.is_empty() {
+        return Err(Status::from(Error::invalid_password("password not set")));
+    }
+
+    if !utils::verify_password(req.old_password.as_bytes(), &user.password)? {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_password(
+            "old password mismatch",
+        )));
+    }
+    limiter::clear_failures(svc, &lockout_key).await?;
+
+    let encoded_password = utils::hash_password(&svc.config.password, req.new_password.as_bytes())?;
+    svc.user_repo
+        .update_password(&user.id, &encoded_password)
+        .await?;
+
+    // 注销其他会话, 并为当前会话签发新的token
+    svc.cache.revoke_user_sessions(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::PasswordChanged,
+        Some(&user.id),
+        &user.account,
+        "",
+    )
+    .await;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::SessionsRevoked,
+        Some(&user.id),
+        &user.account,
+        "password changed",
+    )
+    .await;
+    let token = gen_token(&user, &svc.config.jwt.secret)?;
+
+    Ok(Response::new(ChangePasswordResponse {
+        token: token.token,
+        refresh_token: token.refresh_token,
+    }))
+}
@@ -0,0 +1,49 @@
+use crate::error::Error;
+use crate::pb::user::{ConfirmTotpRequest, ConfirmTotpResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::{jwt, limiter, totp};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn confirm_totp_logic(
+    svc: &ServiceContext,
+    request: Request<ConfirmTotpRequest>,
+) -> Result<Response<ConfirmTotpResponse>, Status> {
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("confirm totp, user_id: {}", user_id);
+
+    let lockout_key = format!("second_factor:{}", user_id);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let totp = match svc.totp_repo.find(&user_id).await? {
+        Some(totp) if !totp.enabled => totp,
+        Some(_) => {
+            return Err(Status::from(Error::invalid_argument(
+                "two-factor authentication already enabled",
+            )))
+        }
+        None => {
+            return Err(Status::from(Error::invalid_argument(
+                "two-factor authentication not enrolled",
+            )))
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let Some(step) = totp::verify(&totp.secret, &req.code, now, svc.config.two_factor.skew) else {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_code("code mismatch")));
+    };
+    limiter::clear_failures(svc, &lockout_key).await?;
+
+    // 只保存恢复码的哈希, 明文只返回这一次
+    let recovery_codes = totp::generate_recovery_codes(svc.config.two_factor.recovery_code_count);
+    let hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| totp::hash_recovery_code(code))
+        .collect();
+    svc.totp_repo.enable(&user_id, &hashes, step).await?;
+
+    Ok(Response::new(ConfirmTotpResponse { recovery_codes }))
+}
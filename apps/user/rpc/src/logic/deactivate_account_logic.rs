@@ -0,0 +1,51 @@
+use crate::error::Error;
+use crate::pb::user::{DeactivateAccountRequest, DeactivateAccountResponse, SecurityEventType};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::{audit, ClientInfo};
+use crate::utils::{jwt, limiter};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn deactivate_account_logic(
+    svc: &ServiceContext,
+    request: Request<DeactivateAccountRequest>,
+) -> Result<Response<DeactivateAccountResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("deactivate account, user_id: {}", user_id);
+
+    // 密码错误次数过多时锁定
+    let lockout_key = format!("deactivate:{}", user_id);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+    if user.password.is_empty() {
+        return Err(Status::from(Error::invalid_password("password not set")));
+    }
+
+    if !utils::verify_password(req.password.as_bytes(), &user.password)? {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_password("password mismatch")));
+    }
+    limiter::clear_failures(svc, &lockout_key).await?;
+
+    svc.user_repo.delete(&user.id).await?;
+    svc.cache.revoke_user_sessions(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::AccountDeactivated,
+        Some(&user.id),
+        &user.account,
+        "",
+    )
+    .await;
+
+    let grace_period = svc.config.account.deactivation_grace_period as i64 * 1000;
+    let purge_time = chrono::Utc::now().timestamp_millis() + grace_period;
+    Ok(Response::new(DeactivateAccountResponse { purge_time }))
+}
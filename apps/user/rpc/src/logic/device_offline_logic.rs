@@ -0,0 +1,22 @@
+use crate::logic::heartbeat_logic::validate_device;
+use crate::pb::user::{DeviceOfflineRequest, DeviceOfflineResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn device_offline_logic(
+    svc: &ServiceContext,
+    request: Request<DeviceOfflineRequest>,
+) -> Result<Response<DeviceOfflineResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    validate_device(svc, &user_id, &req.platform, &req.device_id)?;
+    svc.cache
+        .device_offline(&user_id, &req.platform, &req.device_id)
+        .await?;
+
+    Ok(Response::new(DeviceOfflineResponse {}))
+}
@@ -0,0 +1,56 @@
+use crate::error::Error;
+use crate::logic::verify_second_factor_logic::verify_second_factor_code;
+use crate::pb::user::{DisableTotpRequest, DisableTotpResponse};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::{jwt, limiter, totp};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn disable_totp_logic(
+    svc: &ServiceContext,
+    request: Request<DisableTotpRequest>,
+) -> Result<Response<DisableTotpResponse>, Status> {
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("disable totp, user_id: {}", user_id);
+
+    let Some(totp) = svc.totp_repo.find(&user_id).await? else {
+        return Err(Status::from(Error::invalid_argument(
+            "two-factor authentication not enabled",
+        )));
+    };
+    // 未确认的绑定可以直接删除
+    if totp.enabled {
+        // 使用验证码时还需要校验密码, 只有登录态不能关闭; 恢复码可以单独使用, 供丢失验证器时关闭
+        let now = chrono::Utc::now().timestamp();
+        if totp::verify(&totp.secret, &req.code, now, svc.config.two_factor.skew).is_some() {
+            verify_password(svc, &user_id, &req.password).await?;
+        }
+        verify_second_factor_code(svc, &totp, &req.code).await?;
+    }
+    svc.totp_repo.delete(&user_id).await?;
+
+    Ok(Response::new(DisableTotpResponse {}))
+}
+
+/// 校验密码, 错误次数过多时锁定
+async fn verify_password(svc: &ServiceContext, user_id: &str, password: &str) -> Result<(), Error> {
+    let lockout_key = format!("disable_totp:{}", user_id);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let Some(user) = svc.user_repo.find_by_id(user_id).await? else {
+        return Err(Error::user_not_found("user not found"));
+    };
+    if user.password.is_empty() {
+        return Err(Error::invalid_password(
+            "password not set, use a recovery code instead",
+        ));
+    }
+    if !utils::verify_password(password.as_bytes(), &user.password)? {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Error::invalid_password("password mismatch"));
+    }
+    limiter::clear_failures(svc, &lockout_key).await?;
+    Ok(())
+}
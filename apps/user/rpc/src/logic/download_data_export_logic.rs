@@ -0,0 +1,68 @@
+use crate::error::{Error, ErrorKind};
+use crate::export;
+use crate::pb::user::{DataExportChunk, DownloadDataExportRequest};
+use crate::service_context::ServiceContext;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+/// 每段内容的字节数
+const CHUNK_SIZE: usize = 64 * 1024;
+/// 等待发送的段数
+const STREAM_BUFFER_SIZE: usize = 4;
+
+pub async fn download_data_export_logic(
+    svc: &ServiceContext,
+    request: Request<DownloadDataExportRequest>,
+) -> Result<Response<ReceiverStream<Result<DataExportChunk, Status>>>, Status> {
+    let req = request.into_inner();
+    info!("download data export, export_id: {}", req.export_id);
+
+    // 凭证错误时按不存在处理, 不暴露导出是否存在
+    let not_found = || Error::with_details(ErrorKind::NotFound, "data export not found");
+    let data_export = svc
+        .data_export_repo
+        .find(&req.export_id)
+        .await?
+        .ok_or_else(not_found)?;
+    if !export::verify_download_token(
+        &svc.config.jwt.secret,
+        &data_export.id,
+        data_export.expire_time,
+        &req.download_token,
+    ) {
+        return Err(not_found().into());
+    }
+    if !export::is_downloadable(&data_export, chrono::Utc::now().timestamp_millis()) {
+        return Err(Error::permission_denied("download link expired").into());
+    }
+
+    let path = export::archive_path(&svc.config.export, &data_export.id);
+    let mut file = tokio::fs::File::open(&path).await.map_err(|e| {
+        Error::internal_with_details(format!("open data export {:?} failed, {}", path, e))
+    })?;
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+    tokio::spawn(async move {
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let chunk = match file.read(&mut buf).await {
+                Ok(0) => return,
+                Ok(n) => Ok(DataExportChunk {
+                    data: buf[..n].to_vec(),
+                }),
+                Err(e) => {
+                    warn!("read data export {:?} failed: {:?}", path, e);
+                    Err(Status::internal("read data export failed"))
+                }
+            };
+            let failed = chunk.is_err();
+            // 调用方断开后停止读取
+            if tx.send(chunk).await.is_err() || failed {
+                return;
+            }
+        }
+    });
+    Ok(Response::new(ReceiverStream::new(rx)))
+}
@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::pb::user::{EnrollTotpRequest, EnrollTotpResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::{jwt, totp};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn enroll_totp_logic(
+    svc: &ServiceContext,
+    request: Request<EnrollTotpRequest>,
+) -> Result<Response<EnrollTotpResponse>, Status> {
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    info!("enroll totp, user_id: {}", user_id);
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+    if let Some(totp) = svc.totp_repo.find(&user.id).await? {
+        if totp.enabled {
+            return Err(Status::from(Error::invalid_argument(
+                "two-factor authentication already enabled",
+            )));
+        }
+    }
+
+    // 确认之前不生效, 重复调用会生成新的密钥
+    let secret = totp::generate_secret();
+    svc.totp_repo.save_pending(&user.id, &secret).await?;
+
+    let otpauth_uri = totp::otpauth_uri(&svc.config.two_factor.issuer, &user.account, &secret);
+    Ok(Response::new(EnrollTotpResponse {
+        secret,
+        otpauth_uri,
+    }))
+}
@@ -1,47 +1,74 @@
-use crate::pb::user::{FindUserRequest, FindUserResponse};
+use crate::pb::user::{FindUserRequest, FindUserResponse, UserView};
 use crate::service_context::ServiceContext;
+use crate::utils::block;
+use crate::utils::view::{self, LookupBy};
+use std::collections::HashSet;
 use tracing::info;
 
 pub async fn find_user_logic(
     svc: &ServiceContext,
     request: tonic::Request<FindUserRequest>,
 ) -> Result<tonic::Response<FindUserResponse>, tonic::Status> {
-    info!("request: {:?}", request);
+    info!("request: {:?}", request.get_ref());
+    let viewer = view::viewer(svc, &request).await?;
 
     let req = request.into_inner();
     let mut users = vec![];
 
-    if req.user_id.len() > 0 {
+    if !req.user_id.is_empty() {
         let user = svc.user_repo.find_by_ids(req.user_id).await?;
         users.extend(user);
     }
-    if req.name.is_some() {
-        let user = svc.user_repo.find_by_name(&req.name.unwrap()).await?;
+    if let Some(name) = req.name {
+        let user = svc.user_repo.find_by_name(&name).await?;
         if let Some(user) = user {
             users.push(user);
         }
     }
 
-    if req.account.is_some() {
-        let user = svc.user_repo.find_by_account(&req.account.unwrap()).await?;
+    // 按账号/手机号/邮箱查找时遵守用户的隐私设置
+    if let Some(account) = req.account {
+        let user = svc.user_repo.find_by_account(&account).await?;
         if let Some(user) = user {
-            users.push(user);
+            if view::discoverable(svc, viewer.as_ref(), &user, LookupBy::Account).await? {
+                users.push(user);
+            }
         }
     }
 
-    if req.phone.is_some() {
-        let user = svc.user_repo.find_by_phone(&req.phone.unwrap()).await?;
+    if let Some(phone) = req.phone {
+        let user = svc.user_repo.find_by_phone(&phone).await?;
         if let Some(user) = user {
-            users.push(user);
+            if view::discoverable(svc, viewer.as_ref(), &user, LookupBy::Phone).await? {
+                users.push(user);
+            }
         }
     }
 
-    if req.email.is_some() {
-        let user = svc.user_repo.find_by_email(&req.email.unwrap()).await?;
+    if let Some(email) = req.email {
+        let user = svc.user_repo.find_by_email(&email).await?;
         if let Some(user) = user {
-            users.push(user);
+            if view::discoverable(svc, viewer.as_ref(), &user, LookupBy::Email).await? {
+                users.push(user);
+            }
         }
     }
 
+    // 多个条件可能命中同一用户, 按id去重并保持顺序
+    let mut seen = HashSet::new();
+    users.retain(|user| seen.insert(user.id.clone()));
+
+    // 存在拉黑关系的用户互相查找不到, 管理员/客服不受限制
+    if let Some(viewer) = viewer.as_ref().filter(|viewer| !viewer.privileged) {
+        let others: Vec<String> = users
+            .iter()
+            .filter(|user| user.id != viewer.user_id)
+            .map(|user| user.id.clone())
+            .collect();
+        let blocked = block::blocked_between(svc, &viewer.user_id, &others).await?;
+        users.retain(|user| !blocked.contains(&user.id));
+    }
+    let users = view::filter_users(svc, viewer.as_ref(), users, UserView::Full, None).await?;
+
     Ok(tonic::Response::new(FindUserResponse { users }))
 }
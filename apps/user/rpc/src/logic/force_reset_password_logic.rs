@@ -0,0 +1,67 @@
+use crate::error::Error;
+use crate::pb::user::{
+    ForceResetPasswordRequest, ForceResetPasswordResponse, SecurityEventType, UserRole,
+};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::jwt;
+use crate::utils::{audit, ClientInfo};
+use nanoid::nanoid;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+/// 随机密码长度
+const GENERATED_PASSWORD_LEN: usize = 16;
+
+pub async fn force_reset_password_logic(
+    svc: &ServiceContext,
+    request: Request<ForceResetPasswordRequest>,
+) -> Result<Response<ForceResetPasswordResponse>, Status> {
+    let operator = jwt::authorize(svc, &request, &[UserRole::Admin]).await?;
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!(
+        "force reset password, operator: {}, user_id: {}",
+        operator.id, req.user_id
+    );
+
+    let Some(user) = svc.user_repo.find_by_id(&req.user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+
+    // 未指定新密码时生成随机密码, 只在本次响应中返回
+    let (password, generated) = if req.new_password.is_empty() {
+        let password = nanoid!(GENERATED_PASSWORD_LEN);
+        (password.clone(), password)
+    } else {
+        (req.new_password, String::new())
+    };
+    let encoded_password = utils::hash_password(&svc.config.password, password.as_bytes())?;
+    svc.user_repo
+        .update_password(&user.id, &encoded_password)
+        .await?;
+    svc.cache.revoke_user_sessions(&user.id).await?;
+
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::PasswordReset,
+        Some(&user.id),
+        &user.account,
+        "reset by admin",
+    )
+    .await;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::SessionsRevoked,
+        Some(&user.id),
+        &user.account,
+        "password reset by admin",
+    )
+    .await;
+
+    Ok(Response::new(ForceResetPasswordResponse {
+        password: generated,
+    }))
+}
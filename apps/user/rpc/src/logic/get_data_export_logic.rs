@@ -0,0 +1,32 @@
+use crate::error::{Error, ErrorKind};
+use crate::export;
+use crate::pb::user::{GetDataExportRequest, GetDataExportResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn get_data_export_logic(
+    svc: &ServiceContext,
+    request: Request<GetDataExportRequest>,
+) -> Result<Response<GetDataExportResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    // 导出包含下载凭证, 只能查看自己的导出
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    if req.export_id.is_empty() {
+        return Err(Error::invalid_argument("export_id is empty").into());
+    }
+    // 不属于该用户的导出按不存在处理
+    let data_export = svc
+        .data_export_repo
+        .find(&req.export_id)
+        .await?
+        .filter(|data_export| data_export.user_id == user_id)
+        .ok_or_else(|| Error::with_details(ErrorKind::NotFound, "data export not found"))?;
+
+    Ok(Response::new(GetDataExportResponse {
+        export: Some(export::to_message(svc, data_export)),
+    }))
+}
@@ -0,0 +1,22 @@
+use crate::error::Error;
+use crate::pb::user::{GetPrivacySettingsRequest, GetPrivacySettingsResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn get_privacy_settings_logic(
+    svc: &ServiceContext,
+    request: Request<GetPrivacySettingsRequest>,
+) -> Result<Response<GetPrivacySettingsResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+
+    if svc.user_repo.find_by_id(&user_id).await?.is_none() {
+        return Err(Error::user_not_found("user not found").into());
+    }
+    let privacy = svc.privacy_repo.find(&user_id).await?;
+    Ok(Response::new(GetPrivacySettingsResponse {
+        settings: Some(privacy.into()),
+    }))
+}
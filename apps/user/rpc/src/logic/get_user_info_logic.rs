@@ -1,16 +1,27 @@
 use crate::pb;
 use crate::pb::user::{
     GetUserInfoRequest, GetUserInfoResponse, UserOnlineCountRequest, UserOnlineCountResponse,
+    UserView,
 };
 use crate::service_context::ServiceContext;
+use crate::utils::view;
 use tracing::info;
 
 pub async fn get_user_info_logic(
     svc: &ServiceContext,
     request: tonic::Request<GetUserInfoRequest>,
 ) -> Result<tonic::Response<GetUserInfoResponse>, tonic::Status> {
-    info!("request: {:?}", request);
+    info!("request: {:?}", request.get_ref());
+    let viewer = view::viewer(svc, &request).await?;
     let user_id = request.into_inner().user_id;
-    let user = svc.user_repo.find_by_id(&user_id).await?;
+    let users = svc
+        .user_repo
+        .find_by_id(&user_id)
+        .await?
+        .into_iter()
+        .collect();
+    let user = view::filter_users(svc, viewer.as_ref(), users, UserView::Full, None)
+        .await?
+        .pop();
     Ok(tonic::Response::new(GetUserInfoResponse { user }))
 }
@@ -1,14 +1,26 @@
-use crate::pb;
 use crate::pb::user::{UserOnlineCountRequest, UserOnlineCountResponse};
 use crate::service_context::ServiceContext;
+use std::collections::HashMap;
 use tracing::info;
 
 pub async fn get_user_online_count_logic(
     svc: &ServiceContext,
     request: tonic::Request<UserOnlineCountRequest>,
 ) -> Result<tonic::Response<UserOnlineCountResponse>, tonic::Status> {
-    info!("request: {:?}", request);
+    info!("request: {:?}", request.get_ref());
+    let req = request.into_inner();
     let count = svc.cache.get_user_online_count().await?;
 
-    Ok(tonic::Response::new(UserOnlineCountResponse { count }))
+    let mut platforms = HashMap::new();
+    if req.by_platform {
+        for platform in &svc.config.presence.platforms {
+            let count = svc.cache.get_platform_online_count(platform).await?;
+            platforms.insert(platform.clone(), count);
+        }
+    }
+
+    Ok(tonic::Response::new(UserOnlineCountResponse {
+        count,
+        platforms,
+    }))
 }
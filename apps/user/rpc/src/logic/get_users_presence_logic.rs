@@ -0,0 +1,65 @@
+use crate::error::Error;
+use crate::pb::user::{
+    GetUsersPresenceRequest, GetUsersPresenceResponse, PresenceStatus, UserPresence,
+};
+use crate::service_context::ServiceContext;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn get_users_presence_logic(
+    svc: &ServiceContext,
+    request: Request<GetUsersPresenceRequest>,
+) -> Result<Response<GetUsersPresenceResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let mut user_ids = request.into_inner().user_ids;
+    user_ids.sort();
+    user_ids.dedup();
+    if user_ids.len() > svc.config.presence.max_batch_size {
+        return Err(Status::from(Error::invalid_argument("too many user_ids")));
+    }
+
+    let presences = svc
+        .cache
+        .get_users_presence(&user_ids)
+        .await?
+        .into_iter()
+        .map(hide_invisible)
+        .collect();
+    Ok(Response::new(GetUsersPresenceResponse { presences }))
+}
+
+/// 隐身用户对外显示为离线, 不暴露在线的平台和最后在线时间
+pub(crate) fn hide_invisible(mut presence: UserPresence) -> UserPresence {
+    if presence.status == PresenceStatus::Invisible as i32 {
+        presence.status = PresenceStatus::Offline as i32;
+        presence.platforms.clear();
+        presence.last_seen = 0;
+    }
+    presence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hide_invisible() {
+        let presence = UserPresence {
+            user_id: "id".to_string(),
+            status: PresenceStatus::Invisible as i32,
+            last_seen: 1,
+            platforms: vec!["ios".to_string()],
+        };
+        let presence = hide_invisible(presence);
+        assert_eq!(presence.status, PresenceStatus::Offline as i32);
+        assert!(presence.platforms.is_empty());
+        assert_eq!(presence.last_seen, 0);
+
+        let presence = UserPresence {
+            status: PresenceStatus::Busy as i32,
+            platforms: vec!["web".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(hide_invisible(presence.clone()), presence);
+    }
+}
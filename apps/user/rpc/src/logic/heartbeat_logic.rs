@@ -0,0 +1,49 @@
+use crate::error::Error;
+use crate::pb::user::{HeartbeatRequest, HeartbeatResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::debug;
+
+const MAX_DEVICE_ID_LEN: usize = 128;
+
+pub async fn heartbeat_logic(
+    svc: &ServiceContext,
+    request: Request<HeartbeatRequest>,
+) -> Result<Response<HeartbeatResponse>, Status> {
+    debug!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    validate_device(svc, &user_id, &req.platform, &req.device_id)?;
+    let config = &svc.config.presence;
+    svc.cache
+        .heartbeat(&user_id, &req.platform, &req.device_id, config.ttl)
+        .await?;
+
+    Ok(Response::new(HeartbeatResponse {
+        interval: config.heartbeat_interval as i64,
+    }))
+}
+
+/// 平台必须是配置中的平台, 设备id不能为空
+pub(crate) fn validate_device(
+    svc: &ServiceContext,
+    user_id: &str,
+    platform: &str,
+    device_id: &str,
+) -> Result<(), Error> {
+    if user_id.is_empty() {
+        return Err(Error::invalid_argument("user_id is empty"));
+    }
+    if !svc.config.presence.platforms.iter().any(|p| p == platform) {
+        return Err(Error::invalid_argument(format!(
+            "unknown platform `{}`",
+            platform
+        )));
+    }
+    if device_id.is_empty() || device_id.len() > MAX_DEVICE_ID_LEN {
+        return Err(Error::invalid_argument("invalid device_id"));
+    }
+    Ok(())
+}
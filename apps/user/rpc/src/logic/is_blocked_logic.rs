@@ -0,0 +1,25 @@
+use crate::error::Error;
+use crate::pb::user::{IsBlockedRequest, IsBlockedResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::{block, jwt};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn is_blocked_logic(
+    svc: &ServiceContext,
+    request: Request<IsBlockedRequest>,
+) -> Result<Response<IsBlockedResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    // 只能查询自己与其他用户的关系, 管理员可以查询任意用户
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+    let req = request.into_inner();
+
+    if req.other_user_id.is_empty() {
+        return Err(Error::invalid_argument("other_user_id is empty").into());
+    }
+    let relation = block::relation(svc, &user_id, &req.other_user_id).await?;
+    Ok(Response::new(IsBlockedResponse {
+        blocking: relation.blocking,
+        blocked_by: relation.blocked_by,
+    }))
+}
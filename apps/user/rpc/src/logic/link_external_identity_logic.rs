@@ -0,0 +1,74 @@
+use crate::error::Error;
+use crate::logic::oidc_authorize_logic::take_authorization_state;
+use crate::pb::user::{
+    ExternalIdentity, LinkExternalIdentityRequest, LinkExternalIdentityResponse,
+};
+use crate::repo::UserIdentity;
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn link_external_identity_logic(
+    svc: &ServiceContext,
+    request: Request<LinkExternalIdentityRequest>,
+) -> Result<Response<LinkExternalIdentityResponse>, Status> {
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("link external identity, user_id: {}", user_id);
+
+    // state必须是为调用方签发的, 防止把其他人的第三方身份绑定到自己名下
+    let state = take_authorization_state(svc, &req.state).await?;
+    if state.link_user_id.as_deref() != Some(user_id.as_str()) {
+        return Err(Status::from(Error::invalid_argument(
+            "state is not issued for this user",
+        )));
+    }
+    let provider = svc.identity_providers.get(&state.provider)?;
+    let identity = provider.exchange_code(&req.code, &state.nonce).await?;
+
+    if let Some(link) = svc
+        .identity_repo
+        .find(&identity.provider, &identity.subject)
+        .await?
+    {
+        if link.user_id != user_id {
+            return Err(Status::from(Error::invalid_argument(
+                "identity already linked to another user",
+            )));
+        }
+        // 重复绑定同一身份
+        return Ok(Response::new(LinkExternalIdentityResponse {
+            identity: Some(to_pb(link)),
+        }));
+    }
+
+    let linked = svc.identity_repo.find_by_user(&user_id).await?;
+    if linked.iter().any(|link| link.provider == identity.provider) {
+        return Err(Status::from(Error::invalid_argument(
+            "another identity of this provider already linked",
+        )));
+    }
+
+    let link = UserIdentity {
+        provider: identity.provider,
+        subject: identity.subject,
+        user_id,
+        email: identity.email,
+        create_time: chrono::Utc::now().timestamp_millis(),
+    };
+    svc.identity_repo.insert(&link).await?;
+
+    Ok(Response::new(LinkExternalIdentityResponse {
+        identity: Some(to_pb(link)),
+    }))
+}
+
+pub(crate) fn to_pb(identity: UserIdentity) -> ExternalIdentity {
+    ExternalIdentity {
+        provider: identity.provider,
+        subject: identity.subject,
+        email: identity.email,
+        create_time: identity.create_time,
+    }
+}
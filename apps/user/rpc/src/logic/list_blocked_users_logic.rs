@@ -0,0 +1,66 @@
+use crate::pb::user::{BlockedUser, ListBlockedUsersRequest, ListBlockedUsersResponse, UserView};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use crate::utils::view;
+use std::collections::HashMap;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub async fn list_blocked_users_logic(
+    svc: &ServiceContext,
+    request: Request<ListBlockedUsersRequest>,
+) -> Result<Response<ListBlockedUsersResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    // 只能查看自己的黑名单, 管理员可以查看其他用户的
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+    let req = request.into_inner();
+
+    let limit = match req.limit {
+        n if n <= 0 => DEFAULT_LIMIT,
+        n => (n as i64).min(MAX_LIMIT),
+    };
+    // 游标为上一页最后一条记录的id
+    let before_id = Some(req.cursor).filter(|cursor| *cursor > 0);
+
+    let blocks = svc.block_repo.list(&user_id, before_id, limit).await?;
+
+    // 返回满页时才有下一页
+    let next_cursor = match blocks.last() {
+        Some(last) if blocks.len() as i64 == limit => last.id,
+        _ => 0,
+    };
+    let ids = blocks
+        .iter()
+        .map(|block| block.blocked_user_id.clone())
+        .collect();
+    let mut users: HashMap<String, _> = svc
+        .user_repo
+        .find_by_ids(ids)
+        .await?
+        .into_iter()
+        .map(|user| {
+            let user = view::mask_user(user, UserView::Public, None);
+            (user.id.clone(), user)
+        })
+        .collect();
+    // 已注销的用户不返回
+    let users = blocks
+        .into_iter()
+        .filter_map(|block| {
+            users
+                .remove(&block.blocked_user_id)
+                .map(|user| BlockedUser {
+                    user: Some(user),
+                    create_time: block.create_time,
+                })
+        })
+        .collect();
+
+    Ok(Response::new(ListBlockedUsersResponse {
+        users,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,23 @@
+use crate::logic::link_external_identity_logic::to_pb;
+use crate::pb::user::{ListExternalIdentitiesRequest, ListExternalIdentitiesResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn list_external_identities_logic(
+    svc: &ServiceContext,
+    request: Request<ListExternalIdentitiesRequest>,
+) -> Result<Response<ListExternalIdentitiesResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+
+    let identities = svc
+        .identity_repo
+        .find_by_user(&user_id)
+        .await?
+        .into_iter()
+        .map(to_pb)
+        .collect();
+    Ok(Response::new(ListExternalIdentitiesResponse { identities }))
+}
@@ -0,0 +1,53 @@
+use crate::pb::user::{ListSecurityEventsRequest, ListSecurityEventsResponse, SecurityEvent};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub async fn list_security_events_logic(
+    svc: &ServiceContext,
+    request: Request<ListSecurityEventsRequest>,
+) -> Result<Response<ListSecurityEventsResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    // 只能查看自己的安全事件, 管理员可以查看其他用户的
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+    let req = request.into_inner();
+
+    let limit = match req.limit {
+        n if n <= 0 => DEFAULT_LIMIT,
+        n => (n as i64).min(MAX_LIMIT),
+    };
+    // 游标为上一页最后一条事件的id
+    let before_id = Some(req.cursor).filter(|cursor| *cursor > 0);
+
+    let events = svc
+        .security_event_repo
+        .list(&user_id, before_id, limit)
+        .await?;
+
+    // 返回满页时才有下一页
+    let next_cursor = match events.last() {
+        Some(last) if events.len() as i64 == limit => last.id,
+        _ => 0,
+    };
+    let events = events
+        .into_iter()
+        .map(|event| SecurityEvent {
+            id: event.id,
+            event_type: event.event_type as i32,
+            account: event.account,
+            ip: event.ip,
+            device: event.device,
+            reason: event.reason,
+            create_time: event.create_time,
+        })
+        .collect();
+
+    Ok(Response::new(ListSecurityEventsResponse {
+        events,
+        next_cursor,
+    }))
+}
@@ -1,52 +1,176 @@
 use crate::error::Error;
-use crate::pb::user::{LoginRequest, LoginResponse};
+use crate::pb::user::{LoginRequest, LoginResponse, SecurityEventType, User};
 use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::audit;
 use crate::utils::jwt::gen_token;
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
+use crate::utils::limiter;
+use crate::utils::ClientInfo;
+use nanoid::nanoid;
 use tonic::{Request, Response, Status};
-use tracing::info;
+use tracing::warn;
 
 pub async fn login_logic(
     svc: &ServiceContext,
     request: Request<LoginRequest>,
 ) -> Result<Response<LoginResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
     let req = request.into_inner();
+    let config = &svc.config.rate_limit;
+
+    // 防爆破: 账号锁定检查, 再按账号和IP限流
+    let lockout_key = format!("login:{}", req.account);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+    limiter::check_rate_limit(
+        svc,
+        &format!("login:account:{}", req.account),
+        config.login_per_account,
+        config.login_window,
+    )
+    .await?;
+    limiter::check_rate_limit(
+        svc,
+        &format!("login:ip:{}", client.ip),
+        config.login_per_ip,
+        config.login_window,
+    )
+    .await?;
 
     let Some(user) = svc.user_repo.find_by_account(&req.account).await? else {
+        limiter::record_failure(svc, &lockout_key).await?;
+        audit::record(
+            svc,
+            &client,
+            SecurityEventType::LoginFailed,
+            None,
+            &req.account,
+            "account not found",
+        )
+        .await;
         return Err(Status::from(Error::invalid_account_or_password(
             "账号或密码错误",
         )));
     };
 
-    let password_hash = match PasswordHash::new(&user.password) {
-        Ok(password) => password,
-        Err(e) => {
-            return Err(Status::from(Error::internal_with_details(format!(
-                "密码哈希错误 {}",
-                e
-            ))));
-        }
-    };
-
-    let valid = Argon2::default()
-        .verify_password(req.password.as_bytes(), &password_hash)
-        .is_ok();
+    // 手机号注册时可以不设置密码, 只能使用验证码登录
+    let valid = !user.password.is_empty()
+        && utils::verify_password(req.password.as_bytes(), &user.password)?;
     if !valid {
+        limiter::record_failure(svc, &lockout_key).await?;
+        audit::record(
+            svc,
+            &client,
+            SecurityEventType::LoginFailed,
+            Some(&user.id),
+            &req.account,
+            "password mismatch",
+        )
+        .await;
         return Err(Status::from(Error::invalid_account_or_password(
             "账号或密码错误",
         )));
     }
+    limiter::clear_failures(svc, &lockout_key).await?;
 
+    // 哈希参数已调整时, 使用新参数重新哈希密码
+    if utils::password_needs_rehash(&svc.config.password, &user.password) {
+        match utils::hash_password(&svc.config.password, req.password.as_bytes()) {
+            Ok(password) => {
+                if let Err(e) = svc.user_repo.update_password(&user.id, &password).await {
+                    warn!("rehash password failed, user_id: {}, {:?}", user.id, e);
+                }
+            }
+            Err(e) => warn!("rehash password failed, user_id: {}, {:?}", user.id, e),
+        }
+    }
+
+    Ok(Response::new(
+        finish_login(svc, &user, &client, "password").await?,
+    ))
+}
+
+/// 第一步认证通过后完成登录: 开启了两步验证时返回登录挑战, 否则直接签发token
+///
+/// `method`为登录方式, 记录在登录成功的安全事件中
+pub(crate) async fn finish_login(
+    svc: &ServiceContext,
+    user: &User,
+    client: &ClientInfo,
+    method: &str,
+) -> Result<LoginResponse, Error> {
+    if let Err(e) = ensure_not_banned(user) {
+        audit::record(
+            svc,
+            client,
+            SecurityEventType::LoginFailed,
+            Some(&user.id),
+            &user.account,
+            "account banned",
+        )
+        .await;
+        return Err(e);
+    }
+
+    let totp_enabled = svc
+        .totp_repo
+        .find(&user.id)
+        .await?
+        .is_some_and(|totp| totp.enabled);
+    if totp_enabled {
+        let challenge_token = nanoid!(32);
+        svc.cache
+            .save_login_challenge(
+                &challenge_token,
+                &user.id,
+                svc.config.two_factor.challenge_ttl,
+            )
+            .await?;
+        return Ok(LoginResponse {
+            user_id: user.id.clone(),
+            second_factor_required: true,
+            challenge_token,
+            ..Default::default()
+        });
+    }
+
+    issue_tokens(svc, user, client, method).await
+}
+
+/// 封禁中的账号不能登录, 返回封禁原因
+pub(crate) fn ensure_not_banned(user: &User) -> Result<(), Error> {
+    if utils::is_banned(user, chrono::Utc::now().timestamp_millis()) {
+        return Err(Error::account_banned(format!(
+            "account banned: {}",
+            user.ban_reason
+        )));
+    }
+    Ok(())
+}
+
+/// 签发token并记录登录成功, 在线状态由客户端连接后的心跳维护
+pub(crate) async fn issue_tokens(
+    svc: &ServiceContext,
+    user: &User,
+    client: &ClientInfo,
+    method: &str,
+) -> Result<LoginResponse, Error> {
     // 生成jwt token
-    let token = gen_token(&user, &svc.config.jwt.secret)?;
-    info!("gen token: {:?}", token);
+    let token = gen_token(user, &svc.config.jwt.secret)?;
 
-    // 设置登录态
-    svc.cache.set_user_login(&user.id).await?;
+    audit::record(
+        svc,
+        client,
+        SecurityEventType::LoginSuccess,
+        Some(&user.id),
+        &user.account,
+        method,
+    )
+    .await;
 
-    Ok(Response::new(LoginResponse {
+    Ok(LoginResponse {
         user_id: token.user_id,
         token: token.token,
         refresh_token: token.refresh_token,
-    }))
+        ..Default::default()
+    })
 }
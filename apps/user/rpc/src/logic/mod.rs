@@ -1,15 +1,100 @@
+pub(crate) mod admin_list_users_logic;
+pub(crate) mod ban_user_logic;
+pub(crate) mod batch_get_users_logic;
+pub(crate) mod block_user_logic;
+pub(crate) mod can_send_friend_request_logic;
+pub(crate) mod change_password_logic;
+pub(crate) mod confirm_totp_logic;
+pub(crate) mod deactivate_account_logic;
+pub(crate) mod device_offline_logic;
+pub(crate) mod disable_totp_logic;
+pub(crate) mod download_data_export_logic;
+pub(crate) mod enroll_totp_logic;
 pub(crate) mod find_user_logic;
+pub(crate) mod force_reset_password_logic;
+pub(crate) mod get_data_export_logic;
+pub(crate) mod get_privacy_settings_logic;
 pub(crate) mod get_user_info_logic;
 pub(crate) mod get_user_online_count_logic;
+pub(crate) mod get_users_presence_logic;
+pub(crate) mod heartbeat_logic;
+pub(crate) mod is_blocked_logic;
+pub(crate) mod link_external_identity_logic;
+pub(crate) mod list_blocked_users_logic;
+pub(crate) mod list_external_identities_logic;
+pub(crate) mod list_security_events_logic;
 pub(crate) mod login_logic;
+pub(crate) mod oidc_authorize_logic;
+pub(crate) mod oidc_login_logic;
+pub(crate) mod phone_login_logic;
+pub(crate) mod phone_register_logic;
 pub(crate) mod ping_logic;
 pub(crate) mod register_logic;
+pub(crate) mod request_data_export_logic;
+pub(crate) mod reset_password_logic;
+pub(crate) mod restore_account_logic;
+pub(crate) mod revoke_user_sessions_logic;
+pub(crate) mod search_users_logic;
+pub(crate) mod send_password_reset_code_logic;
 pub(crate) mod send_register_code_logic;
+pub(crate) mod send_sms_code_logic;
+pub(crate) mod set_presence_status_logic;
+pub(crate) mod unban_user_logic;
+pub(crate) mod unblock_user_logic;
+pub(crate) mod unlink_external_identity_logic;
+pub(crate) mod update_privacy_settings_logic;
+pub(crate) mod update_user_profile_logic;
+pub(crate) mod verify_second_factor_logic;
+pub(crate) mod watch_users_logic;
 
+pub(crate) use admin_list_users_logic::admin_list_users_logic;
+pub(crate) use ban_user_logic::ban_user_logic;
+pub(crate) use batch_get_users_logic::batch_get_users_logic;
+pub(crate) use block_user_logic::block_user_logic;
+pub(crate) use can_send_friend_request_logic::can_send_friend_request_logic;
+pub(crate) use change_password_logic::change_password_logic;
+pub(crate) use confirm_totp_logic::confirm_totp_logic;
+pub(crate) use deactivate_account_logic::deactivate_account_logic;
+pub(crate) use device_offline_logic::device_offline_logic;
+pub(crate) use disable_totp_logic::disable_totp_logic;
+pub(crate) use download_data_export_logic::download_data_export_logic;
+pub(crate) use enroll_totp_logic::enroll_totp_logic;
 pub(crate) use find_user_logic::find_user_logic;
+pub(crate) use force_reset_password_logic::force_reset_password_logic;
+pub(crate) use get_data_export_logic::get_data_export_logic;
+pub(crate) use get_privacy_settings_logic::get_privacy_settings_logic;
 pub(crate) use get_user_info_logic::get_user_info_logic;
 pub(crate) use get_user_online_count_logic::get_user_online_count_logic;
+pub(crate) use get_users_presence_logic::get_users_presence_logic;
+pub(crate) use heartbeat_logic::heartbeat_logic;
+pub(crate) use is_blocked_logic::is_blocked_logic;
+pub(crate) use link_external_identity_logic::link_external_identity_logic;
+pub(crate) use list_blocked_users_logic::list_blocked_users_logic;
+pub(crate) use list_external_identities_logic::list_external_identities_logic;
+pub(crate) use list_security_events_logic::list_security_events_logic;
 pub(crate) use login_logic::login_logic;
+pub(crate) use oidc_authorize_logic::oidc_authorize_logic;
+pub(crate) use oidc_login_logic::oidc_login_logic;
+pub(crate) use phone_login_logic::phone_login_logic;
+pub(crate) use phone_register_logic::phone_register_logic;
 pub(crate) use ping_logic::ping_logic;
 pub(crate) use register_logic::register_logic;
+pub(crate) use request_data_export_logic::request_data_export_logic;
+pub(crate) use reset_password_logic::reset_password_logic;
+pub(crate) use restore_account_logic::restore_account_logic;
+pub(crate) use revoke_user_sessions_logic::revoke_user_sessions_logic;
+pub(crate) use search_users_logic::search_users_logic;
+pub(crate) use send_password_reset_code_logic::send_password_reset_code_logic;
 pub(crate) use send_register_code_logic::send_register_code_logic;
+pub(crate) use send_sms_code_logic::send_sms_code_logic;
+pub(crate) use set_presence_status_logic::set_presence_status_logic;
+pub(crate) use unban_user_logic::unban_user_logic;
+pub(crate) use unblock_user_logic::unblock_user_logic;
+pub(crate) use unlink_external_identity_logic::unlink_external_identity_logic;
+pub(crate) use update_privacy_settings_logic::update_privacy_settings_logic;
+pub(crate) use update_user_profile_logic::update_user_profile_logic;
+pub(crate) use verify_second_factor_logic::verify_second_factor_logic;
+pub(crate) use watch_users_logic::watch_users_logic;
+
+#[cfg(test)]
+pub(crate) mod tests;
@@ -0,0 +1,59 @@
+use crate::error::Error;
+use crate::oidc::AuthorizationState;
+use crate::pb::user::{OidcAuthorizeRequest, OidcAuthorizeResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use nanoid::nanoid;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn oidc_authorize_logic(
+    svc: &ServiceContext,
+    request: Request<OidcAuthorizeRequest>,
+) -> Result<Response<OidcAuthorizeResponse>, Status> {
+    // 绑定需要登录, 只能绑定到调用方自己
+    let link_user_id = if request.get_ref().user_id.is_empty() {
+        None
+    } else {
+        Some(jwt::caller_id(&request, &request.get_ref().user_id)?)
+    };
+    let req = request.into_inner();
+    info!("request: {:?}", req);
+
+    let provider = svc.identity_providers.get(&req.provider)?;
+    if let Some(user_id) = &link_user_id {
+        if svc.user_repo.find_by_id(user_id).await?.is_none() {
+            return Err(Status::from(Error::user_not_found("user not found")));
+        }
+    }
+
+    let auth_state = AuthorizationState {
+        provider: req.provider,
+        nonce: nanoid!(32),
+        link_user_id,
+    };
+    let state = nanoid!(32);
+    let value = serde_json::to_string(&auth_state)
+        .map_err(|e| Error::internal_with_details(format!("encode oidc state, {}", e)))?;
+    svc.cache
+        .save_oidc_state(&state, &value, svc.config.oidc.state_ttl)
+        .await?;
+
+    let authorization_url = provider.authorization_url(&state, &auth_state.nonce);
+    Ok(Response::new(OidcAuthorizeResponse {
+        authorization_url,
+        state,
+    }))
+}
+
+/// 取出授权请求的上下文, state只能使用一次
+pub(crate) async fn take_authorization_state(
+    svc: &ServiceContext,
+    state: &str,
+) -> Result<AuthorizationState, Error> {
+    let Some(value) = svc.cache.take_oidc_state(state).await? else {
+        return Err(Error::invalid_argument("state expired"));
+    };
+    serde_json::from_str(&value)
+        .map_err(|e| Error::internal_with_details(format!("decode oidc state, {}", e)))
+}
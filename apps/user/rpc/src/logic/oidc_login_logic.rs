@@ -0,0 +1,91 @@
+use crate::error::Error;
+use crate::logic::login_logic::finish_login;
+use crate::logic::oidc_authorize_logic::take_authorization_state;
+use crate::oidc::VerifiedIdentity;
+use crate::pb::user::{LoginResponse, OidcLoginRequest, User};
+use crate::repo::UserIdentity;
+use crate::service_context::ServiceContext;
+use crate::utils::{self, ClientInfo};
+use nanoid::nanoid;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn oidc_login_logic(
+    svc: &ServiceContext,
+    request: Request<OidcLoginRequest>,
+) -> Result<Response<LoginResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+
+    let state = take_authorization_state(svc, &req.state).await?;
+    if state.link_user_id.is_some() {
+        return Err(Status::from(Error::invalid_argument(
+            "state is issued for linking",
+        )));
+    }
+    let provider = svc.identity_providers.get(&state.provider)?;
+    let identity = provider.exchange_code(&req.code, &state.nonce).await?;
+    info!(
+        "oidc login, provider: {}, subject: {}",
+        identity.provider, identity.subject
+    );
+
+    let user = match svc
+        .identity_repo
+        .find(&identity.provider, &identity.subject)
+        .await?
+    {
+        Some(link) => match svc.user_repo.find_by_id(&link.user_id).await? {
+            Some(user) => user,
+            None => return Err(Status::from(Error::user_not_found("user not found"))),
+        },
+        None => provision_user(svc, &identity).await?,
+    };
+
+    let method = format!("oidc:{}", identity.provider);
+    Ok(Response::new(
+        finish_login(svc, &user, &client, &method).await?,
+    ))
+}
+
+/// 首次登录时创建用户并绑定身份
+///
+/// 邮箱已被其他用户使用时不会自动绑定, 需要先登录该用户再绑定, 防止通过第三方邮箱接管账号
+async fn provision_user(svc: &ServiceContext, identity: &VerifiedIdentity) -> Result<User, Error> {
+    let email = identity.email.clone().filter(|_| identity.email_verified);
+    if let Some(email) = &email {
+        if svc.user_repo.find_by_email(email).await?.is_some() {
+            return Err(Error::invalid_email(
+                "email already registered, login and link the identity instead",
+            ));
+        }
+    }
+
+    // 账号只作为占位, 用户只能通过第三方身份登录, 直到设置密码或绑定手机号
+    let account = format!("{}:{}", identity.provider, nanoid!(12));
+    let user = User {
+        id: utils::gen_user_id(svc)?,
+        name: identity.name.clone().unwrap_or_else(|| account.clone()),
+        account,
+        avatar: identity.picture.clone().unwrap_or_default(),
+        email,
+        ..Default::default()
+    };
+    svc.user_repo.insert(user.clone()).await?;
+
+    svc.identity_repo
+        .insert(&UserIdentity {
+            provider: identity.provider.clone(),
+            subject: identity.subject.clone(),
+            user_id: user.id.clone(),
+            email: identity.email.clone(),
+            create_time: chrono::Utc::now().timestamp_millis(),
+        })
+        .await?;
+    info!(
+        "provisioned user {} for {}:{}",
+        user.id, identity.provider, identity.subject
+    );
+
+    Ok(user)
+}
@@ -0,0 +1,59 @@
+use crate::error::Error;
+use crate::logic::login_logic::finish_login;
+use crate::logic::send_sms_code_logic::verify_sms_code;
+use crate::pb::user::{LoginResponse, PhoneLoginRequest, SecurityEventType, SmsCodeScene};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn phone_login_logic(
+    svc: &ServiceContext,
+    request: Request<PhoneLoginRequest>,
+) -> Result<Response<LoginResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!("phone login, phone: {}, ip: {}", req.phone, client.ip);
+
+    let phone = utils::normalize_phone(&req.phone, &svc.config.sms.default_country_code)?;
+
+    let config = &svc.config.rate_limit;
+    limiter::check_rate_limit(
+        svc,
+        &format!("login:phone:{}", phone),
+        config.login_per_account,
+        config.login_window,
+    )
+    .await?;
+    limiter::check_rate_limit(
+        svc,
+        &format!("login:ip:{}", client.ip),
+        config.login_per_ip,
+        config.login_window,
+    )
+    .await?;
+
+    let user = svc.user_repo.find_by_phone(&phone).await?;
+    if let Err(e) = verify_sms_code(svc, SmsCodeScene::Login, &phone, &req.code).await {
+        audit::record(
+            svc,
+            &client,
+            SecurityEventType::LoginFailed,
+            user.as_ref().map(|user| user.id.as_str()),
+            &phone,
+            "sms code mismatch",
+        )
+        .await;
+        return Err(Status::from(e));
+    }
+
+    let Some(user) = user else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+
+    Ok(Response::new(
+        finish_login(svc, &user, &client, "sms").await?,
+    ))
+}
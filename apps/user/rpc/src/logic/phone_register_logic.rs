@@ -0,0 +1,49 @@
+use crate::error::Error;
+use crate::logic::send_sms_code_logic::verify_sms_code;
+use crate::pb::user::{PhoneRegisterRequest, PhoneRegisterResponse, SmsCodeScene, User};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn phone_register_logic(
+    svc: &ServiceContext,
+    request: Request<PhoneRegisterRequest>,
+) -> Result<Response<PhoneRegisterResponse>, Status> {
+    let req = request.into_inner();
+    info!("phone register, phone: {}, name: {}", req.phone, req.name);
+
+    let phone = utils::normalize_phone(&req.phone, &svc.config.sms.default_country_code)?;
+    if req.name.trim().is_empty() {
+        return Err(Status::from(Error::invalid_argument("name is empty")));
+    }
+
+    // 规范化后的手机号同时作为账号, 可以使用密码登录
+    if svc.user_repo.find_by_phone(&phone).await?.is_some()
+        || svc.user_repo.find_by_account(&phone).await?.is_some()
+    {
+        return Err(Status::from(Error::invalid_phone("phone already exists")));
+    }
+
+    verify_sms_code(svc, SmsCodeScene::Register, &phone, &req.code).await?;
+
+    let password = if req.password.is_empty() {
+        String::new()
+    } else {
+        utils::hash_password(&svc.config.password, req.password.as_bytes())?
+    };
+
+    let user = User {
+        id: utils::gen_user_id(svc)?,
+        name: req.name,
+        account: phone.clone(),
+        password,
+        avatar: req.avatar,
+        phone: Some(phone),
+        ..Default::default()
+    };
+    let user_id = user.id.clone();
+    svc.user_repo.insert(user).await?;
+
+    Ok(Response::new(PhoneRegisterResponse { user_id }))
+}
@@ -6,7 +6,7 @@ pub async fn ping_logic(
     ctx: &ServiceContext,
     request: tonic::Request<pb::user::Request>,
 ) -> Result<tonic::Response<pb::user::Response>, tonic::Status> {
-    info!("request: {:?}", request);
+    info!("request: {:?}", request.get_ref());
     let resp = pb::user::Response {
         pong: "pong".to_string(),
     };
@@ -2,7 +2,7 @@ use crate::error::Error;
 use crate::pb::user::{RegisterRequest, RegisterResponse, User};
 use crate::service_context::ServiceContext;
 use crate::utils;
-use nanoid::nanoid;
+use crate::utils::limiter;
 use tonic::{Request, Response, Status};
 use tracing::info;
 
@@ -10,9 +10,9 @@ pub async fn register_logic(
     svc: &ServiceContext,
     request: Request<RegisterRequest>,
 ) -> Result<Response<RegisterResponse>, Status> {
-    info!("request: {:?}", request);
     // todo: 校验验证码, 用户是否存在, 密码加密, 保存用户信息到数据库, 生成token, 返回token
     let req = request.into_inner();
+    info!("register, account: {}", req.account);
 
     if req.account.is_empty() {
         return Err(Status::from(Error::invalid_account("account is empty")));
@@ -36,6 +36,10 @@ pub async fn register_logic(
         }
     }
 
+    // 验证码错误次数过多时锁定, 防止暴力猜测
+    let lockout_key = format!("register_code:{}", req.account);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
     let code = match svc.cache.get_user_register_code(&req.account).await {
         Ok(code) => code,
         Err(_) => {
@@ -44,22 +48,25 @@ pub async fn register_logic(
     };
 
     if code != req.code {
+        limiter::record_failure(svc, &lockout_key).await?;
         return Err(Status::from(Error::invalid_code("code mismatch")));
     }
 
+    // 验证码只能使用一次
+    svc.cache.delete_user_register_code(&req.account).await?;
+    limiter::clear_failures(svc, &lockout_key).await?;
+
     // encode password
-    let salt = utils::generate_salt();
-    let encoded_password = utils::hash_password(req.password.as_bytes(), &salt)?;
+    let encoded_password = utils::hash_password(&svc.config.password, req.password.as_bytes())?;
 
     let user = User {
-        id: nanoid!(),
+        id: utils::gen_user_id(svc)?,
         name: req.name,
         account: req.account,
         password: encoded_password,
         avatar: req.avatar,
         email: Some(req.email),
         birthday: None,
-        salt,
         ..Default::default()
     };
 
@@ -0,0 +1,68 @@
+use crate::error::Error;
+use crate::export;
+use crate::pb::user::{
+    DataExportStatus, RequestDataExportRequest, RequestDataExportResponse, SecurityEventType,
+};
+use crate::repo::DataExport;
+use crate::service_context::ServiceContext;
+use crate::utils::{audit, jwt, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn request_data_export_logic(
+    svc: &ServiceContext,
+    request: Request<RequestDataExportRequest>,
+) -> Result<Response<RequestDataExportResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    // 导出包含下载凭证, 只能导出自己的数据
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    info!("request data export, user_id: {}", user_id);
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Error::user_not_found("user not found").into());
+    };
+
+    // 导出需要读取用户的全部数据, 进行中或最近导出过时返回已有的导出
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Some(latest) = svc.data_export_repo.find_latest(&user.id).await? {
+        let recent = latest.create_time + svc.config.export.min_interval as i64 * 1000 > now;
+        let reuse = match latest.status {
+            DataExportStatus::Pending | DataExportStatus::Running => true,
+            DataExportStatus::Ready => recent && export::is_downloadable(&latest, now),
+            DataExportStatus::Failed | DataExportStatus::Expired => false,
+        };
+        if reuse {
+            return Ok(Response::new(RequestDataExportResponse {
+                export: Some(export::to_message(svc, latest)),
+            }));
+        }
+    }
+
+    let data_export = DataExport {
+        id: svc
+            .id_generator
+            .next_string()
+            .map_err(|e| Error::internal_with_details(e.to_string()))?,
+        user_id: user.id.clone(),
+        status: DataExportStatus::Pending,
+        file_size: 0,
+        error: String::new(),
+        create_time: now,
+        update_time: now,
+        expire_time: 0,
+    };
+    svc.data_export_repo.insert(&data_export).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::DataExportRequested,
+        Some(&user.id),
+        &user.account,
+        "",
+    )
+    .await;
+
+    Ok(Response::new(RequestDataExportResponse {
+        export: Some(export::to_message(svc, data_export)),
+    }))
+}
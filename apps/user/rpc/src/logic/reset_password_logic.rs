@@ -0,0 +1,76 @@
+use crate::error::Error;
+use crate::pb::user::{ResetPasswordRequest, ResetPasswordResponse, SecurityEventType};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn reset_password_logic(
+    svc: &ServiceContext,
+    request: Request<ResetPasswordRequest>,
+) -> Result<Response<ResetPasswordResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!("reset password, email: {}", req.email);
+
+    if req.email.is_empty() {
+        return Err(Status::from(Error::invalid_email("email is empty")));
+    }
+    if req.password.is_empty() {
+        return Err(Status::from(Error::invalid_password("password is empty")));
+    }
+
+    // 验证码错误次数过多时锁定, 防止暴力猜测
+    let lockout_key = format!("password_reset_code:{}", req.email);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let code = match svc.cache.get_password_reset_code(&req.email).await {
+        Ok(code) => code,
+        Err(_) => {
+            return Err(Status::from(Error::invalid_code("code expired")));
+        }
+    };
+
+    if code != req.code {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_code("code mismatch")));
+    }
+
+    // 验证码只能使用一次
+    svc.cache.delete_password_reset_code(&req.email).await?;
+    limiter::clear_failures(svc, &lockout_key).await?;
+
+    let Some(user) = svc.user_repo.find_by_email(&req.email).await? else {
+        return Err(Status::from(Error::invalid_code("code expired")));
+    };
+
+    let encoded_password = utils::hash_password(&svc.config.password, req.password.as_bytes())?;
+    svc.user_repo
+        .update_password(&user.id, &encoded_password)
+        .await?;
+
+    // 重置密码后注销所有已登录的会话
+    svc.cache.revoke_user_sessions(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::PasswordReset,
+        Some(&user.id),
+        &req.email,
+        "",
+    )
+    .await;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::SessionsRevoked,
+        Some(&user.id),
+        &req.email,
+        "password reset",
+    )
+    .await;
+
+    Ok(Response::new(ResetPasswordResponse {}))
+}
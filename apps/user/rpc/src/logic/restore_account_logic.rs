@@ -0,0 +1,86 @@
+use crate::error::Error;
+use crate::pb::user::{RestoreAccountRequest, RestoreAccountResponse, SecurityEventType};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn restore_account_logic(
+    svc: &ServiceContext,
+    request: Request<RestoreAccountRequest>,
+) -> Result<Response<RestoreAccountResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!(
+        "restore account, account: {}, ip: {}",
+        req.account, client.ip
+    );
+
+    // 与登录一样做防爆破
+    let config = &svc.config.rate_limit;
+    let lockout_key = format!("restore:{}", req.account);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+    limiter::check_rate_limit(
+        svc,
+        &format!("login:ip:{}", client.ip),
+        config.login_per_ip,
+        config.login_window,
+    )
+    .await?;
+
+    let Some(deleted) = svc.user_repo.find_deleted_by_account(&req.account).await? else {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_account_or_password(
+            "账号或密码错误",
+        )));
+    };
+    let user = deleted.user;
+
+    if !utils::verify_password(req.password.as_bytes(), &user.password)? {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Status::from(Error::invalid_account_or_password(
+            "账号或密码错误",
+        )));
+    }
+    limiter::clear_failures(svc, &lockout_key).await?;
+
+    // 超过宽限期的账号等待清理, 不能再恢复
+    let grace_period = svc.config.account.deactivation_grace_period as i64 * 1000;
+    if deleted.delete_time + grace_period <= chrono::Utc::now().timestamp_millis() {
+        return Err(Status::from(Error::user_not_found(
+            "deactivation grace period expired",
+        )));
+    }
+
+    // 注销期间账号或邮箱可能已被其他用户注册
+    if svc
+        .user_repo
+        .find_by_account(&user.account)
+        .await?
+        .is_some()
+    {
+        return Err(Status::from(Error::invalid_account(
+            "account already exists",
+        )));
+    }
+    if let Some(email) = user.email.as_deref().filter(|email| !email.is_empty()) {
+        if svc.user_repo.find_by_email(email).await?.is_some() {
+            return Err(Status::from(Error::invalid_email("email already exists")));
+        }
+    }
+
+    svc.user_repo.restore(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::AccountRestored,
+        Some(&user.id),
+        &user.account,
+        "",
+    )
+    .await;
+
+    Ok(Response::new(RestoreAccountResponse { user_id: user.id }))
+}
@@ -0,0 +1,39 @@
+use crate::error::Error;
+use crate::pb::user::{
+    RevokeUserSessionsRequest, RevokeUserSessionsResponse, SecurityEventType, UserRole,
+};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn revoke_user_sessions_logic(
+    svc: &ServiceContext,
+    request: Request<RevokeUserSessionsRequest>,
+) -> Result<Response<RevokeUserSessionsResponse>, Status> {
+    let operator = jwt::authorize(svc, &request, &[UserRole::Admin, UserRole::Support]).await?;
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!(
+        "revoke user sessions, operator: {}, user_id: {}",
+        operator.id, req.user_id
+    );
+
+    let Some(user) = svc.user_repo.find_by_id(&req.user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+
+    svc.cache.revoke_user_sessions(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::SessionsRevoked,
+        Some(&user.id),
+        &user.account,
+        "revoked by admin",
+    )
+    .await;
+
+    Ok(Response::new(RevokeUserSessionsResponse {}))
+}
@@ -0,0 +1,118 @@
+use crate::error::Error;
+use crate::pb::user::{SearchUsersRequest, SearchUsersResponse, User, UserView};
+use crate::repo::SearchCursor;
+use crate::service_context::ServiceContext;
+use crate::utils::view::{self, Viewer};
+use std::collections::HashSet;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+const MAX_KEYWORD_LEN: usize = 64;
+
+pub async fn search_users_logic(
+    svc: &ServiceContext,
+    request: Request<SearchUsersRequest>,
+) -> Result<Response<SearchUsersResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let viewer = view::viewer(svc, &request).await?;
+    let req = request.into_inner();
+
+    let keyword = req.keyword.trim();
+    let len = keyword.chars().count();
+    if len == 0 || len > MAX_KEYWORD_LEN {
+        return Err(Error::invalid_argument("invalid keyword").into());
+    }
+    let limit = match req.limit {
+        n if n <= 0 => DEFAULT_LIMIT,
+        n => (n as i64).min(MAX_LIMIT),
+    };
+    let cursor = if req.cursor.is_empty() {
+        None
+    } else {
+        Some(decode_cursor(&req.cursor)?)
+    };
+
+    let mut users = svc.user_repo.search(keyword, cursor, limit).await?;
+
+    // 返回满页时才有下一页
+    let next_cursor = match users.last() {
+        Some(last) if users.len() as i64 == limit => encode_cursor(&SearchCursor {
+            create_time: last.create_time,
+            id: last.id.clone(),
+        }),
+        _ => String::new(),
+    };
+    // 游标按过滤前的结果计算, 过滤后不满一页时仍然可能有下一页
+    hide_unsearchable_accounts(svc, viewer.as_ref(), &mut users, keyword).await?;
+    let users = view::filter_users(svc, viewer.as_ref(), users, UserView::Full, None).await?;
+
+    Ok(Response::new(SearchUsersResponse { users, next_cursor }))
+}
+
+/// 关闭了按账号查找的用户, 只有名称包含关键字时才返回, 避免通过账号搜索到
+async fn hide_unsearchable_accounts(
+    svc: &ServiceContext,
+    viewer: Option<&Viewer>,
+    users: &mut Vec<User>,
+    keyword: &str,
+) -> Result<(), Error> {
+    let ids: Vec<String> = users
+        .iter()
+        .filter(|user| !viewer.is_some_and(|viewer| viewer.bypasses_privacy(&user.id)))
+        .map(|user| user.id.clone())
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let hidden: HashSet<String> = svc
+        .privacy_repo
+        .find_by_users(&ids)
+        .await?
+        .into_iter()
+        .filter(|privacy| !privacy.searchable_by_account)
+        .map(|privacy| privacy.user_id)
+        .collect();
+
+    let keyword = keyword.to_lowercase();
+    users.retain(|user| !hidden.contains(&user.id) || user.name.to_lowercase().contains(&keyword));
+    Ok(())
+}
+
+/// 游标格式为`{create_time}_{id}`, 对调用方不透明
+pub(crate) fn encode_cursor(cursor: &SearchCursor) -> String {
+    format!("{}_{}", cursor.create_time, cursor.id)
+}
+
+pub(crate) fn decode_cursor(cursor: &str) -> Result<SearchCursor, Error> {
+    cursor
+        .split_once('_')
+        .and_then(|(create_time, id)| {
+            let create_time = create_time.parse().ok()?;
+            (!id.is_empty()).then(|| SearchCursor {
+                create_time,
+                id: id.to_string(),
+            })
+        })
+        .ok_or_else(|| Error::invalid_argument("invalid cursor"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        // id本身可能包含下划线
+        let cursor = SearchCursor {
+            create_time: 1700000000000,
+            id: "V1_a-b_c".to_string(),
+        };
+        assert_eq!(decode_cursor(&encode_cursor(&cursor)).unwrap(), cursor);
+
+        assert!(decode_cursor("abc").is_err());
+        assert!(decode_cursor("x_id").is_err());
+        assert!(decode_cursor("123_").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::Error;
+use crate::pb::user::{
+    SecurityEventType, SendPasswordResetCodeRequest, SendPasswordResetCodeResponse,
+};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn send_password_reset_code_logic(
+    svc: &ServiceContext,
+    request: Request<SendPasswordResetCodeRequest>,
+) -> Result<Response<SendPasswordResetCodeResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!("request: {:?}, ip: {}", req, client.ip);
+
+    if req.email.is_empty() {
+        return Err(Status::from(Error::invalid_email("email is empty")));
+    }
+
+    // 按邮箱和IP限流
+    let config = &svc.config.rate_limit;
+    for (key, limit) in [
+        (
+            format!("password_reset_code:email:{}", req.email),
+            config.send_code_per_target,
+        ),
+        (
+            format!("password_reset_code:ip:{}", client.ip),
+            config.send_code_per_ip,
+        ),
+    ] {
+        limiter::check_rate_limit(svc, &key, limit, config.send_code_window).await?;
+    }
+
+    // 邮箱未注册时同样返回成功, 避免泄露邮箱是否已注册
+    let Some(user) = svc.user_repo.find_by_email(&req.email).await? else {
+        return Ok(Response::new(SendPasswordResetCodeResponse {}));
+    };
+
+    let code = utils::gen_verify_code(config.code_length);
+    svc.cache
+        .save_password_reset_code(&req.email, &code)
+        .await?;
+    svc.mail_sender
+        .send_code(&req.email, "password_reset", &code)
+        .await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::CodeRequested,
+        Some(&user.id),
+        &req.email,
+        "password reset",
+    )
+    .await;
+
+    Ok(Response::new(SendPasswordResetCodeResponse {}))
+}
@@ -1,7 +1,9 @@
 use crate::error::Error;
-use crate::pb::user::{SendRegisterCodeRequest, SendRegisterCodeResponse};
+use crate::pb::user::{SecurityEventType, SendRegisterCodeRequest, SendRegisterCodeResponse};
 use crate::service_context::ServiceContext;
-use rand::Rng;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
 use tonic::{Request, Response, Status};
 use tracing::info;
 
@@ -9,8 +11,9 @@ pub async fn send_register_code_logic(
     svc: &ServiceContext,
     request: Request<SendRegisterCodeRequest>,
 ) -> Result<Response<SendRegisterCodeResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
     let req = request.into_inner();
-    info!("request: {:?}", req);
+    info!("request: {:?}, ip: {}", req, client.ip);
 
     if req.account.is_empty() {
         return Err(Status::from(Error::invalid_account("account is empty")));
@@ -20,24 +23,42 @@ pub async fn send_register_code_logic(
         return Err(Status::from(Error::invalid_account("email is empty")));
     }
 
-    // 生成验证码,存入缓存
-    let mut code = svc
-        .cache
-        .get_user_register_code(&req.account)
-        .await
-        .unwrap_or_default();
-    if code.is_empty() {
-        let num = {
-            let mut rng = rand::thread_rng();
-            rng.gen_range(1000..5000)
-        };
-        code = num.to_string();
-        svc.cache
-            .save_user_register_code(&req.account, &code)
-            .await?;
+    // 按账号, 邮箱和IP限流
+    let config = &svc.config.rate_limit;
+    for (key, limit) in [
+        (
+            format!("register_code:account:{}", req.account),
+            config.send_code_per_target,
+        ),
+        (
+            format!("register_code:email:{}", req.email),
+            config.send_code_per_target,
+        ),
+        (
+            format!("register_code:ip:{}", client.ip),
+            config.send_code_per_ip,
+        ),
+    ] {
+        limiter::check_rate_limit(svc, &key, limit, config.send_code_window).await?;
     }
 
-    // todo: 发送验证码到邮箱
+    // 生成新的验证码并覆盖旧的验证码
+    let code = utils::gen_verify_code(config.code_length);
+    svc.cache
+        .save_user_register_code(&req.account, &code)
+        .await?;
+    svc.mail_sender
+        .send_code(&req.email, "register", &code)
+        .await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::CodeRequested,
+        None,
+        &req.account,
+        "register",
+    )
+    .await;
 
-    Ok(Response::new(SendRegisterCodeResponse { code }))
+    Ok(Response::new(SendRegisterCodeResponse {}))
 }
@@ -0,0 +1,101 @@
+use crate::error::Error;
+use crate::pb::user::{SecurityEventType, SendSmsCodeRequest, SendSmsCodeResponse, SmsCodeScene};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use crate::utils::limiter;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn send_sms_code_logic(
+    svc: &ServiceContext,
+    request: Request<SendSmsCodeRequest>,
+) -> Result<Response<SendSmsCodeResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!("request: {:?}, ip: {}", req, client.ip);
+
+    let scene =
+        SmsCodeScene::try_from(req.scene).map_err(|_| Error::invalid_argument("invalid scene"))?;
+    let phone = utils::normalize_phone(&req.phone, &svc.config.sms.default_country_code)?;
+
+    // 按手机号和IP限流
+    let config = &svc.config.rate_limit;
+    for (key, limit) in [
+        (
+            format!("sms_code:phone:{}", phone),
+            config.send_code_per_target,
+        ),
+        (
+            format!("sms_code:ip:{}", client.ip),
+            config.send_code_per_ip,
+        ),
+    ] {
+        limiter::check_rate_limit(svc, &key, limit, config.send_code_window).await?;
+    }
+
+    // 注册时手机号必须未注册, 登录时必须已注册, 否则不发送短信,
+    // 统一返回成功, 避免泄露手机号是否已注册
+    let user = svc.user_repo.find_by_phone(&phone).await?;
+    let should_send = match scene {
+        SmsCodeScene::Register => user.is_none(),
+        SmsCodeScene::Login => user.is_some(),
+    };
+    if !should_send {
+        info!(
+            "skip sending sms code, scene: {:?}, phone: {}",
+            scene, phone
+        );
+        return Ok(Response::new(SendSmsCodeResponse {}));
+    }
+
+    let code = utils::gen_verify_code(config.code_length);
+    svc.cache
+        .save_sms_code(sms_code_scene(scene), &phone, &code)
+        .await?;
+    svc.sms_sender.send_code(&phone, &code).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::CodeRequested,
+        user.as_ref().map(|user| user.id.as_str()),
+        &phone,
+        sms_code_scene(scene),
+    )
+    .await;
+
+    Ok(Response::new(SendSmsCodeResponse {}))
+}
+
+/// 验证码在缓存中的用途标识
+pub(crate) fn sms_code_scene(scene: SmsCodeScene) -> &'static str {
+    match scene {
+        SmsCodeScene::Register => "register",
+        SmsCodeScene::Login => "login",
+    }
+}
+
+/// 校验短信验证码, 错误次数过多时锁定, 校验通过后删除验证码
+pub(crate) async fn verify_sms_code(
+    svc: &ServiceContext,
+    scene: SmsCodeScene,
+    phone: &str,
+    code: &str,
+) -> Result<(), Error> {
+    let scene = sms_code_scene(scene);
+    let lockout_key = format!("sms_code:{}:{}", scene, phone);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let Ok(expected) = svc.cache.get_sms_code(scene, phone).await else {
+        return Err(Error::invalid_code("code expired"));
+    };
+    if expected != code {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Error::invalid_code("code mismatch"));
+    }
+
+    // 验证码只能使用一次
+    svc.cache.delete_sms_code(scene, phone).await?;
+    limiter::clear_failures(svc, &lockout_key).await?;
+    Ok(())
+}
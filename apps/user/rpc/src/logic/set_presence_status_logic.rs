@@ -0,0 +1,26 @@
+use crate::error::Error;
+use crate::pb::user::{PresenceStatus, SetPresenceStatusRequest, SetPresenceStatusResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn set_presence_status_logic(
+    svc: &ServiceContext,
+    request: Request<SetPresenceStatusRequest>,
+) -> Result<Response<SetPresenceStatusResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    // 离线由心跳超时或设备下线决定, 不能手动设置
+    let status = match PresenceStatus::try_from(req.status) {
+        Ok(PresenceStatus::Offline) | Err(_) => {
+            return Err(Status::from(Error::invalid_argument("invalid status")));
+        }
+        Ok(status) => status,
+    };
+    svc.cache.set_presence_status(&user_id, status).await?;
+
+    Ok(Response::new(SetPresenceStatusResponse {}))
+}
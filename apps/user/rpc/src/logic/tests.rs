@@ -0,0 +1,1148 @@
+//! 使用内存实现的ServiceContext测试完整的业务流程, 不依赖外部服务
+
+use crate::config::{Config, PasswordConfig};
+use crate::error::Error;
+use crate::export;
+use crate::friend::FriendChecker;
+use crate::job::data_export_job;
+use crate::logic::*;
+use crate::mail::mock::MockMailSender;
+use crate::pb::user::{
+    BatchGetUsersRequest, BlockUserRequest, CanSendFriendRequestRequest, ChangePasswordRequest,
+    ConfirmTotpRequest, DataExportStatus, DeactivateAccountRequest, DeviceOfflineRequest,
+    DisableTotpRequest, DownloadDataExportRequest, EnrollTotpRequest, FindUserRequest,
+    FriendRequestPolicy, GetDataExportRequest, GetPrivacySettingsRequest, HeartbeatRequest,
+    IsBlockedRequest, ListBlockedUsersRequest, ListSecurityEventsRequest, LoginRequest,
+    PhoneLoginRequest, PhoneRegisterRequest, PresenceStatus, PrivacyScope, PrivacySettings,
+    RegisterRequest, RequestDataExportRequest, ResetPasswordRequest, SecurityEventType,
+    SendPasswordResetCodeRequest, SendRegisterCodeRequest, SendSmsCodeRequest, SmsCodeScene,
+    UnblockUserRequest, UpdatePrivacySettingsRequest, UpdateUserProfileRequest, User,
+    UserEventType, UserRole, UserView, WatchUsersRequest,
+};
+use crate::repo::ProfileField;
+use crate::service_context::ServiceContext;
+use crate::utils::{jwt, totp};
+use async_trait::async_trait;
+use common::LoadableConfig;
+use prost_types::FieldMask;
+use std::any::Any;
+use std::collections::HashSet;
+use std::io::Read;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+use tonic::{Code, Request};
+
+pub(crate) fn test_context() -> ServiceContext {
+    let mut config = Config::load("etc/user.yml");
+    // 降低哈希成本, 加快测试
+    config.password = PasswordConfig {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+    ServiceContext::in_memory(config)
+}
+
+async fn register(svc: &ServiceContext, account: &str, email: &str, password: &str) {
+    send_register_code_logic(
+        svc,
+        Request::new(SendRegisterCodeRequest {
+            account: account.to_string(),
+            email: email.to_string(),
+        }),
+    )
+    .await
+    .unwrap();
+    let code = mail_code(svc, email).unwrap();
+
+    register_logic(
+        svc,
+        Request::new(RegisterRequest {
+            name: account.to_string(),
+            account: account.to_string(),
+            password: password.to_string(),
+            email: email.to_string(),
+            code,
+            avatar: String::new(),
+        }),
+    )
+    .await
+    .unwrap();
+}
+
+/// 读取MockMailSender最近一次发送到邮箱的验证码
+fn mail_code(svc: &ServiceContext, email: &str) -> Option<String> {
+    let sender: &dyn Any = svc.mail_sender.as_ref();
+    sender
+        .downcast_ref::<MockMailSender>()
+        .unwrap()
+        .last_code(email)
+}
+
+fn with_token<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert(
+        jwt::AUTHORIZATION_METADATA_KEY,
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    request
+}
+
+/// 模拟拦截器校验token后放入claims, 直接调用logic时使用
+fn as_user<T>(message: T, user_id: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .extensions_mut()
+        .insert(jwt::Claims::new(user_id.to_string(), UserRole::User));
+    request
+}
+
+async fn login(
+    svc: &ServiceContext,
+    account: &str,
+    password: &str,
+) -> Result<crate::pb::user::LoginResponse, tonic::Status> {
+    login_logic(
+        svc,
+        Request::new(LoginRequest {
+            account: account.to_string(),
+            password: password.to_string(),
+        }),
+    )
+    .await
+    .map(|response| response.into_inner())
+}
+
+#[tokio::test]
+async fn test_register_login_and_find() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+
+    // 账号和邮箱不能重复注册
+    let result = register_logic(
+        &svc,
+        Request::new(RegisterRequest {
+            account: "lucas".to_string(),
+            email: "other@example.com".to_string(),
+            ..Default::default()
+        }),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let response = login(&svc, "lucas", "password").await.unwrap();
+    assert!(!response.second_factor_required);
+    let claims = jwt::verify_token(&svc, &response.token).await.unwrap();
+    assert_eq!(claims.user_id, response.user_id);
+    assert_eq!(claims.role, "user");
+
+    let find_request = FindUserRequest {
+        user_id: vec![response.user_id.clone(), response.user_id.clone()],
+        account: Some("lucas".to_string()),
+        ..Default::default()
+    };
+    let users = find_user_logic(&svc, with_token(find_request.clone(), &response.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].email.as_deref(), Some("lucas@example.com"));
+    assert!(users[0].password.is_empty());
+
+    // 未登录只能看到公开名片
+    let users = find_user_logic(&svc, Request::new(find_request))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users[0].name, "lucas");
+    assert_eq!(users[0].email, None);
+
+    // 登录成功记录到安全事件, 只能查看自己的
+    let events_request = ListSecurityEventsRequest {
+        user_id: response.user_id.clone(),
+        ..Default::default()
+    };
+    let status = list_security_events_logic(&svc, Request::new(events_request.clone()))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let status = list_security_events_logic(&svc, as_user(events_request.clone(), "other"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    let events = list_security_events_logic(&svc, as_user(events_request, &response.user_id))
+        .await
+        .unwrap()
+        .into_inner()
+        .events;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, SecurityEventType::LoginSuccess as i32);
+    assert_eq!(events[0].reason, "password");
+}
+
+#[derive(Debug)]
+struct FixedFriendChecker(Vec<(String, String)>);
+
+#[async_trait]
+impl FriendChecker for FixedFriendChecker {
+    async fn filter_friends(
+        &self,
+        user_id: &str,
+        candidates: &[String],
+    ) -> Result<HashSet<String>, Error> {
+        let friends = candidates
+            .iter()
+            .filter(|candidate| {
+                self.0.iter().any(|(a, b)| {
+                    (a == user_id && b == *candidate) || (b == user_id && a == *candidate)
+                })
+            })
+            .cloned()
+            .collect();
+        Ok(friends)
+    }
+}
+
+#[tokio::test]
+async fn test_batch_get_users_views() {
+    let svc = test_context();
+    for account in ["lucas", "luna", "tom"] {
+        register(
+            &svc,
+            account,
+            &format!("{}@example.com", account),
+            "password",
+        )
+        .await;
+    }
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+    let tom = login(&svc, "tom", "password").await.unwrap();
+    for user_id in [&luna.user_id, &tom.user_id] {
+        let profile = User {
+            age: 20,
+            ..Default::default()
+        };
+        svc.user_repo
+            .update_profile(user_id, &profile, &[ProfileField::Age])
+            .await
+            .unwrap();
+    }
+    let svc = ServiceContext {
+        friend_checker: Box::new(FixedFriendChecker(vec![(
+            lucas.user_id.clone(),
+            luna.user_id.clone(),
+        )])),
+        ..svc
+    };
+
+    let request = |token: &str, view: UserView| {
+        let mut request = BatchGetUsersRequest {
+            user_ids: vec![
+                tom.user_id.clone(),
+                luna.user_id.clone(),
+                lucas.user_id.clone(),
+                "nobody".to_string(),
+            ],
+            ..Default::default()
+        };
+        request.set_view(view);
+        with_token(request, token)
+    };
+    let users = batch_get_users_logic(&svc, request(&lucas.token, UserView::Full))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    // 顺序与请求一致, 不存在的用户不返回
+    let ids: Vec<&str> = users.iter().map(|user| user.id.as_str()).collect();
+    assert_eq!(ids, vec![&tom.user_id, &luna.user_id, &lucas.user_id]);
+    // 陌生人只能看到公开名片, 好友看不到邮箱, 本人可以看到全部字段
+    assert_eq!(users[0].name, "tom");
+    assert_eq!(users[0].age, 0);
+    assert_eq!(users[1].age, 20);
+    assert_eq!(users[1].email, None);
+    assert_eq!(users[2].email.as_deref(), Some("lucas@example.com"));
+    assert!(users.iter().all(|user| user.password.is_empty()));
+
+    // 期望的视图低于权限时按期望的视图返回
+    let users = batch_get_users_logic(&svc, request(&lucas.token, UserView::Public))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users[2].email, None);
+
+    // 管理员可以看到全部字段
+    let admin = User {
+        id: "admin".to_string(),
+        account: "admin".to_string(),
+        role: UserRole::Admin as i32,
+        ..Default::default()
+    };
+    svc.user_repo.insert(admin.clone()).await.unwrap();
+    let admin_token = jwt::gen_token(&admin, &svc.config.jwt.secret)
+        .unwrap()
+        .token;
+    let users = batch_get_users_logic(&svc, request(&admin_token, UserView::Full))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users[1].email.as_deref(), Some("luna@example.com"));
+
+    // 不能读取密码
+    let mut request = request(&lucas.token, UserView::Full);
+    request.get_mut().read_mask = Some(FieldMask {
+        paths: vec!["password".to_string()],
+    });
+    let status = batch_get_users_logic(&svc, request).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_privacy_settings() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+    let profile = User {
+        signature: "hello".to_string(),
+        ..Default::default()
+    };
+    svc.user_repo
+        .update_profile(&lucas.user_id, &profile, &[ProfileField::Signature])
+        .await
+        .unwrap();
+
+    // 不能查看其他用户的隐私设置
+    let get_request = GetPrivacySettingsRequest {
+        user_id: lucas.user_id.clone(),
+    };
+    let status = get_privacy_settings_logic(&svc, as_user(get_request.clone(), &luna.user_id))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    let settings = get_privacy_settings_logic(&svc, as_user(get_request, &lucas.user_id))
+        .await
+        .unwrap()
+        .into_inner()
+        .settings
+        .unwrap();
+    assert!(settings.searchable_by_email);
+    assert_eq!(
+        settings.friend_request,
+        FriendRequestPolicy::Everyone as i32
+    );
+
+    let update_request = UpdatePrivacySettingsRequest {
+        user_id: lucas.user_id.clone(),
+        settings: Some(PrivacySettings {
+            searchable_by_email: false,
+            profile_visibility: PrivacyScope::Friends as i32,
+            friend_request: FriendRequestPolicy::Nobody as i32,
+            ..Default::default()
+        }),
+        update_mask: Some(FieldMask {
+            paths: vec![
+                "searchable_by_email".to_string(),
+                "profile_visibility".to_string(),
+                "friend_request".to_string(),
+            ],
+        }),
+    };
+    // 不能修改其他用户的隐私设置
+    let status =
+        update_privacy_settings_logic(&svc, as_user(update_request.clone(), &luna.user_id))
+            .await
+            .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    update_privacy_settings_logic(&svc, as_user(update_request, &lucas.user_id))
+        .await
+        .unwrap();
+
+    // 关闭邮箱查找后其他人查不到, 本人仍然可以
+    let find_request = FindUserRequest {
+        email: Some("lucas@example.com".to_string()),
+        ..Default::default()
+    };
+    let users = find_user_logic(&svc, with_token(find_request.clone(), &luna.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert!(users.is_empty());
+    let users = find_user_logic(&svc, with_token(find_request, &lucas.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].signature, "hello");
+
+    // 签名只对好友可见
+    let users = batch_get_users_logic(
+        &svc,
+        with_token(
+            BatchGetUsersRequest {
+                user_ids: vec![lucas.user_id.clone()],
+                ..Default::default()
+            },
+            &luna.token,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner()
+    .users;
+    assert_eq!(users[0].name, "lucas");
+    assert_eq!(users[0].signature, "");
+
+    let allowed = can_send_friend_request_logic(
+        &svc,
+        as_user(
+            CanSendFriendRequestRequest {
+                from_user_id: luna.user_id.clone(),
+                to_user_id: lucas.user_id.clone(),
+            },
+            &luna.user_id,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner()
+    .allowed;
+    assert!(!allowed);
+}
+
+#[tokio::test]
+async fn test_blocklist() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+    let block_request = |user_id: &str, blocked_user_id: &str| BlockUserRequest {
+        user_id: user_id.to_string(),
+        blocked_user_id: blocked_user_id.to_string(),
+    };
+
+    // 只能以自己的身份拉黑
+    let result = block_user_logic(
+        &svc,
+        as_user(block_request(&lucas.user_id, &luna.user_id), &luna.user_id),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::PermissionDenied);
+    let result = block_user_logic(
+        &svc,
+        as_user(
+            block_request(&lucas.user_id, &lucas.user_id),
+            &lucas.user_id,
+        ),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+    let result = block_user_logic(&svc, as_user(block_request("", "nobody"), &lucas.user_id)).await;
+    assert_eq!(result.unwrap_err().code(), Code::NotFound);
+
+    // 重复拉黑不报错
+    for _ in 0..2 {
+        block_user_logic(
+            &svc,
+            as_user(block_request(&lucas.user_id, &luna.user_id), &lucas.user_id),
+        )
+        .await
+        .unwrap();
+    }
+    let list_request = ListBlockedUsersRequest {
+        user_id: lucas.user_id.clone(),
+        ..Default::default()
+    };
+    let status = list_blocked_users_logic(&svc, as_user(list_request.clone(), &luna.user_id))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    let response = list_blocked_users_logic(&svc, as_user(list_request, &lucas.user_id))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.users.len(), 1);
+    assert_eq!(response.next_cursor, 0);
+    let user = response.users[0].user.as_ref().unwrap();
+    assert_eq!(user.name, "luna");
+    assert_eq!(user.email, None);
+
+    // 不能查询其他用户之间的关系
+    let status = is_blocked_logic(
+        &svc,
+        as_user(
+            IsBlockedRequest {
+                user_id: lucas.user_id.clone(),
+                other_user_id: luna.user_id.clone(),
+            },
+            &luna.user_id,
+        ),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    let response = is_blocked_logic(
+        &svc,
+        as_user(
+            IsBlockedRequest {
+                user_id: luna.user_id.clone(),
+                other_user_id: lucas.user_id.clone(),
+            },
+            &luna.user_id,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner();
+    assert!(!response.blocking);
+    assert!(response.blocked_by);
+
+    // 双方互相查找不到, 也不能发送好友申请
+    let find = |account: &str, token: &str| {
+        with_token(
+            FindUserRequest {
+                account: Some(account.to_string()),
+                ..Default::default()
+            },
+            token,
+        )
+    };
+    let users = find_user_logic(&svc, find("lucas", &luna.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert!(users.is_empty());
+    let users = find_user_logic(&svc, find("luna", &lucas.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert!(users.is_empty());
+    let allowed = can_send_friend_request_logic(
+        &svc,
+        as_user(
+            CanSendFriendRequestRequest {
+                from_user_id: luna.user_id.clone(),
+                to_user_id: lucas.user_id.clone(),
+            },
+            &luna.user_id,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner()
+    .allowed;
+    assert!(!allowed);
+
+    unblock_user_logic(
+        &svc,
+        as_user(
+            UnblockUserRequest {
+                user_id: lucas.user_id.clone(),
+                blocked_user_id: luna.user_id.clone(),
+            },
+            &lucas.user_id,
+        ),
+    )
+    .await
+    .unwrap();
+    let users = find_user_logic(&svc, find("lucas", &luna.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .users;
+    assert_eq!(users.len(), 1);
+}
+
+#[tokio::test]
+async fn test_data_export() {
+    let mut svc = test_context();
+    let dir = std::env::temp_dir().join(nanoid::nanoid!());
+    svc.config.export.dir = dir.to_string_lossy().to_string();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let request_export = || {
+        request_data_export_logic(
+            &svc,
+            as_user(
+                RequestDataExportRequest {
+                    user_id: lucas.user_id.clone(),
+                },
+                &lucas.user_id,
+            ),
+        )
+    };
+    let get_export = |caller: &str, export_id: &str| {
+        get_data_export_logic(
+            &svc,
+            as_user(
+                GetDataExportRequest {
+                    user_id: String::new(),
+                    export_id: export_id.to_string(),
+                },
+                caller,
+            ),
+        )
+    };
+    let download = |export_id: &str, download_token: &str| {
+        download_data_export_logic(
+            &svc,
+            Request::new(DownloadDataExportRequest {
+                export_id: export_id.to_string(),
+                download_token: download_token.to_string(),
+            }),
+        )
+    };
+
+    let export = request_export().await.unwrap().into_inner().export.unwrap();
+    assert_eq!(export.status, DataExportStatus::Pending as i32);
+    assert!(export.download_token.is_empty());
+    // 进行中时返回已有的导出
+    let pending = request_export().await.unwrap().into_inner().export.unwrap();
+    assert_eq!(pending.id, export.id);
+
+    data_export_job::process_exports(&svc).await;
+    let export = get_export(&lucas.user_id, &export.id)
+        .await
+        .unwrap()
+        .into_inner()
+        .export
+        .unwrap();
+    assert_eq!(export.status, DataExportStatus::Ready as i32);
+    assert!(!export.download_token.is_empty());
+    // 其他用户查询不到
+    let result = get_export("nobody", &export.id).await;
+    assert_eq!(result.unwrap_err().code(), Code::NotFound);
+    let result = get_data_export_logic(
+        &svc,
+        as_user(
+            GetDataExportRequest {
+                user_id: lucas.user_id.clone(),
+                export_id: export.id.clone(),
+            },
+            "nobody",
+        ),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::PermissionDenied);
+
+    let result = download(&export.id, "invalid").await;
+    assert_eq!(result.unwrap_err().code(), Code::NotFound);
+    let mut stream = download(&export.id, &export.download_token)
+        .await
+        .unwrap()
+        .into_inner();
+    let mut data = vec![];
+    while let Some(chunk) = stream.next().await {
+        data.extend(chunk.unwrap().data);
+    }
+    assert_eq!(data.len() as i64, export.file_size);
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+    let mut read_json = |name: &str| {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        serde_json::from_str::<serde_json::Value>(&content).unwrap()
+    };
+    let profile = read_json("profile.json");
+    assert_eq!(profile["account"], "lucas");
+    assert!(profile.get("password").is_none());
+    let events = read_json("security_events.json");
+    let event_types: Vec<_> = events
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["event_type"].as_str().unwrap())
+        .collect();
+    assert!(event_types.contains(&SecurityEventType::LoginSuccess.as_str_name()));
+    assert!(event_types.contains(&SecurityEventType::DataExportRequested.as_str_name()));
+    assert_eq!(read_json("two_factor.json")["enabled"], false);
+
+    // 超过下载有效期后链接失效, 压缩包被删除
+    svc.data_export_repo
+        .mark_ready(&export.id, export.file_size, 1)
+        .await
+        .unwrap();
+    let token = export::download_token(&svc.config.jwt.secret, &export.id, 1);
+    let result = download(&export.id, &token).await;
+    assert_eq!(result.unwrap_err().code(), Code::PermissionDenied);
+    data_export_job::expire_exports(&svc).await;
+    assert!(!export::archive_path(&svc.config.export, &export.id).exists());
+    let export = get_export(&lucas.user_id, &export.id)
+        .await
+        .unwrap()
+        .into_inner()
+        .export
+        .unwrap();
+    assert_eq!(export.status, DataExportStatus::Expired as i32);
+
+    // 过期后可以重新申请
+    let export = request_export().await.unwrap().into_inner().export.unwrap();
+    assert_eq!(export.status, DataExportStatus::Pending as i32);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_watch_users() {
+    let svc = Arc::new(test_context());
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+
+    let result = watch_users_logic(
+        svc.clone(),
+        with_token(WatchUsersRequest { user_ids: vec![] }, &luna.token),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+
+    let mut stream = watch_users_logic(
+        svc.clone(),
+        with_token(
+            WatchUsersRequest {
+                user_ids: vec![lucas.user_id.clone()],
+            },
+            &luna.token,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner();
+
+    // 未订阅的用户的变化不会推送
+    let heartbeat = |user_id: &str| HeartbeatRequest {
+        user_id: user_id.to_string(),
+        platform: "ios".to_string(),
+        device_id: "d1".to_string(),
+    };
+    // 只能上报自己的心跳
+    let status = heartbeat_logic(&svc, Request::new(heartbeat(&lucas.user_id)))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let status = heartbeat_logic(&svc, as_user(heartbeat(&lucas.user_id), &luna.user_id))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    heartbeat_logic(&svc, as_user(heartbeat(&luna.user_id), &luna.user_id))
+        .await
+        .unwrap();
+    heartbeat_logic(&svc, as_user(heartbeat(""), &lucas.user_id))
+        .await
+        .unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.user_id, lucas.user_id);
+    assert_eq!(event.event_type, UserEventType::PresenceChanged as i32);
+    let presence = event.presence.unwrap();
+    assert_eq!(presence.status, PresenceStatus::Online as i32);
+    assert_eq!(presence.platforms, vec!["ios"]);
+
+    // 资料按调用方与用户的关系裁剪
+    let update_request = UpdateUserProfileRequest {
+        user_id: lucas.user_id.clone(),
+        user: Some(User {
+            name: "Lucas".to_string(),
+            ..Default::default()
+        }),
+        update_mask: Some(FieldMask {
+            paths: vec!["name".to_string()],
+        }),
+    };
+    // 只能修改自己的资料
+    let status = update_user_profile_logic(&svc, as_user(update_request.clone(), &luna.user_id))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    update_user_profile_logic(&svc, as_user(update_request, &lucas.user_id))
+        .await
+        .unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(event.event_type, UserEventType::ProfileUpdated as i32);
+    let user = event.user.unwrap();
+    assert_eq!(user.name, "Lucas");
+    assert_eq!(user.email, None);
+
+    device_offline_logic(
+        &svc,
+        as_user(
+            DeviceOfflineRequest {
+                user_id: lucas.user_id.clone(),
+                platform: "ios".to_string(),
+                device_id: "d1".to_string(),
+            },
+            &lucas.user_id,
+        ),
+    )
+    .await
+    .unwrap();
+    let event = stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        event.presence.unwrap().status,
+        PresenceStatus::Offline as i32
+    );
+
+    // 订阅方的会话被注销后结束订阅
+    svc.cache.revoke_user_sessions(&luna.user_id).await.unwrap();
+    heartbeat_logic(&svc, as_user(heartbeat(""), &lucas.user_id))
+        .await
+        .unwrap();
+    let status = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    assert!(stream.next().await.is_none());
+
+    // 订阅方被封禁后同样结束订阅
+    let mut stream = watch_users_logic(
+        svc.clone(),
+        with_token(
+            WatchUsersRequest {
+                user_ids: vec![luna.user_id.clone()],
+            },
+            &lucas.token,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner();
+    svc.user_repo.ban(&lucas.user_id, "spam", 0).await.unwrap();
+    heartbeat_logic(&svc, as_user(heartbeat(""), &luna.user_id))
+        .await
+        .unwrap();
+    let status = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_register_with_wrong_code() {
+    let svc = test_context();
+    send_register_code_logic(
+        &svc,
+        Request::new(SendRegisterCodeRequest {
+            account: "lucas".to_string(),
+            email: "lucas@example.com".to_string(),
+        }),
+    )
+    .await
+    .unwrap();
+
+    let result = register_logic(
+        &svc,
+        Request::new(RegisterRequest {
+            account: "lucas".to_string(),
+            email: "lucas@example.com".to_string(),
+            password: "password".to_string(),
+            code: "wrong".to_string(),
+            ..Default::default()
+        }),
+    )
+    .await;
+    assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+    assert!(svc
+        .user_repo
+        .find_by_account("lucas")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_login_lockout() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+
+    // 连续失败后锁定, 正确的密码也不能登录
+    for _ in 0..svc.config.rate_limit.max_failed_attempts {
+        let status = login(&svc, "lucas", "wrong").await.unwrap_err();
+        assert_eq!(status.code(), Code::Internal);
+    }
+    let status = login(&svc, "lucas", "password").await.unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+
+    // 不存在的账号同样计入失败
+    assert!(login(&svc, "nobody", "password").await.is_err());
+}
+
+#[tokio::test]
+async fn test_banned_user_can_not_login() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    let user = svc
+        .user_repo
+        .find_by_account("lucas")
+        .await
+        .unwrap()
+        .unwrap();
+
+    svc.user_repo.ban(&user.id, "spam", 0).await.unwrap();
+    let status = login(&svc, "lucas", "password").await.unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    svc.user_repo.unban(&user.id).await.unwrap();
+    assert!(login(&svc, "lucas", "password").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_phone_register_and_login() {
+    let svc = test_context();
+    let phone = "13800138000";
+    let normalized = "+8613800138000";
+
+    send_sms_code_logic(
+        &svc,
+        Request::new(SendSmsCodeRequest {
+            phone: phone.to_string(),
+            scene: SmsCodeScene::Register as i32,
+        }),
+    )
+    .await
+    .unwrap();
+    let code = svc
+        .cache
+        .get_sms_code("register", normalized)
+        .await
+        .unwrap();
+    phone_register_logic(
+        &svc,
+        Request::new(PhoneRegisterRequest {
+            phone: phone.to_string(),
+            code,
+            name: "lucas".to_string(),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+
+    // 同一手机号发送验证码有频率限制
+    let status = send_sms_code_logic(
+        &svc,
+        Request::new(SendSmsCodeRequest {
+            phone: phone.to_string(),
+            scene: SmsCodeScene::Login as i32,
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+
+    // 直接写入登录验证码
+    svc.cache
+        .save_sms_code("login", normalized, "123456")
+        .await
+        .unwrap();
+    let request = || {
+        Request::new(PhoneLoginRequest {
+            phone: phone.to_string(),
+            code: "123456".to_string(),
+        })
+    };
+    let response = phone_login_logic(&svc, request())
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.token.is_empty());
+
+    // 验证码只能使用一次
+    assert!(phone_login_logic(&svc, request()).await.is_err());
+
+    // 没有设置密码时不能修改密码
+    let status = change_password_logic(
+        &svc,
+        as_user(
+            ChangePasswordRequest {
+                new_password: "password".to_string(),
+                ..Default::default()
+            },
+            &response.user_id,
+        ),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.details(), b"password not set");
+}
+
+#[tokio::test]
+async fn test_reset_password() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let send_code = |email: &str| {
+        send_password_reset_code_logic(
+            &svc,
+            Request::new(SendPasswordResetCodeRequest {
+                email: email.to_string(),
+            }),
+        )
+    };
+    let reset = |code: &str| {
+        reset_password_logic(
+            &svc,
+            Request::new(ResetPasswordRequest {
+                email: "lucas@example.com".to_string(),
+                code: code.to_string(),
+                password: "new password".to_string(),
+            }),
+        )
+    };
+
+    // 未注册的邮箱同样返回成功, 但不发送邮件
+    send_code("nobody@example.com").await.unwrap();
+    assert_eq!(mail_code(&svc, "nobody@example.com"), None);
+
+    send_code("lucas@example.com").await.unwrap();
+    let code = mail_code(&svc, "lucas@example.com").unwrap();
+    let status = reset("000").await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    reset(&code).await.unwrap();
+    // 验证码只能使用一次
+    assert!(reset(&code).await.is_err());
+
+    // 重置前签发的token失效, 只能使用新密码登录
+    assert!(jwt::verify_token(&svc, &lucas.token).await.is_err());
+    assert!(login(&svc, "lucas", "password").await.is_err());
+    let lucas = login(&svc, "lucas", "new password").await.unwrap();
+    assert!(jwt::verify_token(&svc, &lucas.token).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_change_password() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+    let request = |user_id: &str, old_password: &str| ChangePasswordRequest {
+        user_id: user_id.to_string(),
+        old_password: old_password.to_string(),
+        new_password: "new-password".to_string(),
+    };
+
+    // 只能修改自己的密码
+    let status = change_password_logic(
+        &svc,
+        as_user(request(&luna.user_id, "password"), &lucas.user_id),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    let response = change_password_logic(
+        &svc,
+        as_user(request(&lucas.user_id, "password"), &lucas.user_id),
+    )
+    .await
+    .unwrap()
+    .into_inner();
+    let claims = jwt::decode_token(&response.token, &svc.config.jwt.secret).unwrap();
+    assert_eq!(claims.user_id, lucas.user_id);
+    assert!(login(&svc, "lucas", "new-password").await.is_ok());
+    assert!(login(&svc, "luna", "password").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_totp() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+
+    // 不能为其他用户绑定
+    let status = enroll_totp_logic(
+        &svc,
+        as_user(
+            EnrollTotpRequest {
+                user_id: luna.user_id.clone(),
+            },
+            &lucas.user_id,
+        ),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    let secret = enroll_totp_logic(&svc, as_user(EnrollTotpRequest::default(), &lucas.user_id))
+        .await
+        .unwrap()
+        .into_inner()
+        .secret;
+    let now = chrono::Utc::now().timestamp();
+    let recovery_codes = confirm_totp_logic(
+        &svc,
+        as_user(
+            ConfirmTotpRequest {
+                code: totp::generate_code(&secret, now).unwrap(),
+                ..Default::default()
+            },
+            &lucas.user_id,
+        ),
+    )
+    .await
+    .unwrap()
+    .into_inner()
+    .recovery_codes;
+
+    // 使用验证码关闭时需要校验密码
+    let disable = |code: &str, password: &str| {
+        as_user(
+            DisableTotpRequest {
+                code: code.to_string(),
+                password: password.to_string(),
+                ..Default::default()
+            },
+            &lucas.user_id,
+        )
+    };
+    let code = totp::generate_code(&secret, now + 30).unwrap();
+    let status = disable_totp_logic(&svc, disable(&code, "wrong-password"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(svc.totp_repo.find(&lucas.user_id).await.unwrap().is_some());
+
+    // 恢复码可以单独使用
+    disable_totp_logic(&svc, disable(&recovery_codes[0], ""))
+        .await
+        .unwrap();
+    assert!(svc.totp_repo.find(&lucas.user_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_deactivate_account() {
+    let svc = test_context();
+    register(&svc, "lucas", "lucas@example.com", "password").await;
+    register(&svc, "luna", "luna@example.com", "password").await;
+    let lucas = login(&svc, "lucas", "password").await.unwrap();
+    let luna = login(&svc, "luna", "password").await.unwrap();
+    let request = |user_id: &str| DeactivateAccountRequest {
+        user_id: user_id.to_string(),
+        password: "password".to_string(),
+    };
+
+    // 只能注销自己的账号
+    let status = deactivate_account_logic(&svc, as_user(request(&luna.user_id), &lucas.user_id))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+    assert!(login(&svc, "luna", "password").await.is_ok());
+
+    deactivate_account_logic(&svc, as_user(request(""), &lucas.user_id))
+        .await
+        .unwrap();
+    assert!(login(&svc, "lucas", "password").await.is_err());
+}
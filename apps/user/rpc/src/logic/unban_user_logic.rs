@@ -0,0 +1,37 @@
+use crate::error::Error;
+use crate::pb::user::{SecurityEventType, UnbanUserRequest, UnbanUserResponse, UserRole};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn unban_user_logic(
+    svc: &ServiceContext,
+    request: Request<UnbanUserRequest>,
+) -> Result<Response<UnbanUserResponse>, Status> {
+    let operator = jwt::authorize(svc, &request, &[UserRole::Admin]).await?;
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+    info!(
+        "unban user, operator: {}, user_id: {}",
+        operator.id, req.user_id
+    );
+
+    let Some(user) = svc.user_repo.find_by_id(&req.user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+
+    svc.user_repo.unban(&user.id).await?;
+    audit::record(
+        svc,
+        &client,
+        SecurityEventType::AccountUnbanned,
+        Some(&user.id),
+        &user.account,
+        "",
+    )
+    .await;
+
+    Ok(Response::new(UnbanUserResponse {}))
+}
@@ -0,0 +1,24 @@
+use crate::error::Error;
+use crate::pb::user::{UnblockUserRequest, UnblockUserResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn unblock_user_logic(
+    svc: &ServiceContext,
+    request: Request<UnblockUserRequest>,
+) -> Result<Response<UnblockUserResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+
+    if req.blocked_user_id.is_empty() {
+        return Err(Error::invalid_argument("blocked_user_id is empty").into());
+    }
+    // 没有拉黑过时同样返回成功
+    svc.block_repo
+        .delete(&user_id, &req.blocked_user_id)
+        .await?;
+    Ok(Response::new(UnblockUserResponse {}))
+}
@@ -0,0 +1,34 @@
+use crate::error::Error;
+use crate::pb::user::{UnlinkExternalIdentityRequest, UnlinkExternalIdentityResponse};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn unlink_external_identity_logic(
+    svc: &ServiceContext,
+    request: Request<UnlinkExternalIdentityRequest>,
+) -> Result<Response<UnlinkExternalIdentityResponse>, Status> {
+    let user_id = jwt::caller_id(&request, &request.get_ref().user_id)?;
+    let req = request.into_inner();
+    info!("request: {:?}", req);
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+    let linked = svc.identity_repo.find_by_user(&user.id).await?;
+    if !linked.iter().any(|link| link.provider == req.provider) {
+        return Err(Status::from(Error::invalid_argument("identity not linked")));
+    }
+
+    // 至少保留一种登录方式
+    let has_other_login = !user.password.is_empty() || user.phone.is_some() || linked.len() > 1;
+    if !has_other_login {
+        return Err(Status::from(Error::invalid_argument(
+            "can not unlink the only login method",
+        )));
+    }
+
+    svc.identity_repo.delete(&user.id, &req.provider).await?;
+    Ok(Response::new(UnlinkExternalIdentityResponse {}))
+}
@@ -0,0 +1,103 @@
+use crate::error::Error;
+use crate::event::{self, UserChangeKind};
+use crate::pb::user::{
+    FriendRequestPolicy, PrivacyScope, PrivacySettings, UpdatePrivacySettingsRequest,
+    UpdatePrivacySettingsResponse,
+};
+use crate::repo::UserPrivacy;
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn update_privacy_settings_logic(
+    svc: &ServiceContext,
+    request: Request<UpdatePrivacySettingsRequest>,
+) -> Result<Response<UpdatePrivacySettingsResponse>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+    let req = request.into_inner();
+
+    let settings = req.settings.unwrap_or_default();
+    let paths = req.update_mask.map(|mask| mask.paths).unwrap_or_default();
+    if paths.is_empty() {
+        return Err(Error::invalid_argument("update_mask is empty").into());
+    }
+    if svc.user_repo.find_by_id(&user_id).await?.is_none() {
+        return Err(Error::user_not_found("user not found").into());
+    }
+
+    let mut privacy = svc.privacy_repo.find(&user_id).await?;
+    apply_update(&mut privacy, &settings, &paths)?;
+    svc.privacy_repo.save(&privacy).await?;
+    // 隐私设置影响其他用户看到的资料
+    event::notify(svc, UserChangeKind::Profile, &user_id).await;
+
+    Ok(Response::new(UpdatePrivacySettingsResponse {
+        settings: Some(privacy.into()),
+    }))
+}
+
+/// 只修改`paths`中列出的字段
+fn apply_update(
+    privacy: &mut UserPrivacy,
+    settings: &PrivacySettings,
+    paths: &[String],
+) -> Result<(), Error> {
+    for path in paths {
+        match path.as_str() {
+            "searchable_by_phone" => privacy.searchable_by_phone = settings.searchable_by_phone,
+            "searchable_by_email" => privacy.searchable_by_email = settings.searchable_by_email,
+            "searchable_by_account" => {
+                privacy.searchable_by_account = settings.searchable_by_account
+            }
+            "profile_visibility" => {
+                privacy.profile_visibility = PrivacyScope::try_from(settings.profile_visibility)
+                    .map_err(|_| Error::invalid_argument("invalid profile_visibility"))?
+            }
+            "friend_request" => {
+                privacy.friend_request = FriendRequestPolicy::try_from(settings.friend_request)
+                    .map_err(|_| Error::invalid_argument("invalid friend_request"))?
+            }
+            _ => {
+                return Err(Error::invalid_argument(format!(
+                    "field `{}` can not be updated",
+                    path
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_update() {
+        let mut privacy = UserPrivacy::new("id");
+        let settings = PrivacySettings {
+            searchable_by_phone: false,
+            searchable_by_email: false,
+            profile_visibility: PrivacyScope::Friends as i32,
+            ..Default::default()
+        };
+        let paths = vec![
+            "searchable_by_phone".to_string(),
+            "profile_visibility".to_string(),
+        ];
+        apply_update(&mut privacy, &settings, &paths).unwrap();
+        assert!(!privacy.searchable_by_phone);
+        // 不在mask中的字段保持不变
+        assert!(privacy.searchable_by_email);
+        assert_eq!(privacy.profile_visibility, PrivacyScope::Friends);
+
+        let invalid = PrivacySettings {
+            friend_request: 100,
+            ..Default::default()
+        };
+        assert!(apply_update(&mut privacy, &invalid, &["friend_request".to_string()]).is_err());
+        assert!(apply_update(&mut privacy, &settings, &["user_id".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::error::Error;
+use crate::event::{self, UserChangeKind};
+use crate::pb::user::{UpdateUserProfileRequest, UpdateUserProfileResponse, User};
+use crate::repo::ProfileField;
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+const MAX_NAME_LEN: usize = 32;
+const MAX_AVATAR_LEN: usize = 1024;
+const MAX_TEXT_LEN: usize = 1024;
+const MAX_AGE: i32 = 150;
+const GENDERS: [&str; 4] = ["", "male", "female", "other"];
+
+pub async fn update_user_profile_logic(
+    svc: &ServiceContext,
+    request: Request<UpdateUserProfileRequest>,
+) -> Result<Response<UpdateUserProfileResponse>, Status> {
+    // 只能修改自己的资料, 管理员可以修改其他用户的资料
+    let user_id = jwt::caller_or_admin(svc, &request, &request.get_ref().user_id).await?;
+    info!("update user profile, user_id: {}", user_id);
+    let req = request.into_inner();
+
+    let user = req.user.unwrap_or_default();
+    let paths = req.update_mask.map(|mask| mask.paths).unwrap_or_default();
+    let fields = parse_update_mask(&paths)?;
+    validate_profile(&user, &fields)?;
+
+    // 缓存由user_repo在更新数据库后删除
+    let mut user = svc
+        .user_repo
+        .update_profile(&user_id, &user, &fields)
+        .await?;
+    event::notify(svc, UserChangeKind::Profile, &user_id).await;
+
+    user.password.clear();
+    Ok(Response::new(UpdateUserProfileResponse {
+        user: Some(user),
+    }))
+}
+
+fn parse_update_mask(paths: &[String]) -> Result<Vec<ProfileField>, Error> {
+    if paths.is_empty() {
+        return Err(Error::invalid_argument("update_mask is empty"));
+    }
+
+    let mut fields = Vec::with_capacity(paths.len());
+    for path in paths {
+        let Some(field) = ProfileField::from_path(path) else {
+            return Err(Error::invalid_argument(format!(
+                "field `{}` can not be updated",
+                path
+            )));
+        };
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    Ok(fields)
+}
+
+fn validate_profile(user: &User, fields: &[ProfileField]) -> Result<(), Error> {
+    let optional_len = |value: &Option<String>| value.as_ref().map_or(0, |v| v.chars().count());
+
+    for field in fields {
+        let valid = match field {
+            ProfileField::Name => {
+                let len = user.name.trim().chars().count();
+                len > 0 && len <= MAX_NAME_LEN
+            }
+            ProfileField::Avatar => user.avatar.len() <= MAX_AVATAR_LEN,
+            ProfileField::Gender => GENDERS.contains(&user.gender.as_str()),
+            ProfileField::Age => (0..=MAX_AGE).contains(&user.age),
+            ProfileField::Signature => user.signature.chars().count() <= MAX_TEXT_LEN,
+            ProfileField::Address => optional_len(&user.address) <= MAX_TEXT_LEN,
+            ProfileField::Region => optional_len(&user.region) <= MAX_TEXT_LEN,
+            // 生日为毫秒时间戳, 不能晚于当前时间
+            ProfileField::Birthday => user
+                .birthday
+                .is_none_or(|birthday| birthday <= chrono::Utc::now().timestamp_millis()),
+        };
+        if !valid {
+            return Err(Error::invalid_argument(format!(
+                "invalid value of field `{}`",
+                field.column()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_update_mask() {
+        let paths = vec!["name".to_string(), "age".to_string(), "name".to_string()];
+        let fields = parse_update_mask(&paths).unwrap();
+        assert_eq!(fields, vec![ProfileField::Name, ProfileField::Age]);
+
+        assert!(parse_update_mask(&[]).is_err());
+        assert!(parse_update_mask(&["password".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        let user = User {
+            name: "lucas".to_string(),
+            gender: "male".to_string(),
+            age: 18,
+            ..Default::default()
+        };
+        let fields = [ProfileField::Name, ProfileField::Gender, ProfileField::Age];
+        assert!(validate_profile(&user, &fields).is_ok());
+
+        let user = User {
+            name: " ".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_profile(&user, &[ProfileField::Name]).is_err());
+
+        let user = User {
+            age: -1,
+            birthday: Some(i64::MAX),
+            ..Default::default()
+        };
+        assert!(validate_profile(&user, &[ProfileField::Age]).is_err());
+        assert!(validate_profile(&user, &[ProfileField::Birthday]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::Error;
+use crate::logic::login_logic::{ensure_not_banned, issue_tokens};
+use crate::pb::user::{LoginResponse, SecurityEventType, VerifySecondFactorRequest};
+use crate::repo::UserTotp;
+use crate::service_context::ServiceContext;
+use crate::utils::limiter;
+use crate::utils::totp;
+use crate::utils::{audit, ClientInfo};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+pub async fn verify_second_factor_logic(
+    svc: &ServiceContext,
+    request: Request<VerifySecondFactorRequest>,
+) -> Result<Response<LoginResponse>, Status> {
+    let client = ClientInfo::from_request(svc, &request);
+    let req = request.into_inner();
+
+    let Some(user_id) = svc.cache.get_login_challenge(&req.challenge_token).await? else {
+        return Err(Status::from(Error::invalid_token("challenge expired")));
+    };
+    info!("verify second factor, user_id: {}", user_id);
+
+    let Some(user) = svc.user_repo.find_by_id(&user_id).await? else {
+        return Err(Status::from(Error::user_not_found("user not found")));
+    };
+    // 挑战有效期内两步验证被关闭时, 不需要再校验
+    if let Some(totp) = svc.totp_repo.find(&user.id).await? {
+        if totp.enabled {
+            if let Err(e) = verify_second_factor_code(svc, &totp, &req.code).await {
+                audit::record(
+                    svc,
+                    &client,
+                    SecurityEventType::LoginFailed,
+                    Some(&user.id),
+                    &user.account,
+                    "second factor mismatch",
+                )
+                .await;
+                return Err(Status::from(e));
+            }
+        }
+    }
+
+    // 挑战有效期内可能被封禁
+    ensure_not_banned(&user)?;
+
+    // 挑战只能使用一次
+    svc.cache
+        .delete_login_challenge(&req.challenge_token)
+        .await?;
+    Ok(Response::new(
+        issue_tokens(svc, &user, &client, "second_factor").await?,
+    ))
+}
+
+/// 校验验证码或恢复码, 错误次数过多时锁定
+///
+/// 验证码的时间步只能使用一次, 恢复码使用后失效
+pub(crate) async fn verify_second_factor_code(
+    svc: &ServiceContext,
+    totp: &UserTotp,
+    code: &str,
+) -> Result<(), Error> {
+    let lockout_key = format!("second_factor:{}", totp.user_id);
+    limiter::ensure_not_locked(svc, &lockout_key).await?;
+
+    let now = chrono::Utc::now().timestamp();
+    let valid = match totp::verify(&totp.secret, code, now, svc.config.two_factor.skew) {
+        Some(step) => svc.totp_repo.use_step(&totp.user_id, step).await?,
+        None => {
+            let hash = totp::hash_recovery_code(code);
+            svc.totp_repo
+                .use_recovery_code(&totp.user_id, &hash)
+                .await?
+        }
+    };
+    if !valid {
+        limiter::record_failure(svc, &lockout_key).await?;
+        return Err(Error::invalid_code("code mismatch"));
+    }
+
+    limiter::clear_failures(svc, &lockout_key).await?;
+    Ok(())
+}
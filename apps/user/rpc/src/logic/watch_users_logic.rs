@@ -0,0 +1,135 @@
+use crate::error::Error;
+use crate::event::{UserChange, UserChangeKind};
+use crate::logic::get_users_presence_logic::hide_invisible;
+use crate::pb::user::{UserEvent, UserEventType, UserView, WatchUsersRequest};
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use crate::utils::view::{self, Viewer};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+/// 每个订阅等待发送的事件数量, 客户端消费过慢时丢弃新事件
+const STREAM_BUFFER_SIZE: usize = 64;
+
+pub async fn watch_users_logic(
+    svc: Arc<ServiceContext>,
+    request: Request<WatchUsersRequest>,
+) -> Result<Response<ReceiverStream<Result<UserEvent, Status>>>, Status> {
+    info!("request: {:?}", request.get_ref());
+    let viewer = view::viewer(&svc, &request).await?;
+    // 订阅期间每个事件发送前重新校验, 会话被注销或账号被封禁后结束订阅
+    let token = viewer
+        .as_ref()
+        .and_then(|_| jwt::bearer_token(&request))
+        .map(str::to_string);
+    let mut user_ids = request.into_inner().user_ids;
+    user_ids.sort();
+    user_ids.dedup();
+    if user_ids.is_empty() {
+        return Err(Status::from(Error::invalid_argument("user_ids is empty")));
+    }
+    if user_ids.len() > svc.config.presence.max_watch_users {
+        return Err(Status::from(Error::invalid_argument("too many user_ids")));
+    }
+
+    // 返回前订阅, 调用方收到响应后发生的变化不会丢失
+    let changes = svc.user_events.subscribe();
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+    tokio::spawn(forward(
+        svc,
+        viewer,
+        token,
+        user_ids.into_iter().collect(),
+        changes,
+        tx,
+    ));
+    Ok(Response::new(ReceiverStream::new(rx)))
+}
+
+/// 把订阅的用户的变化转换为事件发送给调用方, 调用方断开或不再有效时结束
+async fn forward(
+    svc: Arc<ServiceContext>,
+    mut viewer: Option<Viewer>,
+    token: Option<String>,
+    user_ids: HashSet<String>,
+    mut changes: broadcast::Receiver<UserChange>,
+    tx: mpsc::Sender<Result<UserEvent, Status>>,
+) {
+    loop {
+        let change = tokio::select! {
+            _ = tx.closed() => return,
+            change = changes.recv() => change,
+        };
+        let change = match change {
+            Ok(change) => change,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("watch users lagged, {} changes skipped", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if !user_ids.contains(&change.user_id) {
+            continue;
+        }
+        if let Some(token) = &token {
+            match view::verify_viewer(&svc, token).await {
+                Ok(current) => viewer = Some(current),
+                Err(e) => {
+                    let _ = tx.try_send(Err(Status::from(e)));
+                    return;
+                }
+            }
+        }
+
+        let event = match build_event(&svc, viewer.as_ref(), change).await {
+            Ok(Some(event)) => event,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("build user event failed: {:?}", e);
+                continue;
+            }
+        };
+        if tx.try_send(Ok(event)).is_err() {
+            if tx.is_closed() {
+                return;
+            }
+            warn!("watch users stream is full, event dropped");
+        }
+    }
+}
+
+/// 读取变化后的最新数据, 用户已不存在时返回`None`
+async fn build_event(
+    svc: &ServiceContext,
+    viewer: Option<&Viewer>,
+    change: UserChange,
+) -> Result<Option<UserEvent>, Error> {
+    let mut event = UserEvent {
+        user_id: change.user_id,
+        time: chrono::Utc::now().timestamp_millis(),
+        ..Default::default()
+    };
+    match change.kind {
+        UserChangeKind::Profile => {
+            let Some(user) = svc.user_repo.find_by_id(&event.user_id).await? else {
+                return Ok(None);
+            };
+            let users = view::filter_users(svc, viewer, vec![user], UserView::Full, None).await?;
+            event.event_type = UserEventType::ProfileUpdated as i32;
+            event.user = users.into_iter().next();
+        }
+        UserChangeKind::Presence => {
+            let presences = svc
+                .cache
+                .get_users_presence(std::slice::from_ref(&event.user_id))
+                .await?;
+            event.event_type = UserEventType::PresenceChanged as i32;
+            event.presence = presences.into_iter().next().map(hide_invisible);
+        }
+    }
+    Ok(Some(event))
+}
@@ -0,0 +1,64 @@
+use crate::error::Error;
+use crate::mail::MailSender;
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+/// 把邮件追加写入文件, 每行为`{时间戳} {邮箱} {用途} {验证码}`, 用于联调
+#[derive(Debug)]
+pub struct FileMailSender {
+    path: String,
+}
+
+impl FileMailSender {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl MailSender for FileMailSender {
+    async fn send_code(&self, email: &str, scene: &str, code: &str) -> Result<(), Error> {
+        let line = format!(
+            "{} {} {} {}\n",
+            chrono::Utc::now().timestamp_millis(),
+            email,
+            scene,
+            code
+        );
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| Error::internal_with_details(format!("open mail file, {}", e)))?;
+        // tokio的File在drop时不会等待写入完成, 需要显式flush
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| Error::internal_with_details(format!("write mail file, {}", e)))?;
+        file.flush()
+            .await
+            .map_err(|e| Error::internal_with_details(format!("flush mail file, {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_mail_sender() {
+        let path = std::env::temp_dir().join(format!("mail-{}.log", nanoid::nanoid!()));
+        let sender = FileMailSender::new(path.to_string_lossy());
+        sender
+            .send_code("lucas@example.com", "password_reset", "123456")
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content
+            .trim_end()
+            .ends_with("lucas@example.com password_reset 123456"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,62 @@
+use crate::error::Error;
+use crate::mail::MailSender;
+use async_trait::async_trait;
+use std::sync::Mutex;
+use tracing::info;
+
+/// 不真正发送邮件, 只打印日志并保存已发送的验证码
+#[derive(Debug, Default)]
+pub struct MockMailSender {
+    sent: Mutex<Vec<(String, String)>>,
+}
+
+#[cfg(test)]
+impl MockMailSender {
+    /// 返回最近一次发送到`email`的验证码
+    pub fn last_code(&self, email: &str) -> Option<String> {
+        let sent = self.sent.lock().unwrap();
+        sent.iter()
+            .rev()
+            .find(|(e, _)| e == email)
+            .map(|(_, code)| code.clone())
+    }
+}
+
+#[async_trait]
+impl MailSender for MockMailSender {
+    async fn send_code(&self, email: &str, scene: &str, code: &str) -> Result<(), Error> {
+        info!(
+            "mock mail, email: {}, scene: {}, code: {}",
+            email, scene, code
+        );
+        self.sent
+            .lock()
+            .unwrap()
+            .push((email.to_string(), code.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_mail_sender() {
+        let sender = MockMailSender::default();
+        assert_eq!(sender.last_code("lucas@example.com"), None);
+
+        sender
+            .send_code("lucas@example.com", "register", "111111")
+            .await
+            .unwrap();
+        sender
+            .send_code("lucas@example.com", "register", "222222")
+            .await
+            .unwrap();
+        assert_eq!(
+            sender.last_code("lucas@example.com"),
+            Some("222222".to_string())
+        );
+    }
+}
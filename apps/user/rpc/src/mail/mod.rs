@@ -0,0 +1,22 @@
+use crate::config::{MailConfig, MailSenderKind};
+use crate::error::Error;
+use async_trait::async_trait;
+use std::any::Any;
+use std::fmt::Debug;
+
+pub(crate) mod file;
+pub(crate) mod mock;
+
+/// 邮件发送, 接入邮件服务商时实现该trait
+#[async_trait]
+pub trait MailSender: Any + Sync + Send + Debug {
+    /// 发送验证码到邮箱, `scene`为验证码的用途, 如register, password_reset
+    async fn send_code(&self, email: &str, scene: &str, code: &str) -> Result<(), Error>;
+}
+
+pub fn from_config(config: &MailConfig) -> Box<dyn MailSender> {
+    match config.sender {
+        MailSenderKind::Mock => Box::new(mock::MockMailSender::default()),
+        MailSenderKind::File => Box::new(file::FileMailSender::new(&config.file_path)),
+    }
+}
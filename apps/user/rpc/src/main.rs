@@ -1,8 +1,11 @@
+use anyhow::bail;
 use common::service_discovery::etcd::EtcdServiceDiscovery;
 use common::service_register::ServiceRegister;
 use common::LoadableConfig;
+use sqlx::PgPool;
 use tracing::Level;
 use user_rpc::config::Config;
+use user_rpc::migration;
 use user_rpc::UserRpcServer;
 
 const CONFIG_PATH: &str = "./apps/user/rpc/etc/user.yml";
@@ -10,6 +13,13 @@ const CONFIG_PATH: &str = "./apps/user/rpc/etc/user.yml";
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt().with_max_level(Level::INFO).init();
     let config = Config::load(CONFIG_PATH);
+
+    // `user-rpc migrate [run|status]`: 执行或查看数据库迁移后退出
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migrate(&config, args.get(1).map(String::as_str)).await;
+    }
+
     println!("config: {:?}", config);
 
     UserRpcServer::start(config)
@@ -19,6 +29,22 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn migrate(config: &Config, command: Option<&str>) -> anyhow::Result<()> {
+    let pool = PgPool::connect(&config.postgres.url()).await?;
+    match command {
+        None | Some("run") => migration::run(&pool).await,
+        Some("status") => {
+            let status = migration::status(&pool).await?;
+            print!("{}", status);
+            status.check_compatible()
+        }
+        Some(command) => bail!(
+            "unknown migrate command `{}`, expected `run` or `status`",
+            command
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,6 +53,7 @@ mod tests {
     use user_rpc::pb::user::{FindUserRequest, Request};
 
     #[tokio::test]
+    #[ignore = "requires a running user-rpc and etcd"]
     async fn test_discover_user_rpc() -> anyhow::Result<()> {
         tracing_subscriber::fmt().with_max_level(Level::INFO).init();
 
@@ -68,6 +95,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "requires a running user-rpc and etcd"]
     async fn test_get_user_rpc_client() -> anyhow::Result<()> {
         let mut endpoints = vec!["http://localhost:50051"];
 
@@ -0,0 +1,150 @@
+//! 内嵌的数据库迁移, 迁移脚本位于仓库根目录的`migrations`
+
+use anyhow::bail;
+use sqlx::migrate::{AppliedMigration, Migrate, Migrator};
+use sqlx::PgPool;
+use std::fmt::{Display, Formatter};
+use tracing::{info, warn};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../../migrations");
+
+/// 数据库当前的迁移状态
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// 已执行的迁移版本
+    pub applied: Vec<i64>,
+    /// 未执行的迁移(版本, 描述)
+    pub pending: Vec<(i64, String)>,
+    /// 数据库中存在但本程序不认识的迁移版本, 说明数据库比程序新
+    pub unknown: Vec<i64>,
+    /// 执行失败的迁移版本
+    pub dirty: Option<i64>,
+}
+
+impl MigrationStatus {
+    fn new(applied: &[AppliedMigration], dirty: Option<i64>) -> Self {
+        let pending = MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .filter(|migration| !applied.iter().any(|a| a.version == migration.version))
+            .map(|migration| (migration.version, migration.description.to_string()))
+            .collect();
+        let unknown = applied
+            .iter()
+            .map(|a| a.version)
+            .filter(|version| !MIGRATOR.version_exists(*version))
+            .collect();
+        Self {
+            applied: applied.iter().map(|a| a.version).collect(),
+            pending,
+            unknown,
+            dirty,
+        }
+    }
+
+    /// 程序支持的最新版本
+    pub fn latest_version() -> Option<i64> {
+        MIGRATOR.iter().map(|migration| migration.version).max()
+    }
+
+    /// 数据库结构是否可以被当前程序使用, 有未执行的迁移时仍然兼容
+    pub fn check_compatible(&self) -> anyhow::Result<()> {
+        if let Some(version) = self.dirty {
+            bail!("migration {} failed, fix the database manually", version);
+        }
+        if !self.unknown.is_empty() {
+            bail!(
+                "database schema is newer than this binary, unknown migrations: {:?}, latest supported: {}",
+                self.unknown,
+                Self::latest_version().unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Display for MigrationStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "applied: {}", self.applied.len())?;
+        for (version, description) in &self.pending {
+            writeln!(f, "pending: {} {}", version, description)?;
+        }
+        for version in &self.unknown {
+            writeln!(f, "unknown: {}", version)?;
+        }
+        if let Some(version) = self.dirty {
+            writeln!(f, "dirty: {}", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// 查询迁移状态
+pub async fn status(pool: &PgPool) -> anyhow::Result<MigrationStatus> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let dirty = conn.dirty_version().await?;
+    let applied = conn.list_applied_migrations().await?;
+    Ok(MigrationStatus::new(&applied, dirty))
+}
+
+/// 执行所有未执行的迁移
+pub async fn run(pool: &PgPool) -> anyhow::Result<()> {
+    let status = status(pool).await?;
+    status.check_compatible()?;
+    MIGRATOR.run(pool).await?;
+    for (version, description) in &status.pending {
+        info!("migration applied: {} {}", version, description);
+    }
+    Ok(())
+}
+
+/// 启动时检查数据库结构, `auto_migrate`为`true`时执行未执行的迁移,
+/// 否则只提示未执行的迁移
+pub async fn prepare(pool: &PgPool, auto_migrate: bool) -> anyhow::Result<()> {
+    if auto_migrate {
+        return run(pool).await;
+    }
+
+    let status = status(pool).await?;
+    status.check_compatible()?;
+    for (version, description) in &status.pending {
+        warn!(
+            "migration pending: {} {}, run `user-rpc migrate` to apply",
+            version, description
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn applied(version: i64) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            checksum: Cow::Borrowed(&[]),
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let latest = MigrationStatus::latest_version().unwrap();
+        let first = MIGRATOR.iter().next().unwrap().version;
+
+        let status = MigrationStatus::new(&[applied(first)], None);
+        assert!(!status.pending.is_empty());
+        assert!(status.pending.iter().all(|(version, _)| *version != first));
+        assert!(status.check_compatible().is_ok());
+
+        // 数据库中有更新的迁移时拒绝启动
+        let status = MigrationStatus::new(&[applied(first), applied(latest + 1)], None);
+        assert_eq!(status.unknown, vec![latest + 1]);
+        assert!(status.check_compatible().is_err());
+
+        let status = MigrationStatus::new(&[], Some(first));
+        assert!(status.check_compatible().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::error::Error;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::header::{ACCEPT, CONTENT_TYPE};
+use hyper::http::uri::Scheme;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper::{Method, Request, Uri};
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpConnector};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use tower::Service;
+
+/// 请求身份提供方接口的JSON客户端
+///
+/// 支持http和https, https使用内置的Mozilla根证书校验服务端证书
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client<HttpsConnector, Full<Bytes>>,
+    timeout: Duration,
+}
+
+impl HttpClient {
+    pub fn new(timeout: Duration) -> Self {
+        let client = Client::builder(TokioExecutor::new()).build(HttpsConnector::new());
+        Self { client, timeout }
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(url)
+            .header(ACCEPT, "application/json")
+            .body(Full::default())
+            .map_err(|e| Error::internal_with_details(format!("build request, {}", e)))?;
+        self.send(request).await
+    }
+
+    pub async fn post_form<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish();
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(url)
+            .header(ACCEPT, "application/json")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|e| Error::internal_with_details(format!("build request, {}", e)))?;
+        self.send(request).await
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: Request<Full<Bytes>>) -> Result<T, Error> {
+        let uri = request.uri().to_string();
+        let response = tokio::time::timeout(self.timeout, self.client.request(request))
+            .await
+            .map_err(|_| Error::internal_with_details(format!("request {} timeout", uri)))?
+            .map_err(|e| Error::internal_with_details(format!("request {}, {}", uri, e)))?;
+
+        let status = response.status();
+        let body = tokio::time::timeout(self.timeout, response.into_body().collect())
+            .await
+            .map_err(|_| Error::internal_with_details(format!("read {} timeout", uri)))?
+            .map_err(|e| Error::internal_with_details(format!("read {}, {}", uri, e)))?
+            .to_bytes();
+        if !status.is_success() {
+            return Err(Error::internal_with_details(format!(
+                "request {} failed, status: {}, body: {}",
+                uri,
+                status,
+                String::from_utf8_lossy(&body)
+            )));
+        }
+
+        serde_json::from_slice(&body)
+            .map_err(|e| Error::internal_with_details(format!("decode {} response, {}", uri, e)))
+    }
+}
+
+/// 按scheme建立tcp或tls连接
+#[derive(Clone)]
+struct HttpsConnector {
+    http: HttpConnector,
+    tls: TlsConnector,
+}
+
+impl std::fmt::Debug for HttpsConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpsConnector").finish_non_exhaustive()
+    }
+}
+
+impl HttpsConnector {
+    fn new() -> Self {
+        let mut http = HttpConnector::new();
+        // 允许https的uri, tls握手由本连接器完成
+        http.enforce_http(false);
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .expect("ring supports the default protocol versions")
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        Self {
+            http,
+            tls: TlsConnector::from(Arc::new(config)),
+        }
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+impl Service<Uri> for HttpsConnector {
+    type Response = MaybeTlsStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let is_https = uri.scheme() == Some(&Scheme::HTTPS);
+        let host = uri
+            .host()
+            .unwrap_or_default()
+            .trim_matches(['[', ']'])
+            .to_string();
+        let connecting = self.http.call(uri);
+        let tls = self.tls.clone();
+        Box::pin(async move {
+            let tcp = connecting.await?;
+            if !is_https {
+                return Ok(MaybeTlsStream::Plain(tcp));
+            }
+            let server_name = ServerName::try_from(host)?;
+            let stream = tls.connect(server_name, tcp.into_inner()).await?;
+            Ok(MaybeTlsStream::Tls(Box::new(TokioIo::new(stream))))
+        })
+    }
+}
+
+enum MaybeTlsStream {
+    Plain(TokioIo<TcpStream>),
+    Tls(Box<TokioIo<TlsStream<TcpStream>>>),
+}
+
+impl Connection for MaybeTlsStream {
+    fn connected(&self) -> Connected {
+        match self {
+            Self::Plain(stream) => stream.connected(),
+            Self::Tls(stream) => stream.inner().get_ref().0.connected(),
+        }
+    }
+}
+
+impl Read for MaybeTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl Write for MaybeTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_https_uses_tls() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut record_type = [0u8; 1];
+            stream.read_exact(&mut record_type).await.unwrap();
+            record_type[0]
+        });
+
+        let client = HttpClient::new(Duration::from_secs(1));
+        let url = format!(
+            "https://127.0.0.1:{}/.well-known/openid-configuration",
+            port
+        );
+        // 服务端不完成握手, 请求失败
+        assert!(client.get_json::<serde_json::Value>(&url).await.is_err());
+        // 0x16: TLS握手记录, 客户端发送了ClientHello
+        assert_eq!(server.await.unwrap(), 0x16);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::config::OidcConfig;
+use crate::error::Error;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+pub(crate) mod http;
+pub(crate) mod provider;
+
+/// 校验ID token后得到的第三方身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedIdentity {
+    pub provider: String,
+    /// 提供方内唯一的用户标识(`sub`)
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+}
+
+/// 授权请求的上下文, 以state为key保存在缓存中
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationState {
+    pub provider: String,
+    pub nonce: String,
+    /// 绑定到已有用户时为该用户id, 登录时为空
+    pub link_user_id: Option<String>,
+}
+
+/// 第三方身份提供方, 目前实现了OIDC授权码模式
+#[async_trait]
+pub trait IdentityProvider: Sync + Send + Debug {
+    /// 生成跳转到提供方的授权地址
+    fn authorization_url(&self, state: &str, nonce: &str) -> String;
+    /// 使用授权码换取ID token并校验
+    async fn exchange_code(&self, code: &str, nonce: &str) -> Result<VerifiedIdentity, Error>;
+}
+
+/// 按名称索引的身份提供方
+#[derive(Debug, Default)]
+pub struct IdentityProviders {
+    providers: HashMap<String, Box<dyn IdentityProvider>>,
+}
+
+impl IdentityProviders {
+    pub fn from_config(config: &OidcConfig) -> Self {
+        let http = http::HttpClient::new(Duration::from_millis(config.http_timeout_ms));
+        let mut providers = Self::default();
+        for provider in &config.providers {
+            providers.insert(
+                &provider.name,
+                Box::new(provider::OidcProvider::new(
+                    provider.clone(),
+                    http.clone(),
+                    Duration::from_secs(config.jwks_cache_ttl),
+                )),
+            );
+        }
+        providers
+    }
+
+    pub fn insert(&mut self, name: &str, provider: Box<dyn IdentityProvider>) {
+        self.providers.insert(name.to_string(), provider);
+    }
+
+    pub fn get(&self, name: &str) -> Result<&dyn IdentityProvider, Error> {
+        self.providers
+            .get(name)
+            .map(|provider| provider.as_ref())
+            .ok_or_else(|| Error::invalid_argument(format!("unknown provider `{}`", name)))
+    }
+}
@@ -0,0 +1,296 @@
+use crate::config::OidcProviderConfig;
+use crate::error::Error;
+use crate::oidc::http::HttpClient;
+use crate::oidc::{IdentityProvider, VerifiedIdentity};
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// 只接受非对称签名算法, 避免使用公钥作为HMAC密钥伪造token
+const ALLOWED_ALGORITHMS: [Algorithm; 8] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+    picture: Option<String>,
+}
+
+/// OIDC授权码模式, 使用client_secret_post认证, 通过JWKS校验ID token
+#[derive(Debug)]
+pub struct OidcProvider {
+    config: OidcProviderConfig,
+    http: HttpClient,
+    jwks_cache_ttl: Duration,
+    jwks: RwLock<Option<(JwkSet, Instant)>>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcProviderConfig, http: HttpClient, jwks_cache_ttl: Duration) -> Self {
+        Self {
+            config,
+            http,
+            jwks_cache_ttl,
+            jwks: RwLock::new(None),
+        }
+    }
+
+    /// 查找签名公钥, 缓存中没有对应的kid时刷新一次, 兼容提供方轮换密钥
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey, Error> {
+        {
+            let jwks = self.jwks.read().await;
+            if let Some((jwks, fetched_at)) = jwks.as_ref() {
+                if fetched_at.elapsed() < self.jwks_cache_ttl {
+                    if let Some(jwk) = jwks.find(kid) {
+                        return DecodingKey::from_jwk(jwk).map_err(id_token_error);
+                    }
+                }
+            }
+        }
+
+        let jwks: JwkSet = self.http.get_json(&self.config.jwks_uri).await?;
+        let key = jwks
+            .find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(id_token_error)?;
+        *self.jwks.write().await = Some((jwks, Instant::now()));
+        key.ok_or_else(|| Error::invalid_token(format!("unknown key id `{}`", kid)))
+    }
+
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<VerifiedIdentity, Error> {
+        let header = jsonwebtoken::decode_header(id_token).map_err(id_token_error)?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(Error::invalid_token(format!(
+                "unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+        let kid = header
+            .kid
+            .ok_or_else(|| Error::invalid_token("missing key id"))?;
+        let key = self.decoding_key(&kid).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(id_token_error)?
+            .claims;
+
+        // nonce与授权请求绑定, 防止ID token重放
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(Error::invalid_token("nonce mismatch"));
+        }
+
+        Ok(VerifiedIdentity {
+            provider: self.config.name.clone(),
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+            picture: claims.picture,
+        })
+    }
+}
+
+fn id_token_error(e: jsonwebtoken::errors::Error) -> Error {
+    Error::invalid_token(format!("id token error, {}", e))
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    fn authorization_url(&self, state: &str, nonce: &str) -> String {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .finish();
+        let separator = if self.config.authorization_endpoint.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        format!(
+            "{}{}{}",
+            self.config.authorization_endpoint, separator, query
+        )
+    }
+
+    async fn exchange_code(&self, code: &str, nonce: &str) -> Result<VerifiedIdentity, Error> {
+        let token: TokenResponse = self
+            .http
+            .post_form(
+                &self.config.token_endpoint,
+                &[
+                    ("grant_type", "authorization_code"),
+                    ("code", code),
+                    ("redirect_uri", &self.config.redirect_uri),
+                    ("client_id", &self.config.client_id),
+                    ("client_secret", &self.config.client_secret),
+                ],
+            )
+            .await
+            .map_err(|e| Error::invalid_code(format!("exchange authorization code, {:?}", e)))?;
+        self.verify_id_token(&token.id_token, nonce).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const PRIVATE_KEY: &str = include_str!("testdata/idp_key.pem");
+    const JWKS: &str = include_str!("testdata/jwks.json");
+    const CODE: &str = "test-code";
+
+    /// 本地模拟的身份提供方, 提供/jwks和/token接口, 授权码为`CODE`时签发ID token,
+    /// `claims`的参数为提供方地址
+    async fn start_mock_idp(claims: impl FnOnce(&str) -> serde_json::Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let claims = claims(&base_url);
+        let key = EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+        let id_token = jsonwebtoken::encode(&header, &claims, &key).unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+
+                let (status, body) = if request.starts_with("GET /jwks") {
+                    ("200 OK", JWKS.to_string())
+                } else if request.starts_with("POST /token")
+                    && request.contains(&format!("code={}", CODE))
+                {
+                    ("200 OK", json!({ "id_token": id_token }).to_string())
+                } else {
+                    (
+                        "400 Bad Request",
+                        json!({ "error": "invalid_grant" }).to_string(),
+                    )
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        base_url
+    }
+
+    fn provider(base_url: &str) -> OidcProvider {
+        let config = OidcProviderConfig {
+            name: "mock".to_string(),
+            issuer: base_url.to_string(),
+            client_id: "lucas-im".to_string(),
+            client_secret: "secret".to_string(),
+            authorization_endpoint: format!("{}/authorize", base_url),
+            token_endpoint: format!("{}/token", base_url),
+            jwks_uri: format!("{}/jwks", base_url),
+            redirect_uri: "http://127.0.0.1/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+        };
+        OidcProvider::new(
+            config,
+            HttpClient::new(Duration::from_secs(5)),
+            Duration::from_secs(60),
+        )
+    }
+
+    fn claims(issuer: &str, audience: &str, nonce: &str) -> serde_json::Value {
+        json!({
+            "iss": issuer,
+            "aud": audience,
+            "sub": "subject-1",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "iat": chrono::Utc::now().timestamp(),
+            "nonce": nonce,
+            "email": "lucas@example.com",
+            "email_verified": true,
+            "name": "lucas",
+        })
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let url = provider("http://idp").authorization_url("state-1", "nonce-1");
+        assert_eq!(
+            url,
+            "http://idp/authorize?response_type=code&client_id=lucas-im\
+            &redirect_uri=http%3A%2F%2F127.0.0.1%2Fcallback&scope=openid+email\
+            &state=state-1&nonce=nonce-1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code() {
+        let base_url = start_mock_idp(|url| claims(url, "lucas-im", "nonce-1")).await;
+        let provider = provider(&base_url);
+
+        let identity = provider.exchange_code(CODE, "nonce-1").await.unwrap();
+        assert_eq!(identity.provider, "mock");
+        assert_eq!(identity.subject, "subject-1");
+        assert_eq!(identity.email.as_deref(), Some("lucas@example.com"));
+        assert!(identity.email_verified);
+
+        // nonce不匹配
+        assert!(provider.exchange_code(CODE, "nonce-2").await.is_err());
+        // 授权码错误
+        assert!(provider
+            .exchange_code("wrong-code", "nonce-1")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_with_invalid_claims() {
+        // 其他提供方签发的token
+        let base_url = start_mock_idp(|_| claims("http://other-issuer", "lucas-im", "n")).await;
+        assert!(provider(&base_url).exchange_code(CODE, "n").await.is_err());
+
+        // 签发给其他客户端的token
+        let base_url = start_mock_idp(|url| claims(url, "other-client", "n")).await;
+        assert!(provider(&base_url).exchange_code(CODE, "n").await.is_err());
+    }
+}
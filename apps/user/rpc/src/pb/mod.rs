@@ -1 +1,10 @@
 pub mod user;
+
+/// grpc服务反射协议
+pub mod reflection {
+    include!("grpc.reflection.v1.rs");
+}
+
+/// 编译后的proto描述, 用于服务反射
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/user_descriptor.bin"));
@@ -31,11 +31,18 @@ pub struct User {
     pub create_time: i64,
     #[prost(int64, tag = "14")]
     pub update_time: i64,
-    #[prost(string, tag = "15")]
-    #[serde(skip_serializing)]
-    pub salt: ::prost::alloc::string::String,
     #[prost(string, tag = "16")]
     pub signature: ::prost::alloc::string::String,
+    #[prost(enumeration = "UserRole", tag = "17")]
+    pub role: i32,
+    /// 是否被封禁, 封禁到期后自动解除
+    #[prost(bool, tag = "18")]
+    pub banned: bool,
+    #[prost(string, tag = "19")]
+    pub ban_reason: ::prost::alloc::string::String,
+    /// 解封时间(毫秒), 0表示永久封禁
+    #[prost(int64, tag = "20")]
+    pub ban_until: i64,
 }
 /// 用户注册
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -78,11 +85,9 @@ pub struct SendRegisterCodeRequest {
     #[prost(string, tag = "2")]
     pub email: ::prost::alloc::string::String,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SendRegisterCodeResponse {
-    #[prost(string, tag = "1")]
-    pub code: ::prost::alloc::string::String,
-}
+/// 验证码发送到邮箱, 不在响应中返回
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SendRegisterCodeResponse {}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct LoginRequest {
     #[prost(string, tag = "1")]
@@ -98,13 +103,25 @@ pub struct LoginResponse {
     pub token: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub refresh_token: ::prost::alloc::string::String,
+    /// 开启了两步验证, 需要使用challenge_token调用VerifySecondFactor完成登录
+    #[prost(bool, tag = "4")]
+    pub second_factor_required: bool,
+    #[prost(string, tag = "5")]
+    pub challenge_token: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct UserOnlineCountRequest {}
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UserOnlineCountRequest {
+    /// 是否按平台统计
+    #[prost(bool, tag = "1")]
+    pub by_platform: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserOnlineCountResponse {
     #[prost(int64, tag = "1")]
     pub count: i64,
+    /// 各平台的在线人数, 多端同时在线的用户在每个平台都会计数
+    #[prost(map = "string, int64", tag = "2")]
+    pub platforms: ::std::collections::HashMap<::prost::alloc::string::String, i64>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetUserInfoRequest {
@@ -134,205 +151,4032 @@ pub struct FindUserResponse {
     #[prost(message, repeated, tag = "1")]
     pub users: ::prost::alloc::vec::Vec<User>,
 }
-/// Generated client implementations.
-pub mod user_service_client {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value
-    )]
-    use tonic::codegen::http::Uri;
-    use tonic::codegen::*;
-    #[derive(Debug, Clone)]
-    pub struct UserServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl UserServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> UserServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::Body>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> UserServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::Body>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
-                Into<StdError> + std::marker::Send + std::marker::Sync,
-        {
-            UserServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
-        }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
-        }
-        pub async fn ping(
-            &mut self,
-            request: impl tonic::IntoRequest<super::Request>,
-        ) -> std::result::Result<tonic::Response<super::Response>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/Ping");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "Ping"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn register(
-            &mut self,
-            request: impl tonic::IntoRequest<super::RegisterRequest>,
-        ) -> std::result::Result<tonic::Response<super::RegisterResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/Register");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "Register"));
-            self.inner.unary(req, path, codec).await
-        }
-        pub async fn login(
-            &mut self,
-            request: impl tonic::IntoRequest<super::LoginRequest>,
-        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/Login");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "Login"));
-            self.inner.unary(req, path, codec).await
-        }
-        /// 发送注册验证码到邮箱, 并返回验证码
-        pub async fn send_register_code(
-            &mut self,
-            request: impl tonic::IntoRequest<super::SendRegisterCodeRequest>,
-        ) -> std::result::Result<tonic::Response<super::SendRegisterCodeResponse>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/SendRegisterCode");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "SendRegisterCode"));
-            self.inner.unary(req, path, codec).await
-        }
-        /// 统计用户在线数量
-        pub async fn get_user_online_count(
-            &mut self,
-            request: impl tonic::IntoRequest<super::UserOnlineCountRequest>,
-        ) -> std::result::Result<tonic::Response<super::UserOnlineCountResponse>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetUserOnlineCount");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "GetUserOnlineCount"));
-            self.inner.unary(req, path, codec).await
-        }
-        /// 获取用户信息
-        pub async fn get_user_info(
-            &mut self,
-            request: impl tonic::IntoRequest<super::GetUserInfoRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetUserInfoResponse>, tonic::Status>
-        {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetUserInfo");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "GetUserInfo"));
-            self.inner.unary(req, path, codec).await
+/// 隐私设置, 对本人和管理员/客服不生效
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct PrivacySettings {
+    /// 能否通过手机号/邮箱/账号查找到该用户
+    #[prost(bool, tag = "1")]
+    pub searchable_by_phone: bool,
+    #[prost(bool, tag = "2")]
+    pub searchable_by_email: bool,
+    #[prost(bool, tag = "3")]
+    pub searchable_by_account: bool,
+    /// 签名和地区的可见范围
+    #[prost(enumeration = "PrivacyScope", tag = "4")]
+    pub profile_visibility: i32,
+    #[prost(enumeration = "FriendRequestPolicy", tag = "5")]
+    pub friend_request: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPrivacySettingsRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以查看其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct GetPrivacySettingsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub settings: ::core::option::Option<PrivacySettings>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdatePrivacySettingsRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以修改其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub settings: ::core::option::Option<PrivacySettings>,
+    /// 需要修改的字段, 如`searchable_by_phone`
+    #[prost(message, optional, tag = "3")]
+    pub update_mask: ::core::option::Option<::prost_types::FieldMask>,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UpdatePrivacySettingsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub settings: ::core::option::Option<PrivacySettings>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanSendFriendRequestRequest {
+    #[prost(string, tag = "1")]
+    pub from_user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub to_user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CanSendFriendRequestResponse {
+    #[prost(bool, tag = "1")]
+    pub allowed: bool,
+}
+/// 拉黑后双方互相查找不到, 由消息和好友申请等业务通过IsBlocked检查
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockUserRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub blocked_user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BlockUserResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnblockUserRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub blocked_user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UnblockUserResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BlockedUser {
+    /// 公开名片
+    #[prost(message, optional, tag = "1")]
+    pub user: ::core::option::Option<User>,
+    /// 拉黑时间, 毫秒
+    #[prost(int64, tag = "2")]
+    pub create_time: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListBlockedUsersRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以查看其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 上一页返回的next_cursor, 第一页为0
+    #[prost(int64, tag = "2")]
+    pub cursor: i64,
+    /// 每页数量, 默认20, 最大100
+    #[prost(int32, tag = "3")]
+    pub limit: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListBlockedUsersResponse {
+    /// 按拉黑时间倒序
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<BlockedUser>,
+    /// 为0表示没有下一页
+    #[prost(int64, tag = "2")]
+    pub next_cursor: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IsBlockedRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以查询其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub other_user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct IsBlockedResponse {
+    /// user_id拉黑了other_user_id
+    #[prost(bool, tag = "1")]
+    pub blocking: bool,
+    /// user_id被other_user_id拉黑
+    #[prost(bool, tag = "2")]
+    pub blocked_by: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchGetUsersRequest {
+    /// 最多100个
+    #[prost(string, repeated, tag = "1")]
+    pub user_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// 需要返回的字段, 为空时返回视图内的全部字段
+    #[prost(message, optional, tag = "2")]
+    pub read_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// 期望的视图, 超出调用方权限时降级为调用方可见的视图
+    #[prost(enumeration = "UserView", tag = "3")]
+    pub view: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchGetUsersResponse {
+    /// 不存在的用户不返回, 顺序与请求一致
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<User>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchUsersRequest {
+    /// 按名称或账号前缀/模糊匹配
+    #[prost(string, tag = "1")]
+    pub keyword: ::prost::alloc::string::String,
+    /// 每页数量, 默认20, 最大100
+    #[prost(int32, tag = "2")]
+    pub limit: i32,
+    /// 上一页返回的next_cursor, 第一页为空
+    #[prost(string, tag = "3")]
+    pub cursor: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchUsersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<User>,
+    /// 为空表示没有下一页
+    #[prost(string, tag = "2")]
+    pub next_cursor: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendSmsCodeRequest {
+    #[prost(string, tag = "1")]
+    pub phone: ::prost::alloc::string::String,
+    #[prost(enumeration = "SmsCodeScene", tag = "2")]
+    pub scene: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SendSmsCodeResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhoneRegisterRequest {
+    #[prost(string, tag = "1")]
+    pub phone: ::prost::alloc::string::String,
+    /// 短信验证码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub name: ::prost::alloc::string::String,
+    /// 可选, 为空时只能使用验证码登录
+    #[prost(string, tag = "4")]
+    pub password: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub avatar: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhoneRegisterResponse {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhoneLoginRequest {
+    #[prost(string, tag = "1")]
+    pub phone: ::prost::alloc::string::String,
+    /// 短信验证码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnrollTotpRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnrollTotpResponse {
+    /// base32编码的密钥, 用于手动输入
+    #[prost(string, tag = "1")]
+    pub secret: ::prost::alloc::string::String,
+    /// 用于生成二维码
+    #[prost(string, tag = "2")]
+    pub otpauth_uri: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmTotpRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 验证器App生成的验证码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmTotpResponse {
+    /// 一次性恢复码, 只返回这一次
+    #[prost(string, repeated, tag = "1")]
+    pub recovery_codes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DisableTotpRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 验证码或恢复码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+    /// 使用验证码时需要校验密码, 使用恢复码时不需要
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DisableTotpResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifySecondFactorRequest {
+    /// 登录返回的挑战token
+    #[prost(string, tag = "1")]
+    pub challenge_token: ::prost::alloc::string::String,
+    /// 验证码或恢复码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+/// 绑定的第三方身份
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExternalIdentity {
+    /// 提供方名称
+    #[prost(string, tag = "1")]
+    pub provider: ::prost::alloc::string::String,
+    /// 提供方内的用户标识
+    #[prost(string, tag = "2")]
+    pub subject: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub email: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, tag = "4")]
+    pub create_time: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OidcAuthorizeRequest {
+    #[prost(string, tag = "1")]
+    pub provider: ::prost::alloc::string::String,
+    /// 绑定到已登录用户时填写, 需要携带该用户的token; 登录时为空
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OidcAuthorizeResponse {
+    /// 跳转到提供方的授权地址
+    #[prost(string, tag = "1")]
+    pub authorization_url: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub state: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OidcLoginRequest {
+    #[prost(string, tag = "1")]
+    pub state: ::prost::alloc::string::String,
+    /// 提供方回调返回的授权码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LinkExternalIdentityRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub state: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LinkExternalIdentityResponse {
+    #[prost(message, optional, tag = "1")]
+    pub identity: ::core::option::Option<ExternalIdentity>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnlinkExternalIdentityRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub provider: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UnlinkExternalIdentityResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExternalIdentitiesRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以查看其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExternalIdentitiesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub identities: ::prost::alloc::vec::Vec<ExternalIdentity>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserPresence {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "PresenceStatus", tag = "2")]
+    pub status: i32,
+    /// 最后在线时间, 毫秒
+    #[prost(int64, tag = "3")]
+    pub last_seen: i64,
+    /// 在线的平台
+    #[prost(string, repeated, tag = "4")]
+    pub platforms: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeartbeatRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 平台, 如ios/android/web/desktop
+    #[prost(string, tag = "2")]
+    pub platform: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub device_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct HeartbeatResponse {
+    /// 下一次心跳的间隔, 秒
+    #[prost(int64, tag = "1")]
+    pub interval: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeviceOfflineRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub platform: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub device_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeviceOfflineResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetPresenceStatusRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "PresenceStatus", tag = "2")]
+    pub status: i32,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SetPresenceStatusResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUsersPresenceRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub user_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetUsersPresenceResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub presences: ::prost::alloc::vec::Vec<UserPresence>,
+}
+/// 订阅用户资料和在线状态的变化
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchUsersRequest {
+    /// 数量上限由presence.max_watch_users配置, 默认500
+    #[prost(string, repeated, tag = "1")]
+    pub user_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// 只推送变化后的最新数据, 消费过慢时可能丢失事件, 重新订阅后应重新拉取
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserEvent {
+    #[prost(enumeration = "UserEventType", tag = "1")]
+    pub event_type: i32,
+    #[prost(string, tag = "2")]
+    pub user_id: ::prost::alloc::string::String,
+    /// PROFILE_UPDATED时返回, 按调用方与用户的关系裁剪字段
+    #[prost(message, optional, tag = "3")]
+    pub user: ::core::option::Option<User>,
+    /// PRESENCE_CHANGED时返回, 隐身用户显示为离线
+    #[prost(message, optional, tag = "4")]
+    pub presence: ::core::option::Option<UserPresence>,
+    /// 事件时间, 毫秒
+    #[prost(int64, tag = "5")]
+    pub time: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SecurityEvent {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(enumeration = "SecurityEventType", tag = "2")]
+    pub event_type: i32,
+    /// 尝试登录的账号/邮箱/手机号
+    #[prost(string, tag = "3")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub ip: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub device: ::prost::alloc::string::String,
+    /// 登录方式或失败原因
+    #[prost(string, tag = "6")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(int64, tag = "7")]
+    pub create_time: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSecurityEventsRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以查看其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 上一页返回的next_cursor, 第一页为0
+    #[prost(int64, tag = "2")]
+    pub cursor: i64,
+    /// 每页数量, 默认20, 最大100
+    #[prost(int32, tag = "3")]
+    pub limit: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSecurityEventsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub events: ::prost::alloc::vec::Vec<SecurityEvent>,
+    /// 为0表示没有下一页
+    #[prost(int64, tag = "2")]
+    pub next_cursor: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendPasswordResetCodeRequest {
+    #[prost(string, tag = "1")]
+    pub email: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct SendPasswordResetCodeResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ResetPasswordRequest {
+    #[prost(string, tag = "1")]
+    pub email: ::prost::alloc::string::String,
+    /// 验证码
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+    /// 新密码
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ResetPasswordResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateUserProfileRequest {
+    /// 为空时为当前登录的用户, 只有管理员可以修改其他用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 新的资料, 只有update_mask中列出的字段会被修改
+    #[prost(message, optional, tag = "2")]
+    pub user: ::core::option::Option<User>,
+    /// 可修改: name, avatar, gender, age, signature, address, region, birthday
+    #[prost(message, optional, tag = "3")]
+    pub update_mask: ::core::option::Option<::prost_types::FieldMask>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateUserProfileResponse {
+    #[prost(message, optional, tag = "1")]
+    pub user: ::core::option::Option<User>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeactivateAccountRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 需要校验密码
+    #[prost(string, tag = "2")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct DeactivateAccountResponse {
+    /// 宽限期结束时间(毫秒), 在此之前可以恢复账号
+    #[prost(int64, tag = "1")]
+    pub purge_time: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreAccountRequest {
+    #[prost(string, tag = "1")]
+    pub account: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreAccountResponse {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePasswordRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub old_password: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub new_password: ::prost::alloc::string::String,
+}
+/// 修改密码后其他会话全部失效, 返回新的token
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePasswordResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DataExport {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "DataExportStatus", tag = "2")]
+    pub status: i32,
+    /// 压缩包大小, 字节
+    #[prost(int64, tag = "3")]
+    pub file_size: i64,
+    #[prost(int64, tag = "4")]
+    pub create_time: i64,
+    /// 下载有效期(毫秒), 导出完成后才有值
+    #[prost(int64, tag = "5")]
+    pub expire_time: i64,
+    /// 下载凭证, 只在可以下载时返回
+    #[prost(string, tag = "6")]
+    pub download_token: ::prost::alloc::string::String,
+    /// 导出失败的原因
+    #[prost(string, tag = "7")]
+    pub error: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RequestDataExportRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RequestDataExportResponse {
+    #[prost(message, optional, tag = "1")]
+    pub export: ::core::option::Option<DataExport>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDataExportRequest {
+    /// 只能是当前登录的用户, 为空时为当前登录的用户
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub export_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetDataExportResponse {
+    #[prost(message, optional, tag = "1")]
+    pub export: ::core::option::Option<DataExport>,
+}
+/// 凭download_token下载, 不需要登录
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DownloadDataExportRequest {
+    #[prost(string, tag = "1")]
+    pub export_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub download_token: ::prost::alloc::string::String,
+}
+/// ZIP压缩包的一段内容
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DataExportChunk {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdminListUsersRequest {
+    /// 按名称, 账号, 邮箱或手机号前缀匹配
+    #[prost(string, tag = "1")]
+    pub keyword: ::prost::alloc::string::String,
+    #[prost(enumeration = "UserRole", optional, tag = "2")]
+    pub role: ::core::option::Option<i32>,
+    #[prost(bool, optional, tag = "3")]
+    pub banned: ::core::option::Option<bool>,
+    /// 上一页返回的next_cursor, 第一页为空
+    #[prost(string, tag = "4")]
+    pub cursor: ::prost::alloc::string::String,
+    /// 每页数量, 默认20, 最大100
+    #[prost(int32, tag = "5")]
+    pub limit: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdminListUsersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub users: ::prost::alloc::vec::Vec<User>,
+    /// 为空表示没有下一页
+    #[prost(string, tag = "2")]
+    pub next_cursor: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BanUserRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+    /// 解封时间(毫秒), 0表示永久封禁
+    #[prost(int64, tag = "3")]
+    pub until: i64,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BanUserResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnbanUserRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct UnbanUserResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceResetPasswordRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    /// 为空时生成随机密码
+    #[prost(string, tag = "2")]
+    pub new_password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceResetPasswordResponse {
+    /// 生成的随机密码, 指定了新密码时为空
+    #[prost(string, tag = "1")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RevokeUserSessionsRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RevokeUserSessionsResponse {}
+/// 用户角色
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UserRole {
+    User = 0,
+    Admin = 1,
+    /// 客服, 可以查询用户和注销会话
+    Support = 2,
+}
+impl UserRole {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::User => "USER_ROLE_USER",
+            Self::Admin => "USER_ROLE_ADMIN",
+            Self::Support => "USER_ROLE_SUPPORT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "USER_ROLE_USER" => Some(Self::User),
+            "USER_ROLE_ADMIN" => Some(Self::Admin),
+            "USER_ROLE_SUPPORT" => Some(Self::Support),
+            _ => None,
+        }
+    }
+}
+/// 用户资料的可见范围, 密码在任何视图中都不返回
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UserView {
+    /// 公开名片: id, 名称, 头像, 签名
+    Public = 0,
+    /// 好友可见: 公开名片以及性别, 年龄, 地区, 生日
+    Friend = 1,
+    /// 本人或管理员/客服可见: 全部字段
+    Full = 2,
+}
+impl UserView {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Public => "USER_VIEW_PUBLIC",
+            Self::Friend => "USER_VIEW_FRIEND",
+            Self::Full => "USER_VIEW_FULL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "USER_VIEW_PUBLIC" => Some(Self::Public),
+            "USER_VIEW_FRIEND" => Some(Self::Friend),
+            "USER_VIEW_FULL" => Some(Self::Full),
+            _ => None,
+        }
+    }
+}
+/// 隐私设置中的可见范围
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PrivacyScope {
+    Everyone = 0,
+    Friends = 1,
+    Nobody = 2,
+}
+impl PrivacyScope {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Everyone => "PRIVACY_SCOPE_EVERYONE",
+            Self::Friends => "PRIVACY_SCOPE_FRIENDS",
+            Self::Nobody => "PRIVACY_SCOPE_NOBODY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PRIVACY_SCOPE_EVERYONE" => Some(Self::Everyone),
+            "PRIVACY_SCOPE_FRIENDS" => Some(Self::Friends),
+            "PRIVACY_SCOPE_NOBODY" => Some(Self::Nobody),
+            _ => None,
+        }
+    }
+}
+/// 谁可以发送好友申请
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FriendRequestPolicy {
+    Everyone = 0,
+    Nobody = 1,
+}
+impl FriendRequestPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Everyone => "FRIEND_REQUEST_POLICY_EVERYONE",
+            Self::Nobody => "FRIEND_REQUEST_POLICY_NOBODY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FRIEND_REQUEST_POLICY_EVERYONE" => Some(Self::Everyone),
+            "FRIEND_REQUEST_POLICY_NOBODY" => Some(Self::Nobody),
+            _ => None,
+        }
+    }
+}
+/// 短信验证码用途
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SmsCodeScene {
+    Register = 0,
+    Login = 1,
+}
+impl SmsCodeScene {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Register => "SMS_CODE_SCENE_REGISTER",
+            Self::Login => "SMS_CODE_SCENE_LOGIN",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SMS_CODE_SCENE_REGISTER" => Some(Self::Register),
+            "SMS_CODE_SCENE_LOGIN" => Some(Self::Login),
+            _ => None,
+        }
+    }
+}
+/// 在线状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PresenceStatus {
+    Offline = 0,
+    Online = 1,
+    /// 离开
+    Away = 2,
+    /// 忙碌
+    Busy = 3,
+    /// 隐身, 对其他用户显示为离线
+    Invisible = 4,
+}
+impl PresenceStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Offline => "PRESENCE_STATUS_OFFLINE",
+            Self::Online => "PRESENCE_STATUS_ONLINE",
+            Self::Away => "PRESENCE_STATUS_AWAY",
+            Self::Busy => "PRESENCE_STATUS_BUSY",
+            Self::Invisible => "PRESENCE_STATUS_INVISIBLE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PRESENCE_STATUS_OFFLINE" => Some(Self::Offline),
+            "PRESENCE_STATUS_ONLINE" => Some(Self::Online),
+            "PRESENCE_STATUS_AWAY" => Some(Self::Away),
+            "PRESENCE_STATUS_BUSY" => Some(Self::Busy),
+            "PRESENCE_STATUS_INVISIBLE" => Some(Self::Invisible),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UserEventType {
+    /// 资料或隐私设置被修改
+    ProfileUpdated = 0,
+    /// 上线/下线或在线状态被修改
+    PresenceChanged = 1,
+}
+impl UserEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::ProfileUpdated => "USER_EVENT_TYPE_PROFILE_UPDATED",
+            Self::PresenceChanged => "USER_EVENT_TYPE_PRESENCE_CHANGED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "USER_EVENT_TYPE_PROFILE_UPDATED" => Some(Self::ProfileUpdated),
+            "USER_EVENT_TYPE_PRESENCE_CHANGED" => Some(Self::PresenceChanged),
+            _ => None,
+        }
+    }
+}
+/// 安全事件类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SecurityEventType {
+    Unspecified = 0,
+    LoginSuccess = 1,
+    LoginFailed = 2,
+    PasswordChanged = 3,
+    PasswordReset = 4,
+    SessionsRevoked = 5,
+    /// 请求发送验证码
+    CodeRequested = 6,
+    AccountDeactivated = 7,
+    AccountRestored = 8,
+    AccountBanned = 9,
+    AccountUnbanned = 10,
+    /// 申请导出个人数据
+    DataExportRequested = 11,
+}
+impl SecurityEventType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "SECURITY_EVENT_TYPE_UNSPECIFIED",
+            Self::LoginSuccess => "SECURITY_EVENT_TYPE_LOGIN_SUCCESS",
+            Self::LoginFailed => "SECURITY_EVENT_TYPE_LOGIN_FAILED",
+            Self::PasswordChanged => "SECURITY_EVENT_TYPE_PASSWORD_CHANGED",
+            Self::PasswordReset => "SECURITY_EVENT_TYPE_PASSWORD_RESET",
+            Self::SessionsRevoked => "SECURITY_EVENT_TYPE_SESSIONS_REVOKED",
+            Self::CodeRequested => "SECURITY_EVENT_TYPE_CODE_REQUESTED",
+            Self::AccountDeactivated => "SECURITY_EVENT_TYPE_ACCOUNT_DEACTIVATED",
+            Self::AccountRestored => "SECURITY_EVENT_TYPE_ACCOUNT_RESTORED",
+            Self::AccountBanned => "SECURITY_EVENT_TYPE_ACCOUNT_BANNED",
+            Self::AccountUnbanned => "SECURITY_EVENT_TYPE_ACCOUNT_UNBANNED",
+            Self::DataExportRequested => "SECURITY_EVENT_TYPE_DATA_EXPORT_REQUESTED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SECURITY_EVENT_TYPE_UNSPECIFIED" => Some(Self::Unspecified),
+            "SECURITY_EVENT_TYPE_LOGIN_SUCCESS" => Some(Self::LoginSuccess),
+            "SECURITY_EVENT_TYPE_LOGIN_FAILED" => Some(Self::LoginFailed),
+            "SECURITY_EVENT_TYPE_PASSWORD_CHANGED" => Some(Self::PasswordChanged),
+            "SECURITY_EVENT_TYPE_PASSWORD_RESET" => Some(Self::PasswordReset),
+            "SECURITY_EVENT_TYPE_SESSIONS_REVOKED" => Some(Self::SessionsRevoked),
+            "SECURITY_EVENT_TYPE_CODE_REQUESTED" => Some(Self::CodeRequested),
+            "SECURITY_EVENT_TYPE_ACCOUNT_DEACTIVATED" => Some(Self::AccountDeactivated),
+            "SECURITY_EVENT_TYPE_ACCOUNT_RESTORED" => Some(Self::AccountRestored),
+            "SECURITY_EVENT_TYPE_ACCOUNT_BANNED" => Some(Self::AccountBanned),
+            "SECURITY_EVENT_TYPE_ACCOUNT_UNBANNED" => Some(Self::AccountUnbanned),
+            "SECURITY_EVENT_TYPE_DATA_EXPORT_REQUESTED" => Some(Self::DataExportRequested),
+            _ => None,
+        }
+    }
+}
+/// 个人数据导出的状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DataExportStatus {
+    /// 等待后台任务处理
+    Pending = 0,
+    Running = 1,
+    /// 可以下载
+    Ready = 2,
+    Failed = 3,
+    /// 超过下载有效期, 文件已删除
+    Expired = 4,
+}
+impl DataExportStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Pending => "DATA_EXPORT_STATUS_PENDING",
+            Self::Running => "DATA_EXPORT_STATUS_RUNNING",
+            Self::Ready => "DATA_EXPORT_STATUS_READY",
+            Self::Failed => "DATA_EXPORT_STATUS_FAILED",
+            Self::Expired => "DATA_EXPORT_STATUS_EXPIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DATA_EXPORT_STATUS_PENDING" => Some(Self::Pending),
+            "DATA_EXPORT_STATUS_RUNNING" => Some(Self::Running),
+            "DATA_EXPORT_STATUS_READY" => Some(Self::Ready),
+            "DATA_EXPORT_STATUS_FAILED" => Some(Self::Failed),
+            "DATA_EXPORT_STATUS_EXPIRED" => Some(Self::Expired),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod user_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    #[derive(Debug, Clone)]
+    pub struct UserServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl UserServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> UserServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::Body>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> UserServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            UserServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn ping(
+            &mut self,
+            request: impl tonic::IntoRequest<super::Request>,
+        ) -> std::result::Result<tonic::Response<super::Response>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/Ping");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "Ping"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterRequest>,
+        ) -> std::result::Result<tonic::Response<super::RegisterResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/Register");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "Register"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn login(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/Login");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "Login"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 发送注册验证码到邮箱, 并返回验证码
+        pub async fn send_register_code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SendRegisterCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendRegisterCodeResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/SendRegisterCode");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "SendRegisterCode"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 统计用户在线数量
+        pub async fn get_user_online_count(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UserOnlineCountRequest>,
+        ) -> std::result::Result<tonic::Response<super::UserOnlineCountResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetUserOnlineCount");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetUserOnlineCount"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 获取用户信息, 按调用方与用户的关系裁剪字段
+        pub async fn get_user_info(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetUserInfoRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetUserInfoResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetUserInfo");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetUserInfo"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 查找用户, 按调用方与用户的关系裁剪字段
+        pub async fn find_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FindUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::FindUserResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/FindUser");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "FindUser"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 批量获取用户资料, 按调用方与用户的关系裁剪字段
+        pub async fn batch_get_users(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BatchGetUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::BatchGetUsersResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/BatchGetUsers");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "BatchGetUsers"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 获取隐私设置
+        pub async fn get_privacy_settings(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetPrivacySettingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetPrivacySettingsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetPrivacySettings");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetPrivacySettings"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 修改隐私设置
+        pub async fn update_privacy_settings(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdatePrivacySettingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdatePrivacySettingsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/UpdatePrivacySettings");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "UpdatePrivacySettings"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 按对方的隐私设置判断能否发送好友申请, 由好友服务调用
+        pub async fn can_send_friend_request(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CanSendFriendRequestRequest>,
+        ) -> std::result::Result<tonic::Response<super::CanSendFriendRequestResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/CanSendFriendRequest");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "CanSendFriendRequest"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 拉黑用户
+        pub async fn block_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BlockUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::BlockUserResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/BlockUser");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "BlockUser"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 取消拉黑
+        pub async fn unblock_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnblockUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::UnblockUserResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/UnblockUser");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "UnblockUser"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 分页查询拉黑的用户
+        pub async fn list_blocked_users(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListBlockedUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListBlockedUsersResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/ListBlockedUsers");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ListBlockedUsers"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 查询两个用户之间的拉黑关系
+        pub async fn is_blocked(
+            &mut self,
+            request: impl tonic::IntoRequest<super::IsBlockedRequest>,
+        ) -> std::result::Result<tonic::Response<super::IsBlockedResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/IsBlocked");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "IsBlocked"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 发送短信验证码
+        pub async fn send_sms_code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SendSmsCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendSmsCodeResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/SendSmsCode");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "SendSmsCode"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 手机号注册
+        pub async fn phone_register(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PhoneRegisterRequest>,
+        ) -> std::result::Result<tonic::Response<super::PhoneRegisterResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/PhoneRegister");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "PhoneRegister"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 手机号验证码登录
+        pub async fn phone_login(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PhoneLoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/PhoneLogin");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "PhoneLogin"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 开始绑定两步验证
+        pub async fn enroll_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EnrollTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::EnrollTotpResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/EnrollTotp");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "EnrollTotp"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 确认绑定两步验证
+        pub async fn confirm_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::ConfirmTotpResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/ConfirmTotp");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ConfirmTotp"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 关闭两步验证
+        pub async fn disable_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DisableTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::DisableTotpResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/DisableTotp");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "DisableTotp"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 完成两步验证登录
+        pub async fn verify_second_factor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::VerifySecondFactorRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/VerifySecondFactor");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "VerifySecondFactor"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 获取第三方登录/绑定的授权地址
+        pub async fn oidc_authorize(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OidcAuthorizeRequest>,
+        ) -> std::result::Result<tonic::Response<super::OidcAuthorizeResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/OidcAuthorize");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "OidcAuthorize"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 第三方登录, 首次登录时自动注册
+        pub async fn oidc_login(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OidcLoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/OidcLogin");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "OidcLogin"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 绑定第三方身份
+        pub async fn link_external_identity(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LinkExternalIdentityRequest>,
+        ) -> std::result::Result<tonic::Response<super::LinkExternalIdentityResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/LinkExternalIdentity");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "LinkExternalIdentity"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 解除绑定第三方身份
+        pub async fn unlink_external_identity(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnlinkExternalIdentityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UnlinkExternalIdentityResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/UnlinkExternalIdentity");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "user.UserService",
+                "UnlinkExternalIdentity",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 查询绑定的第三方身份
+        pub async fn list_external_identities(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExternalIdentitiesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExternalIdentitiesResponse>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/ListExternalIdentities");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "user.UserService",
+                "ListExternalIdentities",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 设备心跳
+        pub async fn heartbeat(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HeartbeatRequest>,
+        ) -> std::result::Result<tonic::Response<super::HeartbeatResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/Heartbeat");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "Heartbeat"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 设备下线
+        pub async fn device_offline(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeviceOfflineRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeviceOfflineResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/DeviceOffline");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "DeviceOffline"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 设置在线状态
+        pub async fn set_presence_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetPresenceStatusRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetPresenceStatusResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/SetPresenceStatus");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "SetPresenceStatus"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 批量查询在线状态
+        pub async fn get_users_presence(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetUsersPresenceRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetUsersPresenceResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetUsersPresence");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetUsersPresence"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 订阅用户资料和在线状态的变化, 多个实例之间通过Redis发布订阅同步
+        pub async fn watch_users(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WatchUsersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::UserEvent>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/WatchUsers");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "WatchUsers"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        /// 查询账号的安全事件
+        pub async fn list_security_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListSecurityEventsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListSecurityEventsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/ListSecurityEvents");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ListSecurityEvents"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 分页搜索用户, 按调用方与用户的关系裁剪字段
+        pub async fn search_users(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SearchUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::SearchUsersResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/SearchUsers");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "SearchUsers"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 发送重置密码验证码到邮箱
+        pub async fn send_password_reset_code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SendPasswordResetCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendPasswordResetCodeResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.UserService/SendPasswordResetCode");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "SendPasswordResetCode"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 通过邮箱验证码重置密码, 成功后注销所有已登录的会话
+        pub async fn reset_password(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ResetPasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ResetPasswordResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/ResetPassword");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ResetPassword"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 修改用户资料
+        pub async fn update_user_profile(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateUserProfileRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateUserProfileResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/UpdateUserProfile");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "UpdateUserProfile"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 修改密码, 需要校验旧密码
+        pub async fn change_password(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ChangePasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ChangePasswordResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/ChangePassword");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "ChangePassword"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 注销账号, 宽限期内可以恢复, 之后会被清理
+        pub async fn deactivate_account(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeactivateAccountRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeactivateAccountResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/DeactivateAccount");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "DeactivateAccount"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 宽限期内恢复已注销的账号
+        pub async fn restore_account(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestoreAccountRequest>,
+        ) -> std::result::Result<tonic::Response<super::RestoreAccountResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/RestoreAccount");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "RestoreAccount"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 申请导出个人数据, 由后台任务生成压缩包, 进行中或最近已导出时返回已有的导出
+        pub async fn request_data_export(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RequestDataExportRequest>,
+        ) -> std::result::Result<tonic::Response<super::RequestDataExportResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/RequestDataExport");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "RequestDataExport"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 查询导出状态, 完成后返回下载凭证
+        pub async fn get_data_export(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetDataExportRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetDataExportResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/GetDataExport");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "GetDataExport"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 下载导出的压缩包
+        pub async fn download_data_export(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DownloadDataExportRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::DataExportChunk>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.UserService/DownloadDataExport");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.UserService", "DownloadDataExport"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+    }
+}
+/// Generated client implementations.
+pub mod admin_user_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::http::Uri;
+    use tonic::codegen::*;
+    /// 管理接口, 调用方需要在metadata中携带`authorization: Bearer <token>`
+    #[derive(Debug, Clone)]
+    pub struct AdminUserServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl AdminUserServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> AdminUserServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::Body>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> AdminUserServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
+                Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            AdminUserServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// 按条件分页查询用户, 管理员和客服可用
+        pub async fn list_users(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AdminListUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::AdminListUsersResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.AdminUserService/ListUsers");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.AdminUserService", "ListUsers"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 封禁用户并注销所有会话, 仅管理员可用
+        pub async fn ban_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BanUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::BanUserResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.AdminUserService/BanUser");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.AdminUserService", "BanUser"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 解除封禁, 仅管理员可用
+        pub async fn unban_user(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnbanUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::UnbanUserResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/user.AdminUserService/UnbanUser");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("user.AdminUserService", "UnbanUser"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 强制重置密码并注销所有会话, 仅管理员可用
+        pub async fn force_reset_password(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ForceResetPasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ForceResetPasswordResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.AdminUserService/ForceResetPassword");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "user.AdminUserService",
+                "ForceResetPassword",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// 注销用户所有会话, 管理员和客服可用
+        pub async fn revoke_user_sessions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RevokeUserSessionsRequest>,
+        ) -> std::result::Result<tonic::Response<super::RevokeUserSessionsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/user.AdminUserService/RevokeUserSessions");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "user.AdminUserService",
+                "RevokeUserSessions",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod user_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with UserServiceServer.
+    #[async_trait]
+    pub trait UserService: std::marker::Send + std::marker::Sync + 'static {
+        async fn ping(
+            &self,
+            request: tonic::Request<super::Request>,
+        ) -> std::result::Result<tonic::Response<super::Response>, tonic::Status>;
+        async fn register(
+            &self,
+            request: tonic::Request<super::RegisterRequest>,
+        ) -> std::result::Result<tonic::Response<super::RegisterResponse>, tonic::Status>;
+        async fn login(
+            &self,
+            request: tonic::Request<super::LoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
+        /// 发送注册验证码到邮箱, 并返回验证码
+        async fn send_register_code(
+            &self,
+            request: tonic::Request<super::SendRegisterCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendRegisterCodeResponse>, tonic::Status>;
+        /// 统计用户在线数量
+        async fn get_user_online_count(
+            &self,
+            request: tonic::Request<super::UserOnlineCountRequest>,
+        ) -> std::result::Result<tonic::Response<super::UserOnlineCountResponse>, tonic::Status>;
+        /// 获取用户信息, 按调用方与用户的关系裁剪字段
+        async fn get_user_info(
+            &self,
+            request: tonic::Request<super::GetUserInfoRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetUserInfoResponse>, tonic::Status>;
+        /// 查找用户, 按调用方与用户的关系裁剪字段
+        async fn find_user(
+            &self,
+            request: tonic::Request<super::FindUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::FindUserResponse>, tonic::Status>;
+        /// 批量获取用户资料, 按调用方与用户的关系裁剪字段
+        async fn batch_get_users(
+            &self,
+            request: tonic::Request<super::BatchGetUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::BatchGetUsersResponse>, tonic::Status>;
+        /// 获取隐私设置
+        async fn get_privacy_settings(
+            &self,
+            request: tonic::Request<super::GetPrivacySettingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetPrivacySettingsResponse>, tonic::Status>;
+        /// 修改隐私设置
+        async fn update_privacy_settings(
+            &self,
+            request: tonic::Request<super::UpdatePrivacySettingsRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdatePrivacySettingsResponse>, tonic::Status>;
+        /// 按对方的隐私设置判断能否发送好友申请, 由好友服务调用
+        async fn can_send_friend_request(
+            &self,
+            request: tonic::Request<super::CanSendFriendRequestRequest>,
+        ) -> std::result::Result<tonic::Response<super::CanSendFriendRequestResponse>, tonic::Status>;
+        /// 拉黑用户
+        async fn block_user(
+            &self,
+            request: tonic::Request<super::BlockUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::BlockUserResponse>, tonic::Status>;
+        /// 取消拉黑
+        async fn unblock_user(
+            &self,
+            request: tonic::Request<super::UnblockUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::UnblockUserResponse>, tonic::Status>;
+        /// 分页查询拉黑的用户
+        async fn list_blocked_users(
+            &self,
+            request: tonic::Request<super::ListBlockedUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListBlockedUsersResponse>, tonic::Status>;
+        /// 查询两个用户之间的拉黑关系
+        async fn is_blocked(
+            &self,
+            request: tonic::Request<super::IsBlockedRequest>,
+        ) -> std::result::Result<tonic::Response<super::IsBlockedResponse>, tonic::Status>;
+        /// 发送短信验证码
+        async fn send_sms_code(
+            &self,
+            request: tonic::Request<super::SendSmsCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendSmsCodeResponse>, tonic::Status>;
+        /// 手机号注册
+        async fn phone_register(
+            &self,
+            request: tonic::Request<super::PhoneRegisterRequest>,
+        ) -> std::result::Result<tonic::Response<super::PhoneRegisterResponse>, tonic::Status>;
+        /// 手机号验证码登录
+        async fn phone_login(
+            &self,
+            request: tonic::Request<super::PhoneLoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
+        /// 开始绑定两步验证
+        async fn enroll_totp(
+            &self,
+            request: tonic::Request<super::EnrollTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::EnrollTotpResponse>, tonic::Status>;
+        /// 确认绑定两步验证
+        async fn confirm_totp(
+            &self,
+            request: tonic::Request<super::ConfirmTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::ConfirmTotpResponse>, tonic::Status>;
+        /// 关闭两步验证
+        async fn disable_totp(
+            &self,
+            request: tonic::Request<super::DisableTotpRequest>,
+        ) -> std::result::Result<tonic::Response<super::DisableTotpResponse>, tonic::Status>;
+        /// 完成两步验证登录
+        async fn verify_second_factor(
+            &self,
+            request: tonic::Request<super::VerifySecondFactorRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
+        /// 获取第三方登录/绑定的授权地址
+        async fn oidc_authorize(
+            &self,
+            request: tonic::Request<super::OidcAuthorizeRequest>,
+        ) -> std::result::Result<tonic::Response<super::OidcAuthorizeResponse>, tonic::Status>;
+        /// 第三方登录, 首次登录时自动注册
+        async fn oidc_login(
+            &self,
+            request: tonic::Request<super::OidcLoginRequest>,
+        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
+        /// 绑定第三方身份
+        async fn link_external_identity(
+            &self,
+            request: tonic::Request<super::LinkExternalIdentityRequest>,
+        ) -> std::result::Result<tonic::Response<super::LinkExternalIdentityResponse>, tonic::Status>;
+        /// 解除绑定第三方身份
+        async fn unlink_external_identity(
+            &self,
+            request: tonic::Request<super::UnlinkExternalIdentityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UnlinkExternalIdentityResponse>,
+            tonic::Status,
+        >;
+        /// 查询绑定的第三方身份
+        async fn list_external_identities(
+            &self,
+            request: tonic::Request<super::ListExternalIdentitiesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExternalIdentitiesResponse>,
+            tonic::Status,
+        >;
+        /// 设备心跳
+        async fn heartbeat(
+            &self,
+            request: tonic::Request<super::HeartbeatRequest>,
+        ) -> std::result::Result<tonic::Response<super::HeartbeatResponse>, tonic::Status>;
+        /// 设备下线
+        async fn device_offline(
+            &self,
+            request: tonic::Request<super::DeviceOfflineRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeviceOfflineResponse>, tonic::Status>;
+        /// 设置在线状态
+        async fn set_presence_status(
+            &self,
+            request: tonic::Request<super::SetPresenceStatusRequest>,
+        ) -> std::result::Result<tonic::Response<super::SetPresenceStatusResponse>, tonic::Status>;
+        /// 批量查询在线状态
+        async fn get_users_presence(
+            &self,
+            request: tonic::Request<super::GetUsersPresenceRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetUsersPresenceResponse>, tonic::Status>;
+        /// Server streaming response type for the WatchUsers method.
+        type WatchUsersStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::UserEvent, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        /// 订阅用户资料和在线状态的变化, 多个实例之间通过Redis发布订阅同步
+        async fn watch_users(
+            &self,
+            request: tonic::Request<super::WatchUsersRequest>,
+        ) -> std::result::Result<tonic::Response<Self::WatchUsersStream>, tonic::Status>;
+        /// 查询账号的安全事件
+        async fn list_security_events(
+            &self,
+            request: tonic::Request<super::ListSecurityEventsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListSecurityEventsResponse>, tonic::Status>;
+        /// 分页搜索用户, 按调用方与用户的关系裁剪字段
+        async fn search_users(
+            &self,
+            request: tonic::Request<super::SearchUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::SearchUsersResponse>, tonic::Status>;
+        /// 发送重置密码验证码到邮箱
+        async fn send_password_reset_code(
+            &self,
+            request: tonic::Request<super::SendPasswordResetCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendPasswordResetCodeResponse>, tonic::Status>;
+        /// 通过邮箱验证码重置密码, 成功后注销所有已登录的会话
+        async fn reset_password(
+            &self,
+            request: tonic::Request<super::ResetPasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ResetPasswordResponse>, tonic::Status>;
+        /// 修改用户资料
+        async fn update_user_profile(
+            &self,
+            request: tonic::Request<super::UpdateUserProfileRequest>,
+        ) -> std::result::Result<tonic::Response<super::UpdateUserProfileResponse>, tonic::Status>;
+        /// 修改密码, 需要校验旧密码
+        async fn change_password(
+            &self,
+            request: tonic::Request<super::ChangePasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ChangePasswordResponse>, tonic::Status>;
+        /// 注销账号, 宽限期内可以恢复, 之后会被清理
+        async fn deactivate_account(
+            &self,
+            request: tonic::Request<super::DeactivateAccountRequest>,
+        ) -> std::result::Result<tonic::Response<super::DeactivateAccountResponse>, tonic::Status>;
+        /// 宽限期内恢复已注销的账号
+        async fn restore_account(
+            &self,
+            request: tonic::Request<super::RestoreAccountRequest>,
+        ) -> std::result::Result<tonic::Response<super::RestoreAccountResponse>, tonic::Status>;
+        /// 申请导出个人数据, 由后台任务生成压缩包, 进行中或最近已导出时返回已有的导出
+        async fn request_data_export(
+            &self,
+            request: tonic::Request<super::RequestDataExportRequest>,
+        ) -> std::result::Result<tonic::Response<super::RequestDataExportResponse>, tonic::Status>;
+        /// 查询导出状态, 完成后返回下载凭证
+        async fn get_data_export(
+            &self,
+            request: tonic::Request<super::GetDataExportRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetDataExportResponse>, tonic::Status>;
+        /// Server streaming response type for the DownloadDataExport method.
+        type DownloadDataExportStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::DataExportChunk, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        /// 下载导出的压缩包
+        async fn download_data_export(
+            &self,
+            request: tonic::Request<super::DownloadDataExportRequest>,
+        ) -> std::result::Result<tonic::Response<Self::DownloadDataExportStream>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct UserServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> UserServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for UserServiceServer<T>
+    where
+        T: UserService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/user.UserService/Ping" => {
+                    #[allow(non_camel_case_types)]
+                    struct PingSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::Request> for PingSvc<T> {
+                        type Response = super::Response;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::Request>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as UserService>::ping(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/Register" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::RegisterRequest> for RegisterSvc<T> {
+                        type Response = super::RegisterResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RegisterRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as UserService>::register(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RegisterSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/Login" => {
+                    #[allow(non_camel_case_types)]
+                    struct LoginSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::LoginRequest> for LoginSvc<T> {
+                        type Response = super::LoginResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LoginRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as UserService>::login(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = LoginSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/SendRegisterCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct SendRegisterCodeSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::SendRegisterCodeRequest>
+                        for SendRegisterCodeSvc<T>
+                    {
+                        type Response = super::SendRegisterCodeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SendRegisterCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::send_register_code(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SendRegisterCodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetUserOnlineCount" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUserOnlineCountSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::UserOnlineCountRequest>
+                        for GetUserOnlineCountSvc<T>
+                    {
+                        type Response = super::UserOnlineCountResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UserOnlineCountRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_user_online_count(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetUserOnlineCountSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetUserInfo" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUserInfoSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::GetUserInfoRequest> for GetUserInfoSvc<T> {
+                        type Response = super::GetUserInfoResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetUserInfoRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_user_info(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetUserInfoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/FindUser" => {
+                    #[allow(non_camel_case_types)]
+                    struct FindUserSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::FindUserRequest> for FindUserSvc<T> {
+                        type Response = super::FindUserResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FindUserRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as UserService>::find_user(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FindUserSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/BatchGetUsers" => {
+                    #[allow(non_camel_case_types)]
+                    struct BatchGetUsersSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::BatchGetUsersRequest>
+                        for BatchGetUsersSvc<T>
+                    {
+                        type Response = super::BatchGetUsersResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BatchGetUsersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::batch_get_users(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BatchGetUsersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetPrivacySettings" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPrivacySettingsSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::GetPrivacySettingsRequest>
+                        for GetPrivacySettingsSvc<T>
+                    {
+                        type Response = super::GetPrivacySettingsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPrivacySettingsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_privacy_settings(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetPrivacySettingsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/UpdatePrivacySettings" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdatePrivacySettingsSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::UpdatePrivacySettingsRequest>
+                        for UpdatePrivacySettingsSvc<T>
+                    {
+                        type Response = super::UpdatePrivacySettingsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdatePrivacySettingsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::update_privacy_settings(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdatePrivacySettingsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/CanSendFriendRequest" => {
+                    #[allow(non_camel_case_types)]
+                    struct CanSendFriendRequestSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::CanSendFriendRequestRequest>
+                        for CanSendFriendRequestSvc<T>
+                    {
+                        type Response = super::CanSendFriendRequestResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CanSendFriendRequestRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::can_send_friend_request(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CanSendFriendRequestSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/BlockUser" => {
+                    #[allow(non_camel_case_types)]
+                    struct BlockUserSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::BlockUserRequest> for BlockUserSvc<T> {
+                        type Response = super::BlockUserResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BlockUserRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::block_user(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BlockUserSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/UnblockUser" => {
+                    #[allow(non_camel_case_types)]
+                    struct UnblockUserSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::UnblockUserRequest> for UnblockUserSvc<T> {
+                        type Response = super::UnblockUserResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UnblockUserRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::unblock_user(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UnblockUserSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ListBlockedUsers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListBlockedUsersSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::ListBlockedUsersRequest>
+                        for ListBlockedUsersSvc<T>
+                    {
+                        type Response = super::ListBlockedUsersResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListBlockedUsersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::list_blocked_users(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListBlockedUsersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/IsBlocked" => {
+                    #[allow(non_camel_case_types)]
+                    struct IsBlockedSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::IsBlockedRequest> for IsBlockedSvc<T> {
+                        type Response = super::IsBlockedResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::IsBlockedRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::is_blocked(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = IsBlockedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/SendSmsCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct SendSmsCodeSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::SendSmsCodeRequest> for SendSmsCodeSvc<T> {
+                        type Response = super::SendSmsCodeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SendSmsCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::send_sms_code(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SendSmsCodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/PhoneRegister" => {
+                    #[allow(non_camel_case_types)]
+                    struct PhoneRegisterSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::PhoneRegisterRequest>
+                        for PhoneRegisterSvc<T>
+                    {
+                        type Response = super::PhoneRegisterResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PhoneRegisterRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::phone_register(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PhoneRegisterSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/PhoneLogin" => {
+                    #[allow(non_camel_case_types)]
+                    struct PhoneLoginSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::PhoneLoginRequest> for PhoneLoginSvc<T> {
+                        type Response = super::LoginResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PhoneLoginRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::phone_login(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PhoneLoginSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/EnrollTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct EnrollTotpSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::EnrollTotpRequest> for EnrollTotpSvc<T> {
+                        type Response = super::EnrollTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EnrollTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::enroll_totp(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = EnrollTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ConfirmTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConfirmTotpSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::ConfirmTotpRequest> for ConfirmTotpSvc<T> {
+                        type Response = super::ConfirmTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConfirmTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::confirm_totp(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConfirmTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/DisableTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct DisableTotpSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::DisableTotpRequest> for DisableTotpSvc<T> {
+                        type Response = super::DisableTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DisableTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::disable_totp(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DisableTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/VerifySecondFactor" => {
+                    #[allow(non_camel_case_types)]
+                    struct VerifySecondFactorSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::VerifySecondFactorRequest>
+                        for VerifySecondFactorSvc<T>
+                    {
+                        type Response = super::LoginResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::VerifySecondFactorRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::verify_second_factor(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = VerifySecondFactorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/OidcAuthorize" => {
+                    #[allow(non_camel_case_types)]
+                    struct OidcAuthorizeSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::OidcAuthorizeRequest>
+                        for OidcAuthorizeSvc<T>
+                    {
+                        type Response = super::OidcAuthorizeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OidcAuthorizeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::oidc_authorize(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = OidcAuthorizeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/OidcLogin" => {
+                    #[allow(non_camel_case_types)]
+                    struct OidcLoginSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::OidcLoginRequest> for OidcLoginSvc<T> {
+                        type Response = super::LoginResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OidcLoginRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::oidc_login(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = OidcLoginSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/LinkExternalIdentity" => {
+                    #[allow(non_camel_case_types)]
+                    struct LinkExternalIdentitySvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::LinkExternalIdentityRequest>
+                        for LinkExternalIdentitySvc<T>
+                    {
+                        type Response = super::LinkExternalIdentityResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LinkExternalIdentityRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::link_external_identity(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = LinkExternalIdentitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/UnlinkExternalIdentity" => {
+                    #[allow(non_camel_case_types)]
+                    struct UnlinkExternalIdentitySvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::UnlinkExternalIdentityRequest>
+                        for UnlinkExternalIdentitySvc<T>
+                    {
+                        type Response = super::UnlinkExternalIdentityResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UnlinkExternalIdentityRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::unlink_external_identity(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UnlinkExternalIdentitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ListExternalIdentities" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExternalIdentitiesSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::ListExternalIdentitiesRequest>
+                        for ListExternalIdentitiesSvc<T>
+                    {
+                        type Response = super::ListExternalIdentitiesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExternalIdentitiesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::list_external_identities(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListExternalIdentitiesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/Heartbeat" => {
+                    #[allow(non_camel_case_types)]
+                    struct HeartbeatSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::HeartbeatRequest> for HeartbeatSvc<T> {
+                        type Response = super::HeartbeatResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HeartbeatRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as UserService>::heartbeat(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = HeartbeatSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/DeviceOffline" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeviceOfflineSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::DeviceOfflineRequest>
+                        for DeviceOfflineSvc<T>
+                    {
+                        type Response = super::DeviceOfflineResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeviceOfflineRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::device_offline(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeviceOfflineSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/SetPresenceStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetPresenceStatusSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::SetPresenceStatusRequest>
+                        for SetPresenceStatusSvc<T>
+                    {
+                        type Response = super::SetPresenceStatusResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetPresenceStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::set_presence_status(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SetPresenceStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetUsersPresence" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUsersPresenceSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::GetUsersPresenceRequest>
+                        for GetUsersPresenceSvc<T>
+                    {
+                        type Response = super::GetUsersPresenceResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetUsersPresenceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_users_presence(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetUsersPresenceSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/WatchUsers" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchUsersSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::ServerStreamingService<super::WatchUsersRequest>
+                        for WatchUsersSvc<T>
+                    {
+                        type Response = super::UserEvent;
+                        type ResponseStream = T::WatchUsersStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WatchUsersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::watch_users(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WatchUsersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ListSecurityEvents" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSecurityEventsSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::ListSecurityEventsRequest>
+                        for ListSecurityEventsSvc<T>
+                    {
+                        type Response = super::ListSecurityEventsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSecurityEventsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::list_security_events(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListSecurityEventsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/SearchUsers" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchUsersSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::SearchUsersRequest> for SearchUsersSvc<T> {
+                        type Response = super::SearchUsersResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchUsersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::search_users(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SearchUsersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/SendPasswordResetCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct SendPasswordResetCodeSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::SendPasswordResetCodeRequest>
+                        for SendPasswordResetCodeSvc<T>
+                    {
+                        type Response = super::SendPasswordResetCodeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SendPasswordResetCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::send_password_reset_code(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SendPasswordResetCodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ResetPassword" => {
+                    #[allow(non_camel_case_types)]
+                    struct ResetPasswordSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::ResetPasswordRequest>
+                        for ResetPasswordSvc<T>
+                    {
+                        type Response = super::ResetPasswordResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ResetPasswordRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::reset_password(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ResetPasswordSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/UpdateUserProfile" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateUserProfileSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::UpdateUserProfileRequest>
+                        for UpdateUserProfileSvc<T>
+                    {
+                        type Response = super::UpdateUserProfileResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateUserProfileRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::update_user_profile(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateUserProfileSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/ChangePassword" => {
+                    #[allow(non_camel_case_types)]
+                    struct ChangePasswordSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::ChangePasswordRequest>
+                        for ChangePasswordSvc<T>
+                    {
+                        type Response = super::ChangePasswordResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ChangePasswordRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::change_password(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ChangePasswordSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/DeactivateAccount" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeactivateAccountSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::DeactivateAccountRequest>
+                        for DeactivateAccountSvc<T>
+                    {
+                        type Response = super::DeactivateAccountResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeactivateAccountRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::deactivate_account(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DeactivateAccountSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/RestoreAccount" => {
+                    #[allow(non_camel_case_types)]
+                    struct RestoreAccountSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::RestoreAccountRequest>
+                        for RestoreAccountSvc<T>
+                    {
+                        type Response = super::RestoreAccountResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RestoreAccountRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::restore_account(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RestoreAccountSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/RequestDataExport" => {
+                    #[allow(non_camel_case_types)]
+                    struct RequestDataExportSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::UnaryService<super::RequestDataExportRequest>
+                        for RequestDataExportSvc<T>
+                    {
+                        type Response = super::RequestDataExportResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RequestDataExportRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::request_data_export(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RequestDataExportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/GetDataExport" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetDataExportSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService> tonic::server::UnaryService<super::GetDataExportRequest>
+                        for GetDataExportSvc<T>
+                    {
+                        type Response = super::GetDataExportResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetDataExportRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::get_data_export(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetDataExportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/user.UserService/DownloadDataExport" => {
+                    #[allow(non_camel_case_types)]
+                    struct DownloadDataExportSvc<T: UserService>(pub Arc<T>);
+                    impl<T: UserService>
+                        tonic::server::ServerStreamingService<super::DownloadDataExportRequest>
+                        for DownloadDataExportSvc<T>
+                    {
+                        type Response = super::DataExportChunk;
+                        type ResponseStream = T::DownloadDataExportStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DownloadDataExportRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as UserService>::download_data_export(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DownloadDataExportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    let mut response = http::Response::new(tonic::body::Body::default());
+                    let headers = response.headers_mut();
+                    headers.insert(
+                        tonic::Status::GRPC_STATUS,
+                        (tonic::Code::Unimplemented as i32).into(),
+                    );
+                    headers.insert(
+                        http::header::CONTENT_TYPE,
+                        tonic::metadata::GRPC_CONTENT_TYPE,
+                    );
+                    Ok(response)
+                }),
+            }
         }
-        /// 查找用户
-        pub async fn find_user(
-            &mut self,
-            request: impl tonic::IntoRequest<super::FindUserRequest>,
-        ) -> std::result::Result<tonic::Response<super::FindUserResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/user.UserService/FindUser");
-            let mut req = request.into_request();
-            req.extensions_mut()
-                .insert(GrpcMethod::new("user.UserService", "FindUser"));
-            self.inner.unary(req, path, codec).await
+    }
+    impl<T> Clone for UserServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
         }
     }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "user.UserService";
+    impl<T> tonic::server::NamedService for UserServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
 }
 /// Generated server implementations.
-pub mod user_service_server {
+pub mod admin_user_service_server {
     #![allow(
         unused_variables,
         dead_code,
@@ -341,51 +4185,45 @@ pub mod user_service_server {
         clippy::let_unit_value
     )]
     use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with UserServiceServer.
+    /// Generated trait containing gRPC methods that should be implemented for use with AdminUserServiceServer.
     #[async_trait]
-    pub trait UserService: std::marker::Send + std::marker::Sync + 'static {
-        async fn ping(
-            &self,
-            request: tonic::Request<super::Request>,
-        ) -> std::result::Result<tonic::Response<super::Response>, tonic::Status>;
-        async fn register(
-            &self,
-            request: tonic::Request<super::RegisterRequest>,
-        ) -> std::result::Result<tonic::Response<super::RegisterResponse>, tonic::Status>;
-        async fn login(
+    pub trait AdminUserService: std::marker::Send + std::marker::Sync + 'static {
+        /// 按条件分页查询用户, 管理员和客服可用
+        async fn list_users(
             &self,
-            request: tonic::Request<super::LoginRequest>,
-        ) -> std::result::Result<tonic::Response<super::LoginResponse>, tonic::Status>;
-        /// 发送注册验证码到邮箱, 并返回验证码
-        async fn send_register_code(
+            request: tonic::Request<super::AdminListUsersRequest>,
+        ) -> std::result::Result<tonic::Response<super::AdminListUsersResponse>, tonic::Status>;
+        /// 封禁用户并注销所有会话, 仅管理员可用
+        async fn ban_user(
             &self,
-            request: tonic::Request<super::SendRegisterCodeRequest>,
-        ) -> std::result::Result<tonic::Response<super::SendRegisterCodeResponse>, tonic::Status>;
-        /// 统计用户在线数量
-        async fn get_user_online_count(
+            request: tonic::Request<super::BanUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::BanUserResponse>, tonic::Status>;
+        /// 解除封禁, 仅管理员可用
+        async fn unban_user(
             &self,
-            request: tonic::Request<super::UserOnlineCountRequest>,
-        ) -> std::result::Result<tonic::Response<super::UserOnlineCountResponse>, tonic::Status>;
-        /// 获取用户信息
-        async fn get_user_info(
+            request: tonic::Request<super::UnbanUserRequest>,
+        ) -> std::result::Result<tonic::Response<super::UnbanUserResponse>, tonic::Status>;
+        /// 强制重置密码并注销所有会话, 仅管理员可用
+        async fn force_reset_password(
             &self,
-            request: tonic::Request<super::GetUserInfoRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetUserInfoResponse>, tonic::Status>;
-        /// 查找用户
-        async fn find_user(
+            request: tonic::Request<super::ForceResetPasswordRequest>,
+        ) -> std::result::Result<tonic::Response<super::ForceResetPasswordResponse>, tonic::Status>;
+        /// 注销用户所有会话, 管理员和客服可用
+        async fn revoke_user_sessions(
             &self,
-            request: tonic::Request<super::FindUserRequest>,
-        ) -> std::result::Result<tonic::Response<super::FindUserResponse>, tonic::Status>;
+            request: tonic::Request<super::RevokeUserSessionsRequest>,
+        ) -> std::result::Result<tonic::Response<super::RevokeUserSessionsResponse>, tonic::Status>;
     }
+    /// 管理接口, 调用方需要在metadata中携带`authorization: Bearer <token>`
     #[derive(Debug)]
-    pub struct UserServiceServer<T> {
+    pub struct AdminUserServiceServer<T> {
         inner: Arc<T>,
         accept_compression_encodings: EnabledCompressionEncodings,
         send_compression_encodings: EnabledCompressionEncodings,
         max_decoding_message_size: Option<usize>,
         max_encoding_message_size: Option<usize>,
     }
-    impl<T> UserServiceServer<T> {
+    impl<T> AdminUserServiceServer<T> {
         pub fn new(inner: T) -> Self {
             Self::from_arc(Arc::new(inner))
         }
@@ -433,9 +4271,9 @@ pub mod user_service_server {
             self
         }
     }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for UserServiceServer<T>
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AdminUserServiceServer<T>
     where
-        T: UserService,
+        T: AdminUserService,
         B: Body + std::marker::Send + 'static,
         B::Error: Into<StdError> + std::marker::Send + 'static,
     {
@@ -450,57 +4288,23 @@ pub mod user_service_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
-                "/user.UserService/Ping" => {
-                    #[allow(non_camel_case_types)]
-                    struct PingSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::Request> for PingSvc<T> {
-                        type Response = super::Response;
-                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::Request>,
-                        ) -> Self::Future {
-                            let inner = Arc::clone(&self.0);
-                            let fut =
-                                async move { <T as UserService>::ping(&inner, request).await };
-                            Box::pin(fut)
-                        }
-                    }
-                    let accept_compression_encodings = self.accept_compression_encodings;
-                    let send_compression_encodings = self.send_compression_encodings;
-                    let max_decoding_message_size = self.max_decoding_message_size;
-                    let max_encoding_message_size = self.max_encoding_message_size;
-                    let inner = self.inner.clone();
-                    let fut = async move {
-                        let method = PingSvc(inner);
-                        let codec = tonic::codec::ProstCodec::default();
-                        let mut grpc = tonic::server::Grpc::new(codec)
-                            .apply_compression_config(
-                                accept_compression_encodings,
-                                send_compression_encodings,
-                            )
-                            .apply_max_message_size_config(
-                                max_decoding_message_size,
-                                max_encoding_message_size,
-                            );
-                        let res = grpc.unary(method, req).await;
-                        Ok(res)
-                    };
-                    Box::pin(fut)
-                }
-                "/user.UserService/Register" => {
+                "/user.AdminUserService/ListUsers" => {
                     #[allow(non_camel_case_types)]
-                    struct RegisterSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::RegisterRequest> for RegisterSvc<T> {
-                        type Response = super::RegisterResponse;
+                    struct ListUsersSvc<T: AdminUserService>(pub Arc<T>);
+                    impl<T: AdminUserService>
+                        tonic::server::UnaryService<super::AdminListUsersRequest>
+                        for ListUsersSvc<T>
+                    {
+                        type Response = super::AdminListUsersResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RegisterRequest>,
+                            request: tonic::Request<super::AdminListUsersRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut =
-                                async move { <T as UserService>::register(&inner, request).await };
+                            let fut = async move {
+                                <T as AdminUserService>::list_users(&inner, request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -510,7 +4314,7 @@ pub mod user_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RegisterSvc(inner);
+                        let method = ListUsersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -526,19 +4330,20 @@ pub mod user_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/user.UserService/Login" => {
+                "/user.AdminUserService/BanUser" => {
                     #[allow(non_camel_case_types)]
-                    struct LoginSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::LoginRequest> for LoginSvc<T> {
-                        type Response = super::LoginResponse;
+                    struct BanUserSvc<T: AdminUserService>(pub Arc<T>);
+                    impl<T: AdminUserService> tonic::server::UnaryService<super::BanUserRequest> for BanUserSvc<T> {
+                        type Response = super::BanUserResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::LoginRequest>,
+                            request: tonic::Request<super::BanUserRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut =
-                                async move { <T as UserService>::login(&inner, request).await };
+                            let fut = async move {
+                                <T as AdminUserService>::ban_user(&inner, request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -548,7 +4353,7 @@ pub mod user_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = LoginSvc(inner);
+                        let method = BanUserSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -564,21 +4369,19 @@ pub mod user_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/user.UserService/SendRegisterCode" => {
+                "/user.AdminUserService/UnbanUser" => {
                     #[allow(non_camel_case_types)]
-                    struct SendRegisterCodeSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::SendRegisterCodeRequest>
-                        for SendRegisterCodeSvc<T>
-                    {
-                        type Response = super::SendRegisterCodeResponse;
+                    struct UnbanUserSvc<T: AdminUserService>(pub Arc<T>);
+                    impl<T: AdminUserService> tonic::server::UnaryService<super::UnbanUserRequest> for UnbanUserSvc<T> {
+                        type Response = super::UnbanUserResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SendRegisterCodeRequest>,
+                            request: tonic::Request<super::UnbanUserRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserService>::send_register_code(&inner, request).await
+                                <T as AdminUserService>::unban_user(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -589,7 +4392,7 @@ pub mod user_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = SendRegisterCodeSvc(inner);
+                        let method = UnbanUserSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -605,21 +4408,22 @@ pub mod user_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/user.UserService/GetUserOnlineCount" => {
+                "/user.AdminUserService/ForceResetPassword" => {
                     #[allow(non_camel_case_types)]
-                    struct GetUserOnlineCountSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::UserOnlineCountRequest>
-                        for GetUserOnlineCountSvc<T>
+                    struct ForceResetPasswordSvc<T: AdminUserService>(pub Arc<T>);
+                    impl<T: AdminUserService>
+                        tonic::server::UnaryService<super::ForceResetPasswordRequest>
+                        for ForceResetPasswordSvc<T>
                     {
-                        type Response = super::UserOnlineCountResponse;
+                        type Response = super::ForceResetPasswordResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UserOnlineCountRequest>,
+                            request: tonic::Request<super::ForceResetPasswordRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserService>::get_user_online_count(&inner, request).await
+                                <T as AdminUserService>::force_reset_password(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -630,7 +4434,7 @@ pub mod user_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetUserOnlineCountSvc(inner);
+                        let method = ForceResetPasswordSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -646,19 +4450,22 @@ pub mod user_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/user.UserService/GetUserInfo" => {
+                "/user.AdminUserService/RevokeUserSessions" => {
                     #[allow(non_camel_case_types)]
-                    struct GetUserInfoSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::GetUserInfoRequest> for GetUserInfoSvc<T> {
-                        type Response = super::GetUserInfoResponse;
+                    struct RevokeUserSessionsSvc<T: AdminUserService>(pub Arc<T>);
+                    impl<T: AdminUserService>
+                        tonic::server::UnaryService<super::RevokeUserSessionsRequest>
+                        for RevokeUserSessionsSvc<T>
+                    {
+                        type Response = super::RevokeUserSessionsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetUserInfoRequest>,
+                            request: tonic::Request<super::RevokeUserSessionsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as UserService>::get_user_info(&inner, request).await
+                                <T as AdminUserService>::revoke_user_sessions(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -669,45 +4476,7 @@ pub mod user_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetUserInfoSvc(inner);
-                        let codec = tonic::codec::ProstCodec::default();
-                        let mut grpc = tonic::server::Grpc::new(codec)
-                            .apply_compression_config(
-                                accept_compression_encodings,
-                                send_compression_encodings,
-                            )
-                            .apply_max_message_size_config(
-                                max_decoding_message_size,
-                                max_encoding_message_size,
-                            );
-                        let res = grpc.unary(method, req).await;
-                        Ok(res)
-                    };
-                    Box::pin(fut)
-                }
-                "/user.UserService/FindUser" => {
-                    #[allow(non_camel_case_types)]
-                    struct FindUserSvc<T: UserService>(pub Arc<T>);
-                    impl<T: UserService> tonic::server::UnaryService<super::FindUserRequest> for FindUserSvc<T> {
-                        type Response = super::FindUserResponse;
-                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::FindUserRequest>,
-                        ) -> Self::Future {
-                            let inner = Arc::clone(&self.0);
-                            let fut =
-                                async move { <T as UserService>::find_user(&inner, request).await };
-                            Box::pin(fut)
-                        }
-                    }
-                    let accept_compression_encodings = self.accept_compression_encodings;
-                    let send_compression_encodings = self.send_compression_encodings;
-                    let max_decoding_message_size = self.max_decoding_message_size;
-                    let max_encoding_message_size = self.max_encoding_message_size;
-                    let inner = self.inner.clone();
-                    let fut = async move {
-                        let method = FindUserSvc(inner);
+                        let method = RevokeUserSessionsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -739,7 +4508,7 @@ pub mod user_service_server {
             }
         }
     }
-    impl<T> Clone for UserServiceServer<T> {
+    impl<T> Clone for AdminUserServiceServer<T> {
         fn clone(&self) -> Self {
             let inner = self.inner.clone();
             Self {
@@ -752,8 +4521,8 @@ pub mod user_service_server {
         }
     }
     /// Generated gRPC service name
-    pub const SERVICE_NAME: &str = "user.UserService";
-    impl<T> tonic::server::NamedService for UserServiceServer<T> {
+    pub const SERVICE_NAME: &str = "user.AdminUserService";
+    impl<T> tonic::server::NamedService for AdminUserServiceServer<T> {
         const NAME: &'static str = SERVICE_NAME;
     }
 }
@@ -0,0 +1,81 @@
+use crate::error::Error;
+use crate::repo::{BlockRepo, UserBlock};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// 内存实现的BlockRepo, id自增
+#[derive(Debug, Default)]
+pub struct MemoryBlockRepo {
+    blocks: Mutex<Vec<UserBlock>>,
+}
+
+impl MemoryBlockRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BlockRepo for MemoryBlockRepo {
+    async fn insert(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let mut blocks = self.blocks.lock().unwrap();
+        if blocks
+            .iter()
+            .any(|block| block.user_id == user_id && block.blocked_user_id == blocked_user_id)
+        {
+            return Ok(false);
+        }
+        let id = blocks.last().map_or(1, |last| last.id + 1);
+        blocks.push(UserBlock {
+            id,
+            user_id: user_id.to_string(),
+            blocked_user_id: blocked_user_id.to_string(),
+            create_time: chrono::Utc::now().timestamp_millis(),
+        });
+        Ok(true)
+    }
+
+    async fn delete(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let mut blocks = self.blocks.lock().unwrap();
+        let before = blocks.len();
+        blocks.retain(|block| {
+            !(block.user_id == user_id && block.blocked_user_id == blocked_user_id)
+        });
+        Ok(blocks.len() != before)
+    }
+
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<UserBlock>, Error> {
+        let blocks = self.blocks.lock().unwrap();
+        let blocks = blocks
+            .iter()
+            .rev()
+            .filter(|block| block.user_id == user_id)
+            .filter(|block| before_id.is_none_or(|before_id| block.id < before_id))
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect();
+        Ok(blocks)
+    }
+
+    async fn find_blocked_ids(&self, user_id: &str) -> Result<Vec<String>, Error> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(blocks
+            .iter()
+            .filter(|block| block.user_id == user_id)
+            .map(|block| block.blocked_user_id.clone())
+            .collect())
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        self.blocks
+            .lock()
+            .unwrap()
+            .retain(|block| block.user_id != user_id && block.blocked_user_id != user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::Error;
+use crate::pb::user::DataExportStatus;
+use crate::repo::{DataExport, DataExportRepo};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// 内存实现的DataExportRepo, 按申请顺序保存
+#[derive(Debug, Default)]
+pub struct MemoryDataExportRepo {
+    exports: Mutex<Vec<DataExport>>,
+}
+
+impl MemoryDataExportRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut DataExport)) {
+        let mut exports = self.exports.lock().unwrap();
+        if let Some(export) = exports.iter_mut().find(|export| export.id == id) {
+            f(export);
+            export.update_time = chrono::Utc::now().timestamp_millis();
+        }
+    }
+}
+
+#[async_trait]
+impl DataExportRepo for MemoryDataExportRepo {
+    async fn insert(&self, export: &DataExport) -> Result<(), Error> {
+        self.exports.lock().unwrap().push(export.clone());
+        Ok(())
+    }
+
+    async fn find(&self, id: &str) -> Result<Option<DataExport>, Error> {
+        let exports = self.exports.lock().unwrap();
+        Ok(exports.iter().find(|export| export.id == id).cloned())
+    }
+
+    async fn find_latest(&self, user_id: &str) -> Result<Option<DataExport>, Error> {
+        let exports = self.exports.lock().unwrap();
+        Ok(exports
+            .iter()
+            .rev()
+            .find(|export| export.user_id == user_id)
+            .cloned())
+    }
+
+    async fn claim(&self, stale_before: i64, limit: i64) -> Result<Vec<DataExport>, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut exports = self.exports.lock().unwrap();
+        let claimed = exports
+            .iter_mut()
+            .filter(|export| match export.status {
+                DataExportStatus::Pending => true,
+                DataExportStatus::Running => export.update_time < stale_before,
+                _ => false,
+            })
+            .take(limit.max(0) as usize)
+            .map(|export| {
+                export.status = DataExportStatus::Running;
+                export.update_time = now;
+                export.clone()
+            })
+            .collect();
+        Ok(claimed)
+    }
+
+    async fn mark_ready(&self, id: &str, file_size: i64, expire_time: i64) -> Result<(), Error> {
+        self.update(id, |export| {
+            export.status = DataExportStatus::Ready;
+            export.file_size = file_size;
+            export.expire_time = expire_time;
+        });
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &str, error: &str) -> Result<(), Error> {
+        self.update(id, |export| {
+            export.status = DataExportStatus::Failed;
+            export.error = error.to_string();
+        });
+        Ok(())
+    }
+
+    async fn expire(&self, now: i64, limit: i64) -> Result<Vec<String>, Error> {
+        let mut exports = self.exports.lock().unwrap();
+        let expired = exports
+            .iter_mut()
+            .filter(|export| export.status == DataExportStatus::Ready && export.expire_time <= now)
+            .take(limit.max(0) as usize)
+            .map(|export| {
+                export.status = DataExportStatus::Expired;
+                export.update_time = now;
+                export.id.clone()
+            })
+            .collect();
+        Ok(expired)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<Vec<String>, Error> {
+        let mut exports = self.exports.lock().unwrap();
+        let ids = exports
+            .iter()
+            .filter(|export| export.user_id == user_id)
+            .map(|export| export.id.clone())
+            .collect();
+        exports.retain(|export| export.user_id != user_id);
+        Ok(ids)
+    }
+}
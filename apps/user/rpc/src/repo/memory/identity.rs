@@ -0,0 +1,71 @@
+use crate::error::{Error, ErrorKind};
+use crate::repo::{IdentityRepo, UserIdentity};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// 内存实现的IdentityRepo
+#[derive(Debug, Default)]
+pub struct MemoryIdentityRepo {
+    rows: Mutex<Vec<UserIdentity>>,
+}
+
+impl MemoryIdentityRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IdentityRepo for MemoryIdentityRepo {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error> {
+        let rows = self.rows.lock().unwrap();
+        let identity = rows
+            .iter()
+            .find(|identity| identity.provider == provider && identity.subject == subject)
+            .cloned();
+        Ok(identity)
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<UserIdentity>, Error> {
+        let rows = self.rows.lock().unwrap();
+        let mut identities: Vec<UserIdentity> = rows
+            .iter()
+            .filter(|identity| identity.user_id == user_id)
+            .cloned()
+            .collect();
+        identities.sort_by_key(|identity| identity.create_time);
+        Ok(identities)
+    }
+
+    async fn insert(&self, identity: &UserIdentity) -> Result<(), Error> {
+        let mut rows = self.rows.lock().unwrap();
+        // 与数据库约束一致: (provider, subject)唯一, 每个用户每个提供方只能绑定一个身份
+        let conflict = rows.iter().any(|row| {
+            row.provider == identity.provider
+                && (row.subject == identity.subject || row.user_id == identity.user_id)
+        });
+        if conflict {
+            return Err(Error::with_details(
+                ErrorKind::DbError,
+                "duplicate user identity",
+            ));
+        }
+        rows.push(identity.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str, provider: &str) -> Result<bool, Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let len = rows.len();
+        rows.retain(|identity| !(identity.user_id == user_id && identity.provider == provider));
+        Ok(rows.len() < len)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|identity| identity.user_id != user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,613 @@
+use crate::error::{Error, ErrorKind};
+use crate::event::{UserChange, UserChangeKind, EVENT_BUFFER_SIZE};
+use crate::pb::user::{PresenceStatus, UserPresence};
+use crate::repo::Cache;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+pub(crate) mod block;
+pub(crate) mod data_export;
+pub(crate) mod identity;
+pub(crate) mod privacy;
+pub(crate) mod security_event;
+pub(crate) mod totp;
+pub(crate) mod user;
+
+const REGISTER_CODE_KEY: &str = "register_code";
+const PASSWORD_RESET_CODE_KEY: &str = "password_reset_code";
+const SMS_CODE_KEY: &str = "sms_code";
+const LOGIN_CHALLENGE_KEY: &str = "login_challenge";
+const OIDC_STATE_KEY: &str = "oidc_state";
+const FAILED_ATTEMPTS_KEY: &str = "failed_attempts";
+const LOCKOUT_KEY: &str = "lockout";
+
+/// 验证码的过期时间, 与RedisCache一致
+const CODE_TTL_SECONDS: u64 = 300;
+const PASSWORD_RESET_CODE_TTL_SECONDS: u64 = 600;
+
+#[derive(Debug, Default)]
+struct State {
+    /// key -> (值, 过期时间(毫秒))
+    values: HashMap<String, (String, Option<i64>)>,
+    sessions_revoked_at: HashMap<String, i64>,
+    /// 用户 -> `{platform}:{device_id}` -> 过期时间(毫秒)
+    devices: HashMap<String, BTreeMap<String, i64>>,
+    statuses: HashMap<String, PresenceStatus>,
+    last_seen: HashMap<String, i64>,
+    /// 对应RedisCache的在线集合, 由expire_presence移除过期的用户
+    online: HashSet<String>,
+    /// 滑动窗口内的请求时间(毫秒)
+    requests: HashMap<String, Vec<i64>>,
+}
+
+impl State {
+    fn get(&mut self, key: &str, now: i64) -> Option<String> {
+        if self
+            .values
+            .get(key)
+            .is_some_and(|(_, expire_at)| expire_at.is_some_and(|expire_at| expire_at <= now))
+        {
+            self.values.remove(key);
+        }
+        self.values.get(key).map(|(value, _)| value.clone())
+    }
+
+    fn set(&mut self, key: String, value: String, ttl_seconds: u64, now: i64) {
+        let expire_at = now + ttl_seconds as i64 * 1000;
+        self.values.insert(key, (value, Some(expire_at)));
+    }
+
+    /// 移除设备, `members`为空时移除全部设备, 非隐身时记录最后在线时间
+    fn offline(&mut self, user_id: &str, members: &[String], now: i64) {
+        let Some(devices) = self.devices.get_mut(user_id) else {
+            return;
+        };
+        let removed = if members.is_empty() {
+            let removed = !devices.is_empty();
+            devices.clear();
+            removed
+        } else {
+            let before = devices.len();
+            for member in members {
+                devices.remove(member);
+            }
+            devices.len() != before
+        };
+        if self.online_devices(user_id, now).is_empty() {
+            self.online.remove(user_id);
+        }
+        if removed && self.statuses.get(user_id) != Some(&PresenceStatus::Invisible) {
+            self.last_seen.insert(user_id.to_string(), now);
+        }
+    }
+
+    /// 在线的平台, 已排序
+    fn online_platforms(&self, user_id: &str, now: i64) -> Vec<String> {
+        let mut platforms: Vec<String> = self
+            .online_devices(user_id, now)
+            .iter()
+            .filter_map(|device| device.split_once(':'))
+            .map(|(platform, _)| platform.to_string())
+            .collect();
+        platforms.sort();
+        platforms.dedup();
+        platforms
+    }
+
+    /// 未过期的设备
+    fn online_devices(&self, user_id: &str, now: i64) -> Vec<&str> {
+        self.devices
+            .get(user_id)
+            .map(|devices| {
+                devices
+                    .iter()
+                    .filter(|(_, expire_at)| **expire_at > now)
+                    .map(|(member, _)| member.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// 内存实现的Cache, 行为与RedisCache一致, 用于不依赖Redis的测试
+#[derive(Debug)]
+pub struct MemoryCache {
+    state: Mutex<State>,
+    events: broadcast::Sender<UserChange>,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self {
+            state: Mutex::default(),
+            events,
+        }
+    }
+
+    /// 在线状态变化通知的发送端, 对应RedisCache发布到的频道
+    pub fn events(&self) -> broadcast::Sender<UserChange> {
+        self.events.clone()
+    }
+
+    fn notify_presence(&self, user_id: &str) {
+        // 没有订阅者时发送失败, 可以忽略
+        let _ = self
+            .events
+            .send(UserChange::new(UserChangeKind::Presence, user_id));
+    }
+
+    /// 下线设备, 在线的平台因此变化且用户不是隐身时发送通知
+    fn offline(&self, user_id: &str, members: &[String]) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut state = self.state.lock().unwrap();
+        let before = state.online_platforms(user_id, now);
+        state.offline(user_id, members, now);
+        let changed = state.online_platforms(user_id, now) != before;
+        let invisible = state.statuses.get(user_id) == Some(&PresenceStatus::Invisible);
+        drop(state);
+        if changed && !invisible {
+            self.notify_presence(user_id);
+        }
+    }
+
+    fn get_code(&self, scope: &str, target: &str) -> Result<String, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.state
+            .lock()
+            .unwrap()
+            .get(&format!("{}:{}", scope, target), now)
+            .ok_or_else(|| Error::with_details(ErrorKind::NotFound, "code not found"))
+    }
+
+    fn get_value(&self, scope: &str, target: &str) -> Option<String> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.state
+            .lock()
+            .unwrap()
+            .get(&format!("{}:{}", scope, target), now)
+    }
+
+    fn save_value(&self, scope: &str, target: &str, value: &str, ttl_seconds: u64) {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.state.lock().unwrap().set(
+            format!("{}:{}", scope, target),
+            value.to_string(),
+            ttl_seconds,
+            now,
+        );
+    }
+
+    fn delete_value(&self, scope: &str, target: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .values
+            .remove(&format!("{}:{}", scope, target));
+    }
+
+    fn count_online(&self, platform: Option<&str>) -> i64 {
+        let now = chrono::Utc::now().timestamp_millis();
+        let state = self.state.lock().unwrap();
+        state
+            .devices
+            .keys()
+            .filter(|user_id| {
+                state.online_devices(user_id, now).iter().any(|member| {
+                    platform.is_none_or(|platform| {
+                        member
+                            .split_once(':')
+                            .is_some_and(|(device_platform, _)| device_platform == platform)
+                    })
+                })
+            })
+            .count() as i64
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get_user_register_code(&self, account: &str) -> Result<String, Error> {
+        self.get_code(REGISTER_CODE_KEY, account)
+    }
+
+    async fn save_user_register_code(&self, account: &str, code: &str) -> Result<(), Error> {
+        self.save_value(REGISTER_CODE_KEY, account, code, CODE_TTL_SECONDS);
+        Ok(())
+    }
+
+    async fn delete_user_register_code(&self, account: &str) -> Result<(), Error> {
+        self.delete_value(REGISTER_CODE_KEY, account);
+        Ok(())
+    }
+
+    async fn get_password_reset_code(&self, email: &str) -> Result<String, Error> {
+        self.get_code(PASSWORD_RESET_CODE_KEY, email)
+    }
+
+    async fn save_password_reset_code(&self, email: &str, code: &str) -> Result<(), Error> {
+        self.save_value(
+            PASSWORD_RESET_CODE_KEY,
+            email,
+            code,
+            PASSWORD_RESET_CODE_TTL_SECONDS,
+        );
+        Ok(())
+    }
+
+    async fn delete_password_reset_code(&self, email: &str) -> Result<(), Error> {
+        self.delete_value(PASSWORD_RESET_CODE_KEY, email);
+        Ok(())
+    }
+
+    async fn get_sms_code(&self, scene: &str, phone: &str) -> Result<String, Error> {
+        self.get_code(SMS_CODE_KEY, &format!("{}:{}", scene, phone))
+    }
+
+    async fn save_sms_code(&self, scene: &str, phone: &str, code: &str) -> Result<(), Error> {
+        let target = format!("{}:{}", scene, phone);
+        self.save_value(SMS_CODE_KEY, &target, code, CODE_TTL_SECONDS);
+        Ok(())
+    }
+
+    async fn delete_sms_code(&self, scene: &str, phone: &str) -> Result<(), Error> {
+        self.delete_value(SMS_CODE_KEY, &format!("{}:{}", scene, phone));
+        Ok(())
+    }
+
+    async fn save_login_challenge(
+        &self,
+        token: &str,
+        user_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        self.save_value(LOGIN_CHALLENGE_KEY, token, user_id, ttl_seconds);
+        Ok(())
+    }
+
+    async fn get_login_challenge(&self, token: &str) -> Result<Option<String>, Error> {
+        Ok(self.get_value(LOGIN_CHALLENGE_KEY, token))
+    }
+
+    async fn delete_login_challenge(&self, token: &str) -> Result<(), Error> {
+        self.delete_value(LOGIN_CHALLENGE_KEY, token);
+        Ok(())
+    }
+
+    async fn save_oidc_state(
+        &self,
+        state: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        self.save_value(OIDC_STATE_KEY, state, value, ttl_seconds);
+        Ok(())
+    }
+
+    async fn take_oidc_state(&self, state: &str) -> Result<Option<String>, Error> {
+        let value = self.get_value(OIDC_STATE_KEY, state);
+        self.delete_value(OIDC_STATE_KEY, state);
+        Ok(value)
+    }
+
+    async fn revoke_user_sessions(&self, user_id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.state
+            .lock()
+            .unwrap()
+            .sessions_revoked_at
+            .insert(user_id.to_string(), now);
+        self.offline(user_id, &[]);
+        Ok(())
+    }
+
+    async fn get_user_sessions_revoked_at(&self, user_id: &str) -> Result<Option<i64>, Error> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .sessions_revoked_at
+            .get(user_id)
+            .copied())
+    }
+
+    async fn heartbeat(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut state = self.state.lock().unwrap();
+        let before = state.online_platforms(user_id, now);
+        let devices = state.devices.entry(user_id.to_string()).or_default();
+        devices.retain(|_, expire_at| *expire_at > now);
+        devices.insert(
+            format!("{}:{}", platform, device_id),
+            now + ttl_seconds as i64 * 1000,
+        );
+        state.online.insert(user_id.to_string());
+        if state.statuses.get(user_id) == Some(&PresenceStatus::Invisible) {
+            return Ok(());
+        }
+        state.last_seen.insert(user_id.to_string(), now);
+        let changed = !before.iter().any(|p| p == platform);
+        drop(state);
+        if changed {
+            self.notify_presence(user_id);
+        }
+        Ok(())
+    }
+
+    async fn device_offline(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+    ) -> Result<(), Error> {
+        self.offline(user_id, &[format!("{}:{}", platform, device_id)]);
+        Ok(())
+    }
+
+    async fn set_presence_status(
+        &self,
+        user_id: &str,
+        status: PresenceStatus,
+    ) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        if status == PresenceStatus::Online {
+            state.statuses.remove(user_id);
+        } else {
+            state.statuses.insert(user_id.to_string(), status);
+        }
+        drop(state);
+        self.notify_presence(user_id);
+        Ok(())
+    }
+
+    async fn get_users_presence(&self, user_ids: &[String]) -> Result<Vec<UserPresence>, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let state = self.state.lock().unwrap();
+        let presences = user_ids
+            .iter()
+            .map(|user_id| {
+                let platforms = state.online_platforms(user_id, now);
+                let status = if platforms.is_empty() {
+                    PresenceStatus::Offline
+                } else {
+                    state
+                        .statuses
+                        .get(user_id)
+                        .copied()
+                        .unwrap_or(PresenceStatus::Online)
+                };
+                UserPresence {
+                    user_id: user_id.clone(),
+                    status: status as i32,
+                    last_seen: state.last_seen.get(user_id).copied().unwrap_or_default(),
+                    platforms,
+                }
+            })
+            .collect();
+        Ok(presences)
+    }
+
+    async fn get_user_online_count(&self) -> Result<i64, Error> {
+        Ok(self.count_online(None))
+    }
+
+    async fn get_platform_online_count(&self, platform: &str) -> Result<i64, Error> {
+        Ok(self.count_online(Some(platform)))
+    }
+
+    async fn expire_presence(&self, limit: usize) -> Result<Vec<String>, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut state = self.state.lock().unwrap();
+        let expired: Vec<String> = state
+            .online
+            .iter()
+            .filter(|user_id| state.online_devices(user_id, now).is_empty())
+            .take(limit)
+            .cloned()
+            .collect();
+        let mut notify = Vec::new();
+        for user_id in &expired {
+            state.online.remove(user_id);
+            if state.statuses.get(user_id) != Some(&PresenceStatus::Invisible) {
+                notify.push(user_id);
+            }
+        }
+        drop(state);
+        for user_id in notify {
+            self.notify_presence(user_id);
+        }
+        Ok(expired)
+    }
+
+    async fn check_rate_limit(
+        &self,
+        key: &str,
+        limit: u64,
+        window_seconds: u64,
+    ) -> Result<Option<u64>, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let window = window_seconds as i64 * 1000;
+        let mut state = self.state.lock().unwrap();
+        let requests = state.requests.entry(key.to_string()).or_default();
+        requests.retain(|time| *time > now - window);
+        if (requests.len() as u64) < limit {
+            requests.push(now);
+            return Ok(None);
+        }
+        let wait_millis = requests[0] + window - now;
+        Ok(Some((wait_millis.max(0) as u64).div_ceil(1000).max(1)))
+    }
+
+    async fn incr_failed_attempts(&self, key: &str, window_seconds: u64) -> Result<u64, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let key = format!("{}:{}", FAILED_ATTEMPTS_KEY, key);
+        let mut state = self.state.lock().unwrap();
+        let count = match state.get(&key, now) {
+            Some(count) => {
+                let count = count.parse::<u64>().unwrap_or_default() + 1;
+                // 只在第一次失败时设置过期时间
+                if let Some((value, _)) = state.values.get_mut(&key) {
+                    *value = count.to_string();
+                }
+                count
+            }
+            None => {
+                state.set(key, "1".to_string(), window_seconds, now);
+                1
+            }
+        };
+        Ok(count)
+    }
+
+    async fn clear_failed_attempts(&self, key: &str) -> Result<(), Error> {
+        self.delete_value(FAILED_ATTEMPTS_KEY, key);
+        Ok(())
+    }
+
+    async fn set_lockout(&self, key: &str, seconds: u64) -> Result<(), Error> {
+        self.save_value(LOCKOUT_KEY, key, "1", seconds);
+        Ok(())
+    }
+
+    async fn get_lockout_ttl(&self, key: &str) -> Result<Option<u64>, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let key = format!("{}:{}", LOCKOUT_KEY, key);
+        let mut state = self.state.lock().unwrap();
+        if state.get(&key, now).is_none() {
+            return Ok(None);
+        }
+        let ttl = state.values[&key]
+            .1
+            .map(|expire_at| ((expire_at - now).max(0) as u64).div_ceil(1000));
+        Ok(ttl.filter(|ttl| *ttl > 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_codes_and_rate_limit() {
+        let cache = MemoryCache::new();
+        assert!(cache.get_user_register_code("lucas").await.is_err());
+        cache
+            .save_user_register_code("lucas", "123456")
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.get_user_register_code("lucas").await.unwrap(),
+            "123456"
+        );
+        cache.delete_user_register_code("lucas").await.unwrap();
+        assert!(cache.get_user_register_code("lucas").await.is_err());
+
+        cache.save_oidc_state("state", "value", 60).await.unwrap();
+        assert_eq!(
+            cache.take_oidc_state("state").await.unwrap().as_deref(),
+            Some("value")
+        );
+        assert_eq!(cache.take_oidc_state("state").await.unwrap(), None);
+
+        for _ in 0..2 {
+            assert_eq!(cache.check_rate_limit("key", 2, 60).await.unwrap(), None);
+        }
+        assert!(matches!(
+            cache.check_rate_limit("key", 2, 60).await.unwrap(),
+            Some(1..=60)
+        ));
+
+        assert_eq!(cache.incr_failed_attempts("key", 60).await.unwrap(), 1);
+        assert_eq!(cache.incr_failed_attempts("key", 60).await.unwrap(), 2);
+        cache.clear_failed_attempts("key").await.unwrap();
+        assert_eq!(cache.incr_failed_attempts("key", 60).await.unwrap(), 1);
+
+        cache.set_lockout("key", 60).await.unwrap();
+        assert!(matches!(
+            cache.get_lockout_ttl("key").await.unwrap(),
+            Some(1..=60)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_presence() {
+        let cache = MemoryCache::new();
+        let user_id = "user".to_string();
+
+        cache.heartbeat(&user_id, "ios", "d1", 60).await.unwrap();
+        cache.heartbeat(&user_id, "web", "d2", 60).await.unwrap();
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].status, PresenceStatus::Online as i32);
+        assert_eq!(presences[0].platforms, vec!["ios", "web"]);
+        assert_eq!(cache.get_user_online_count().await.unwrap(), 1);
+        assert_eq!(cache.get_platform_online_count("ios").await.unwrap(), 1);
+
+        // 一个设备下线后仍然在线
+        cache.device_offline(&user_id, "ios", "d1").await.unwrap();
+        assert_eq!(cache.get_platform_online_count("ios").await.unwrap(), 0);
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].platforms, vec!["web"]);
+
+        // 注销会话后全部下线
+        cache.revoke_user_sessions(&user_id).await.unwrap();
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].status, PresenceStatus::Offline as i32);
+        assert!(cache
+            .get_user_sessions_revoked_at(&user_id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_presence_events() {
+        let cache = MemoryCache::new();
+        let mut events = cache.events().subscribe();
+
+        // 同一平台的第二个设备不改变在线状态
+        cache.heartbeat("user", "ios", "d1", 60).await.unwrap();
+        cache.heartbeat("user", "ios", "d2", 60).await.unwrap();
+        cache.device_offline("user", "ios", "d1").await.unwrap();
+        cache.device_offline("user", "ios", "d2").await.unwrap();
+        for _ in 0..2 {
+            let change = events.try_recv().unwrap();
+            assert_eq!(change, UserChange::new(UserChangeKind::Presence, "user"));
+        }
+        assert!(events.try_recv().is_err());
+
+        // 隐身时不通知上线, 心跳过期后由expire_presence移除
+        cache
+            .set_presence_status("user", PresenceStatus::Invisible)
+            .await
+            .unwrap();
+        assert!(events.try_recv().is_ok());
+        cache.heartbeat("user", "web", "d3", 0).await.unwrap();
+        assert!(events.try_recv().is_err());
+        assert_eq!(cache.expire_presence(10).await.unwrap(), vec!["user"]);
+        assert!(events.try_recv().is_err());
+        assert!(cache.expire_presence(10).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::Error;
+use crate::repo::{PrivacyRepo, UserPrivacy};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 内存实现的PrivacyRepo
+#[derive(Debug, Default)]
+pub struct MemoryPrivacyRepo {
+    rows: Mutex<HashMap<String, UserPrivacy>>,
+}
+
+impl MemoryPrivacyRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PrivacyRepo for MemoryPrivacyRepo {
+    async fn find(&self, user_id: &str) -> Result<UserPrivacy, Error> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows
+            .get(user_id)
+            .cloned()
+            .unwrap_or_else(|| UserPrivacy::new(user_id)))
+    }
+
+    async fn find_by_users(&self, user_ids: &[String]) -> Result<Vec<UserPrivacy>, Error> {
+        let rows = self.rows.lock().unwrap();
+        let privacies = user_ids
+            .iter()
+            .map(|id| {
+                rows.get(id)
+                    .cloned()
+                    .unwrap_or_else(|| UserPrivacy::new(id))
+            })
+            .collect();
+        Ok(privacies)
+    }
+
+    async fn save(&self, privacy: &UserPrivacy) -> Result<(), Error> {
+        self.rows
+            .lock()
+            .unwrap()
+            .insert(privacy.user_id.clone(), privacy.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), Error> {
+        self.rows.lock().unwrap().remove(user_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::Error;
+use crate::repo::{SecurityEvent, SecurityEventRepo};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// 内存实现的SecurityEventRepo, id自增
+#[derive(Debug, Default)]
+pub struct MemorySecurityEventRepo {
+    events: Mutex<Vec<SecurityEvent>>,
+}
+
+impl MemorySecurityEventRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SecurityEventRepo for MemorySecurityEventRepo {
+    async fn insert(&self, event: &SecurityEvent) -> Result<(), Error> {
+        let mut events = self.events.lock().unwrap();
+        let id = events.last().map_or(1, |last| last.id + 1);
+        events.push(SecurityEvent {
+            id,
+            ..event.clone()
+        });
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, Error> {
+        let events = self.events.lock().unwrap();
+        let events = events
+            .iter()
+            .rev()
+            .filter(|event| event.user_id.as_deref() == Some(user_id))
+            .filter(|event| before_id.is_none_or(|before_id| event.id < before_id))
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect();
+        Ok(events)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        self.events
+            .lock()
+            .unwrap()
+            .retain(|event| event.user_id.as_deref() != Some(user_id));
+        Ok(())
+    }
+}
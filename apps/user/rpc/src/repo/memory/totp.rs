@@ -0,0 +1,92 @@
+use crate::error::Error;
+use crate::repo::{TotpRepo, UserTotp};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 内存实现的TotpRepo
+#[derive(Debug, Default)]
+pub struct MemoryTotpRepo {
+    rows: Mutex<HashMap<String, UserTotp>>,
+}
+
+impl MemoryTotpRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TotpRepo for MemoryTotpRepo {
+    async fn find(&self, user_id: &str) -> Result<Option<UserTotp>, Error> {
+        Ok(self.rows.lock().unwrap().get(user_id).cloned())
+    }
+
+    async fn save_pending(&self, user_id: &str, secret: &str) -> Result<(), Error> {
+        let mut rows = self.rows.lock().unwrap();
+        // 已启用的记录不会被覆盖
+        if rows.get(user_id).is_some_and(|totp| totp.enabled) {
+            return Ok(());
+        }
+        rows.insert(
+            user_id.to_string(),
+            UserTotp {
+                user_id: user_id.to_string(),
+                secret: secret.to_string(),
+                enabled: false,
+                recovery_codes: vec![],
+                last_used_step: 0,
+            },
+        );
+        Ok(())
+    }
+
+    async fn enable(
+        &self,
+        user_id: &str,
+        recovery_codes: &[String],
+        used_step: i64,
+    ) -> Result<(), Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let totp = rows
+            .get_mut(user_id)
+            .filter(|totp| !totp.enabled)
+            .ok_or_else(|| Error::from(sqlx::Error::RowNotFound))?;
+        totp.enabled = true;
+        totp.recovery_codes = recovery_codes.to_vec();
+        totp.last_used_step = used_step;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), Error> {
+        self.rows.lock().unwrap().remove(user_id);
+        Ok(())
+    }
+
+    async fn use_step(&self, user_id: &str, step: i64) -> Result<bool, Error> {
+        let mut rows = self.rows.lock().unwrap();
+        match rows.get_mut(user_id) {
+            Some(totp) if totp.last_used_step < step => {
+                totp.last_used_step = step;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let Some(totp) = rows.get_mut(user_id).filter(|totp| totp.enabled) else {
+            return Ok(false);
+        };
+        let Some(index) = totp
+            .recovery_codes
+            .iter()
+            .position(|code| code == code_hash)
+        else {
+            return Ok(false);
+        };
+        totp.recovery_codes.remove(index);
+        Ok(true)
+    }
+}
@@ -0,0 +1,395 @@
+use crate::config::PurgeMode;
+use crate::error::{Error, ErrorKind};
+use crate::pb::user::User;
+use crate::repo::{DeletedUser, ProfileField, SearchCursor, UserFilter, UserRepo};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone)]
+struct Row {
+    user: User,
+    delete_time: Option<i64>,
+    purge_time: Option<i64>,
+}
+
+impl Row {
+    fn is_deleted(&self) -> bool {
+        self.delete_time.is_some()
+    }
+}
+
+/// 内存实现的UserRepo, 行为与UserPostgres一致, 用于不依赖数据库的测试
+///
+/// 搜索只做前缀匹配, 不支持trigram模糊匹配
+#[derive(Debug, Default)]
+pub struct MemoryUserRepo {
+    rows: Mutex<HashMap<String, Row>>,
+}
+
+impl MemoryUserRepo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find(&self, predicate: impl Fn(&User) -> bool) -> Option<User> {
+        self.rows
+            .lock()
+            .unwrap()
+            .values()
+            .find(|row| !row.is_deleted() && predicate(&row.user))
+            .map(|row| row.user.clone())
+    }
+
+    /// 按(create_time, id)倒序返回游标之后的`limit`个用户
+    fn page(
+        &self,
+        predicate: impl Fn(&User) -> bool,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Vec<User> {
+        let rows = self.rows.lock().unwrap();
+        let mut users: Vec<User> = rows
+            .values()
+            .filter(|row| !row.is_deleted() && predicate(&row.user))
+            .map(|row| row.user.clone())
+            .filter(|user| {
+                cursor.as_ref().is_none_or(|cursor| {
+                    (user.create_time, user.id.as_str()) < (cursor.create_time, cursor.id.as_str())
+                })
+            })
+            .collect();
+        users.sort_by(|a, b| (b.create_time, &b.id).cmp(&(a.create_time, &a.id)));
+        users.truncate(limit.max(0) as usize);
+        users
+    }
+
+    /// 更新未注销的用户, 不存在时返回RowNotFound
+    fn update(&self, id: &str, f: impl FnOnce(&mut User)) -> Result<User, Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let row = rows
+            .get_mut(id)
+            .filter(|row| !row.is_deleted())
+            .ok_or_else(|| Error::from(sqlx::Error::RowNotFound))?;
+        f(&mut row.user);
+        row.user.update_time = chrono::Utc::now().timestamp_millis();
+        Ok(row.user.clone())
+    }
+}
+
+fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+    value.to_lowercase().starts_with(&prefix.to_lowercase())
+}
+
+#[async_trait]
+impl UserRepo for MemoryUserRepo {
+    async fn find_by_id(&self, id: &str) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.id == id))
+    }
+
+    async fn find_by_ids(&self, ids: Vec<String>) -> Result<Vec<User>, Error> {
+        let rows = self.rows.lock().unwrap();
+        let users = ids
+            .iter()
+            .filter_map(|id| rows.get(id))
+            .filter(|row| !row.is_deleted())
+            .map(|row| row.user.clone())
+            .collect();
+        Ok(users)
+    }
+
+    async fn find_by_account(&self, account: &str) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.account == account))
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.email.as_deref() == Some(email)))
+    }
+
+    async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.phone.as_deref() == Some(phone)))
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.name == name))
+    }
+
+    async fn search(
+        &self,
+        keyword: &str,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        let users = self.page(
+            |user| {
+                starts_with_ignore_case(&user.name, keyword)
+                    || starts_with_ignore_case(&user.account, keyword)
+            },
+            cursor,
+            limit,
+        );
+        Ok(users)
+    }
+
+    async fn list(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        let users = self.page(
+            |user| {
+                let keyword_matched = filter.keyword.as_deref().is_none_or(|keyword| {
+                    starts_with_ignore_case(&user.name, keyword)
+                        || starts_with_ignore_case(&user.account, keyword)
+                        || user
+                            .email
+                            .as_deref()
+                            .is_some_and(|email| starts_with_ignore_case(email, keyword))
+                        || user
+                            .phone
+                            .as_deref()
+                            .is_some_and(|phone| phone.starts_with(keyword))
+                });
+                keyword_matched
+                    && filter.role.is_none_or(|role| user.role() == role)
+                    && filter.banned.is_none_or(|banned| user.banned == banned)
+            },
+            cursor,
+            limit,
+        );
+        Ok(users)
+    }
+
+    async fn find_by_account_or_email(
+        &self,
+        account: &str,
+        email: &str,
+    ) -> Result<Option<User>, Error> {
+        Ok(self.find(|user| user.account == account || user.email.as_deref() == Some(email)))
+    }
+
+    async fn insert(&self, mut user: User) -> Result<(), Error> {
+        let mut rows = self.rows.lock().unwrap();
+        if rows.contains_key(&user.id) {
+            return Err(Error::with_details(
+                ErrorKind::DbError,
+                format!("duplicate user id `{}`", user.id),
+            ));
+        }
+        // 与数据库中的唯一索引一致, 手机号不能重复
+        if let Some(phone) = &user.phone {
+            if rows
+                .values()
+                .any(|row| !row.is_deleted() && row.user.phone.as_ref() == Some(phone))
+            {
+                return Err(Error::with_details(
+                    ErrorKind::DbError,
+                    format!("duplicate phone `{}`", phone),
+                ));
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        user.create_time = now;
+        user.update_time = now;
+        rows.insert(
+            user.id.clone(),
+            Row {
+                user,
+                delete_time: None,
+                purge_time: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn update_password(&self, id: &str, password: &str) -> Result<(), Error> {
+        self.update(id, |user| user.password = password.to_string())?;
+        Ok(())
+    }
+
+    async fn update_profile(
+        &self,
+        id: &str,
+        user: &User,
+        fields: &[ProfileField],
+    ) -> Result<User, Error> {
+        self.update(id, |current| {
+            for field in fields {
+                match field {
+                    ProfileField::Name => current.name = user.name.clone(),
+                    ProfileField::Avatar => current.avatar = user.avatar.clone(),
+                    ProfileField::Gender => current.gender = user.gender.clone(),
+                    ProfileField::Age => current.age = user.age,
+                    ProfileField::Signature => current.signature = user.signature.clone(),
+                    ProfileField::Address => current.address = user.address.clone(),
+                    ProfileField::Region => current.region = user.region.clone(),
+                    ProfileField::Birthday => current.birthday = user.birthday,
+                }
+            }
+        })
+    }
+
+    async fn ban(&self, id: &str, reason: &str, until: i64) -> Result<(), Error> {
+        self.update(id, |user| {
+            user.banned = true;
+            user.ban_reason = reason.to_string();
+            user.ban_until = until;
+        })?;
+        Ok(())
+    }
+
+    async fn unban(&self, id: &str) -> Result<(), Error> {
+        self.update(id, |user| {
+            user.banned = false;
+            user.ban_reason.clear();
+            user.ban_until = 0;
+        })?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        if let Some(row) = self
+            .rows
+            .lock()
+            .unwrap()
+            .get_mut(id)
+            .filter(|row| !row.is_deleted())
+        {
+            row.delete_time = Some(now);
+            row.user.update_time = now;
+        }
+        Ok(())
+    }
+
+    async fn find_deleted_by_account(&self, account: &str) -> Result<Option<DeletedUser>, Error> {
+        let rows = self.rows.lock().unwrap();
+        let deleted = rows
+            .values()
+            .filter(|row| row.purge_time.is_none() && row.user.account == account)
+            .filter_map(|row| {
+                row.delete_time.map(|delete_time| DeletedUser {
+                    user: row.user.clone(),
+                    delete_time,
+                })
+            })
+            .max_by_key(|deleted| deleted.delete_time);
+        Ok(deleted)
+    }
+
+    async fn restore(&self, id: &str) -> Result<(), Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let row = rows
+            .get_mut(id)
+            .filter(|row| row.is_deleted() && row.purge_time.is_none())
+            .ok_or_else(|| Error::from(sqlx::Error::RowNotFound))?;
+        row.delete_time = None;
+        row.user.update_time = chrono::Utc::now().timestamp_millis();
+        Ok(())
+    }
+
+    async fn purge_deleted(
+        &self,
+        before: i64,
+        limit: i64,
+        mode: PurgeMode,
+    ) -> Result<Vec<String>, Error> {
+        let mut rows = self.rows.lock().unwrap();
+        let ids: Vec<String> = rows
+            .values()
+            .filter(|row| {
+                row.purge_time.is_none() && row.delete_time.is_some_and(|time| time < before)
+            })
+            .map(|row| row.user.id.clone())
+            .take(limit.max(0) as usize)
+            .collect();
+
+        let now = chrono::Utc::now().timestamp_millis();
+        for id in &ids {
+            match mode {
+                PurgeMode::Anonymize => {
+                    let row = rows.get_mut(id).expect("purged row exists");
+                    row.user = User {
+                        id: id.clone(),
+                        account: format!("deleted:{}", id),
+                        create_time: row.user.create_time,
+                        update_time: now,
+                        ..Default::default()
+                    };
+                    row.purge_time = Some(now);
+                }
+                PurgeMode::Delete => {
+                    rows.remove(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, name: &str) -> User {
+        User {
+            id: id.to_string(),
+            name: name.to_string(),
+            account: format!("account_{}", id),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_restore_and_purge() {
+        let repo = MemoryUserRepo::new();
+        repo.insert(user("1", "lucas")).await.unwrap();
+        assert!(repo.insert(user("1", "lucas")).await.is_err());
+
+        repo.delete("1").await.unwrap();
+        assert!(repo.find_by_id("1").await.unwrap().is_none());
+        let deleted = repo
+            .find_deleted_by_account("account_1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deleted.user.id, "1");
+
+        repo.restore("1").await.unwrap();
+        assert!(repo.find_by_id("1").await.unwrap().is_some());
+
+        repo.delete("1").await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+        let ids = repo
+            .purge_deleted(now + 1, 10, PurgeMode::Anonymize)
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["1"]);
+        assert!(repo.restore("1").await.is_err());
+        assert!(repo
+            .find_deleted_by_account("account_1")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_with_cursor() {
+        let repo = MemoryUserRepo::new();
+        for (id, name) in [("1", "lucas"), ("2", "Luna"), ("3", "tom")] {
+            repo.insert(user(id, name)).await.unwrap();
+        }
+
+        let users = repo.search("lu", None, 1).await.unwrap();
+        assert_eq!(users.len(), 1);
+        let cursor = SearchCursor {
+            create_time: users[0].create_time,
+            id: users[0].id.clone(),
+        };
+        let next = repo.search("lu", Some(cursor), 10).await.unwrap();
+        assert_eq!(next.len(), 1);
+        assert_ne!(next[0].id, users[0].id);
+    }
+}
@@ -1,12 +1,88 @@
+use crate::config::PurgeMode;
 use crate::error::Error;
 
-use crate::pb::user::User;
+use crate::pb::user::{
+    DataExportStatus, FriendRequestPolicy, PresenceStatus, PrivacyScope, PrivacySettings,
+    SecurityEventType, User, UserPresence, UserRole,
+};
 use async_trait::async_trait;
 use std::fmt::Debug;
 
+#[cfg(test)]
+pub(crate) mod memory;
+pub(crate) mod mongodb;
 pub(crate) mod postgres;
 pub(crate) mod redis;
 
+/// 允许用户修改的资料字段, 与`UpdateUserProfileRequest.update_mask`中的路径对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileField {
+    Name,
+    Avatar,
+    Gender,
+    Age,
+    Signature,
+    Address,
+    Region,
+    Birthday,
+}
+
+impl ProfileField {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let field = match path {
+            "name" => Self::Name,
+            "avatar" => Self::Avatar,
+            "gender" => Self::Gender,
+            "age" => Self::Age,
+            "signature" => Self::Signature,
+            "address" => Self::Address,
+            "region" => Self::Region,
+            "birthday" => Self::Birthday,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// 对应的数据库字段名
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Avatar => "avatar",
+            Self::Gender => "gender",
+            Self::Age => "age",
+            Self::Signature => "signature",
+            Self::Address => "address",
+            Self::Region => "region",
+            Self::Birthday => "birthday",
+        }
+    }
+}
+
+/// 已注销但还未清理的用户
+#[derive(Debug, Clone)]
+pub struct DeletedUser {
+    pub user: User,
+    /// 注销时间(毫秒)
+    pub delete_time: i64,
+}
+
+/// 搜索的分页游标, 结果按(create_time, id)倒序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCursor {
+    pub create_time: i64,
+    pub id: String,
+}
+
+/// 管理后台查询用户的过滤条件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserFilter {
+    /// 按名称, 账号, 邮箱或手机号前缀匹配
+    pub keyword: Option<String>,
+    pub role: Option<UserRole>,
+    /// 只按`banned`标记过滤, 不考虑封禁是否已到期
+    pub banned: Option<bool>,
+}
+
 #[async_trait]
 pub trait UserRepo: Sync + Send + Debug {
     async fn find_by_id(&self, id: &str) -> Result<Option<User>, Error>;
@@ -15,14 +91,246 @@ pub trait UserRepo: Sync + Send + Debug {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error>;
     async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, Error>;
     async fn find_by_name(&self, name: &str) -> Result<Option<User>, Error>;
+    /// 按名称或账号的前缀/模糊匹配搜索, 返回游标之后的`limit`个用户
+    async fn search(
+        &self,
+        keyword: &str,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error>;
+    /// 按过滤条件查询, 返回游标之后的`limit`个用户, 排序与`search`一致
+    async fn list(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error>;
     async fn find_by_account_or_email(
         &self,
         account: &str,
         email: &str,
     ) -> Result<Option<User>, Error>;
     async fn insert(&self, user: User) -> Result<(), Error>;
+    /// 更新密码, 同时更新`update_time`
+    async fn update_password(&self, id: &str, password: &str) -> Result<(), Error>;
+    /// 只更新`fields`中列出的资料字段, 同时更新`update_time`, 返回更新后的用户
+    async fn update_profile(
+        &self,
+        id: &str,
+        user: &User,
+        fields: &[ProfileField],
+    ) -> Result<User, Error>;
+
+    /// 封禁用户, `until`为解封时间(毫秒), 0表示永久封禁
+    async fn ban(&self, id: &str, reason: &str, until: i64) -> Result<(), Error>;
+    /// 解除封禁
+    async fn unban(&self, id: &str) -> Result<(), Error>;
 
+    /// 软删除(注销), 记录注销时间, 之后所有`find_by_*`都查不到该用户
     async fn delete(&self, id: &str) -> Result<(), Error>;
+    /// 按账号查找已注销且还未清理的用户
+    async fn find_deleted_by_account(&self, account: &str) -> Result<Option<DeletedUser>, Error>;
+    /// 恢复已注销且还未清理的用户
+    async fn restore(&self, id: &str) -> Result<(), Error>;
+    /// 清理在`before`(毫秒)之前注销的用户, 最多`limit`个, 返回被清理的用户id
+    async fn purge_deleted(
+        &self,
+        before: i64,
+        limit: i64,
+        mode: PurgeMode,
+    ) -> Result<Vec<String>, Error>;
+}
+
+/// 用户的两步验证(TOTP)信息
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct UserTotp {
+    pub user_id: String,
+    /// base32编码的密钥
+    pub secret: String,
+    /// 确认绑定后才生效
+    pub enabled: bool,
+    /// 未使用的恢复码哈希
+    pub recovery_codes: Vec<String>,
+    /// 最后一次使用的时间步
+    pub last_used_step: i64,
+}
+
+#[async_trait]
+pub trait TotpRepo: Sync + Send + Debug {
+    async fn find(&self, user_id: &str) -> Result<Option<UserTotp>, Error>;
+    /// 保存待确认的密钥, 覆盖之前未确认的密钥
+    async fn save_pending(&self, user_id: &str, secret: &str) -> Result<(), Error>;
+    /// 确认绑定, 同时保存恢复码哈希和本次使用的时间步
+    async fn enable(
+        &self,
+        user_id: &str,
+        recovery_codes: &[String],
+        used_step: i64,
+    ) -> Result<(), Error>;
+    async fn delete(&self, user_id: &str) -> Result<(), Error>;
+    /// 记录使用的时间步, 时间步不大于上一次使用的时间步时返回`false`(验证码重放)
+    async fn use_step(&self, user_id: &str, step: i64) -> Result<bool, Error>;
+    /// 使用一个恢复码, 恢复码不存在或已使用时返回`false`
+    async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Error>;
+}
+
+/// 用户的隐私设置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserPrivacy {
+    pub user_id: String,
+    pub searchable_by_phone: bool,
+    pub searchable_by_email: bool,
+    pub searchable_by_account: bool,
+    /// 签名和地区的可见范围
+    pub profile_visibility: PrivacyScope,
+    pub friend_request: FriendRequestPolicy,
+}
+
+impl UserPrivacy {
+    /// 没有保存过隐私设置时的默认值, 全部公开
+    pub fn new(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            searchable_by_phone: true,
+            searchable_by_email: true,
+            searchable_by_account: true,
+            profile_visibility: PrivacyScope::Everyone,
+            friend_request: FriendRequestPolicy::Everyone,
+        }
+    }
+}
+
+impl From<UserPrivacy> for PrivacySettings {
+    fn from(privacy: UserPrivacy) -> Self {
+        PrivacySettings {
+            searchable_by_phone: privacy.searchable_by_phone,
+            searchable_by_email: privacy.searchable_by_email,
+            searchable_by_account: privacy.searchable_by_account,
+            profile_visibility: privacy.profile_visibility as i32,
+            friend_request: privacy.friend_request as i32,
+        }
+    }
+}
+
+#[async_trait]
+pub trait PrivacyRepo: Sync + Send + Debug {
+    /// 查询隐私设置, 没有保存过时返回默认值
+    async fn find(&self, user_id: &str) -> Result<UserPrivacy, Error>;
+    /// 批量查询, 结果与`user_ids`一一对应
+    async fn find_by_users(&self, user_ids: &[String]) -> Result<Vec<UserPrivacy>, Error>;
+    async fn save(&self, privacy: &UserPrivacy) -> Result<(), Error>;
+    async fn delete(&self, user_id: &str) -> Result<(), Error>;
+}
+
+/// 拉黑记录, `user_id`拉黑了`blocked_user_id`
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct UserBlock {
+    pub id: i64,
+    pub user_id: String,
+    pub blocked_user_id: String,
+    pub create_time: i64,
+}
+
+#[async_trait]
+pub trait BlockRepo: Sync + Send + Debug {
+    /// 拉黑, 已经拉黑过时返回`false`
+    async fn insert(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error>;
+    /// 取消拉黑, 没有拉黑过时返回`false`
+    async fn delete(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error>;
+    /// 按拉黑时间倒序分页, `before_id`为上一页最后一条记录的id
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<UserBlock>, Error>;
+    /// 拉黑的全部用户
+    async fn find_blocked_ids(&self, user_id: &str) -> Result<Vec<String>, Error>;
+    /// 删除用户拉黑和被拉黑的全部记录
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error>;
+}
+
+/// 第三方身份与用户的绑定关系
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub user_id: String,
+    pub email: Option<String>,
+    pub create_time: i64,
+}
+
+#[async_trait]
+pub trait IdentityRepo: Sync + Send + Debug {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error>;
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<UserIdentity>, Error>;
+    async fn insert(&self, identity: &UserIdentity) -> Result<(), Error>;
+    /// 解除绑定, 不存在时返回`false`
+    async fn delete(&self, user_id: &str, provider: &str) -> Result<bool, Error>;
+    /// 删除用户的所有绑定
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error>;
+}
+
+/// 安全事件, 供用户查看账号活动
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityEvent {
+    pub id: i64,
+    /// 登录失败时用户可能不存在
+    pub user_id: Option<String>,
+    /// 尝试登录的账号/邮箱/手机号
+    pub account: String,
+    pub event_type: SecurityEventType,
+    pub ip: String,
+    pub device: String,
+    pub reason: String,
+    pub create_time: i64,
+}
+
+#[async_trait]
+pub trait SecurityEventRepo: Sync + Send + Debug {
+    async fn insert(&self, event: &SecurityEvent) -> Result<(), Error>;
+    /// 按时间倒序返回id小于`before_id`的`limit`条事件
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, Error>;
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error>;
+}
+
+/// 个人数据导出记录, 压缩包保存在`export.dir`中, 以id命名
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataExport {
+    pub id: String,
+    pub user_id: String,
+    pub status: DataExportStatus,
+    /// 压缩包大小, 字节
+    pub file_size: i64,
+    /// 导出失败的原因
+    pub error: String,
+    pub create_time: i64,
+    pub update_time: i64,
+    /// 下载有效期(毫秒), 导出完成后才有值
+    pub expire_time: i64,
+}
+
+#[async_trait]
+pub trait DataExportRepo: Sync + Send + Debug {
+    async fn insert(&self, export: &DataExport) -> Result<(), Error>;
+    async fn find(&self, id: &str) -> Result<Option<DataExport>, Error>;
+    /// 用户最近一次申请的导出
+    async fn find_latest(&self, user_id: &str) -> Result<Option<DataExport>, Error>;
+    /// 领取最多`limit`个待处理的导出并标记为处理中, 每个导出只会被一个实例领取.
+    /// 处理中但`update_time`早于`stale_before`(毫秒)的导出视为处理的实例已退出, 可以重新领取
+    async fn claim(&self, stale_before: i64, limit: i64) -> Result<Vec<DataExport>, Error>;
+    /// 导出完成, 可以下载
+    async fn mark_ready(&self, id: &str, file_size: i64, expire_time: i64) -> Result<(), Error>;
+    async fn mark_failed(&self, id: &str, error: &str) -> Result<(), Error>;
+    /// 把下载有效期在`now`(毫秒)之前的导出标记为已过期, 最多`limit`个, 返回这些导出的id
+    async fn expire(&self, now: i64, limit: i64) -> Result<Vec<String>, Error>;
+    /// 删除用户的全部导出记录, 返回被删除的导出id
+    async fn delete_by_user(&self, user_id: &str) -> Result<Vec<String>, Error>;
 }
 
 #[async_trait]
@@ -33,8 +341,83 @@ pub trait Cache: Sync + Send + Debug {
     async fn save_user_register_code(&self, account: &str, code: &str) -> Result<(), Error>;
     /// 删除用户临时验证码
     async fn delete_user_register_code(&self, account: &str) -> Result<(), Error>;
-    /// 设置用户登录状态
-    async fn set_user_login(&self, user_id: &str) -> Result<(), Error>;
+    /// 获取重置密码验证码
+    async fn get_password_reset_code(&self, email: &str) -> Result<String, Error>;
+    /// 保存重置密码验证码
+    async fn save_password_reset_code(&self, email: &str, code: &str) -> Result<(), Error>;
+    /// 删除重置密码验证码
+    async fn delete_password_reset_code(&self, email: &str) -> Result<(), Error>;
+    /// 获取短信验证码, `scene`区分注册/登录等用途
+    async fn get_sms_code(&self, scene: &str, phone: &str) -> Result<String, Error>;
+    /// 保存短信验证码
+    async fn save_sms_code(&self, scene: &str, phone: &str, code: &str) -> Result<(), Error>;
+    /// 删除短信验证码
+    async fn delete_sms_code(&self, scene: &str, phone: &str) -> Result<(), Error>;
+    /// 保存两步验证的登录挑战, 挑战token对应用户id
+    async fn save_login_challenge(
+        &self,
+        token: &str,
+        user_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error>;
+    /// 获取登录挑战对应的用户id, 不存在或已过期返回`None`
+    async fn get_login_challenge(&self, token: &str) -> Result<Option<String>, Error>;
+    /// 删除登录挑战
+    async fn delete_login_challenge(&self, token: &str) -> Result<(), Error>;
+    /// 保存第三方授权请求的state, 值为序列化后的授权上下文
+    async fn save_oidc_state(
+        &self,
+        state: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error>;
+    /// 取出并删除state, 保证只能使用一次, 不存在或已过期返回`None`
+    async fn take_oidc_state(&self, state: &str) -> Result<Option<String>, Error>;
+    /// 注销用户所有会话: 记录注销时间, 在此之前签发的token全部失效
+    async fn revoke_user_sessions(&self, user_id: &str) -> Result<(), Error>;
+    /// 获取用户会话的注销时间(毫秒), 从未注销返回`None`
+    async fn get_user_sessions_revoked_at(&self, user_id: &str) -> Result<Option<i64>, Error>;
+    /// 设备心跳, 超过`ttl_seconds`没有心跳的设备视为离线
+    async fn heartbeat(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error>;
+    /// 设备主动下线
+    async fn device_offline(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+    ) -> Result<(), Error>;
+    /// 设置用户的在线状态
+    async fn set_presence_status(&self, user_id: &str, status: PresenceStatus)
+        -> Result<(), Error>;
+    /// 批量查询在线状态, 结果与`user_ids`一一对应, 隐身用户返回真实状态
+    async fn get_users_presence(&self, user_ids: &[String]) -> Result<Vec<UserPresence>, Error>;
     /// 统计在线人数
     async fn get_user_online_count(&self) -> Result<i64, Error>;
+    /// 统计某个平台的在线人数
+    async fn get_platform_online_count(&self, platform: &str) -> Result<i64, Error>;
+    /// 移除心跳已过期的在线用户并发布离线通知, 每次最多`limit`个, 返回被移除的用户
+    async fn expire_presence(&self, limit: usize) -> Result<Vec<String>, Error>;
+
+    /// 滑动窗口限流: 窗口内请求未超过`limit`时记录本次请求并返回`None`,
+    /// 否则返回需要等待的秒数
+    async fn check_rate_limit(
+        &self,
+        key: &str,
+        limit: u64,
+        window_seconds: u64,
+    ) -> Result<Option<u64>, Error>;
+    /// 记录一次失败尝试, 返回窗口内的累计失败次数
+    async fn incr_failed_attempts(&self, key: &str, window_seconds: u64) -> Result<u64, Error>;
+    /// 清除失败计数
+    async fn clear_failed_attempts(&self, key: &str) -> Result<(), Error>;
+    /// 临时锁定
+    async fn set_lockout(&self, key: &str, seconds: u64) -> Result<(), Error>;
+    /// 获取锁定的剩余秒数, 未锁定返回`None`
+    async fn get_lockout_ttl(&self, key: &str) -> Result<Option<u64>, Error>;
 }
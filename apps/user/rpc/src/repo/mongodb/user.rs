@@ -0,0 +1,553 @@
+use crate::config::{Config, PurgeMode};
+use crate::error::Error;
+use crate::pb::user::User;
+use crate::repo::{DeletedUser, ProfileField, SearchCursor, UserFilter, UserRepo};
+use crate::utils::{parse_role, role_name};
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, Document, Regex};
+use mongodb::options::{IndexOptions, ReturnDocument};
+use mongodb::{Collection, Cursor, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+
+const COLLECTION_NAME: &str = "users";
+
+/// users集合中的文档, 字段与users表一致
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct UserDocument {
+    #[serde(rename = "_id")]
+    id: String,
+    name: String,
+    account: String,
+    password: String,
+    avatar: String,
+    gender: String,
+    age: i32,
+    phone: Option<String>,
+    email: Option<String>,
+    address: Option<String>,
+    region: Option<String>,
+    birthday: Option<i64>,
+    signature: String,
+    role: String,
+    banned: bool,
+    ban_reason: String,
+    ban_until: i64,
+    create_time: i64,
+    update_time: i64,
+    is_delete: bool,
+    delete_time: Option<i64>,
+    purge_time: Option<i64>,
+}
+
+impl From<UserDocument> for User {
+    fn from(document: UserDocument) -> Self {
+        User {
+            id: document.id,
+            name: document.name,
+            account: document.account,
+            password: document.password,
+            avatar: document.avatar,
+            gender: document.gender,
+            age: document.age,
+            phone: document.phone,
+            email: document.email,
+            address: document.address,
+            region: document.region,
+            birthday: document.birthday,
+            create_time: document.create_time,
+            update_time: document.update_time,
+            signature: document.signature,
+            role: parse_role(&document.role) as i32,
+            banned: document.banned,
+            ban_reason: document.ban_reason,
+            ban_until: document.ban_until,
+        }
+    }
+}
+
+/// MongoDB实现的UserRepo, 行为与UserPostgres一致
+///
+/// 搜索只做前缀匹配, 不支持trigram模糊匹配
+#[derive(Debug)]
+pub struct UserMongoDb {
+    collection: Collection<UserDocument>,
+}
+
+impl UserMongoDb {
+    /// 创建时同步建立索引, 索引已存在时不做任何操作
+    pub async fn new(db: Database) -> Result<Self, Error> {
+        let collection = db.collection(COLLECTION_NAME);
+        collection.create_indexes(indexes()).await?;
+        Ok(Self { collection })
+    }
+
+    pub async fn from_config(config: &Config) -> Self {
+        let client = mongodb::Client::with_uri_str(config.mongodb.url())
+            .await
+            .expect("connect to mongodb success");
+        Self::new(client.database(&config.mongodb.database))
+            .await
+            .expect("create mongodb indexes success")
+    }
+
+    async fn find_one(&self, filter: Document) -> Result<Option<User>, Error> {
+        let document = self.collection.find_one(filter).await?;
+        Ok(document.map(User::from))
+    }
+
+    /// 按(create_time, id)倒序返回游标之后的`limit`个用户
+    async fn page(
+        &self,
+        mut filter: Document,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        if let Some(cursor) = cursor {
+            let after_cursor = doc! {
+                "$or": [
+                    { "create_time": { "$lt": cursor.create_time } },
+                    { "create_time": cursor.create_time, "_id": { "$lt": cursor.id } },
+                ]
+            };
+            filter = doc! { "$and": [filter, after_cursor] };
+        }
+        let cursor = self
+            .collection
+            .find(filter)
+            .sort(doc! { "create_time": -1, "_id": -1 })
+            .limit(limit)
+            .await?;
+        collect(cursor).await
+    }
+
+    /// 按id更新, 同时更新`update_time`, 没有匹配的用户时返回RowNotFound
+    async fn update_one(&self, filter: Document, mut set: Document) -> Result<(), Error> {
+        set.insert("update_time", chrono::Utc::now().timestamp_millis());
+        let result = self
+            .collection
+            .update_one(filter, doc! { "$set": set })
+            .await?;
+        if result.matched_count == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+}
+
+/// 与users表的索引对应, 唯一索引只约束未注销的用户
+fn indexes() -> Vec<IndexModel> {
+    let unique = |field: &str, name: &str| {
+        let options = IndexOptions::builder()
+            .name(name.to_string())
+            .unique(true)
+            .partial_filter_expression(doc! { "is_delete": false, field: { "$type": "string" } })
+            .build();
+        IndexModel::builder()
+            .keys(doc! { field: 1 })
+            .options(options)
+            .build()
+    };
+    let create_time = IndexModel::builder()
+        .keys(doc! { "create_time": -1, "_id": -1 })
+        .options(
+            IndexOptions::builder()
+                .name("idx_users_create_time_id".to_string())
+                .partial_filter_expression(doc! { "is_delete": false })
+                .build(),
+        )
+        .build();
+    let deleted = IndexModel::builder()
+        .keys(doc! { "delete_time": 1 })
+        .options(
+            IndexOptions::builder()
+                .name("idx_users_deleted".to_string())
+                .partial_filter_expression(doc! { "is_delete": true })
+                .build(),
+        )
+        .build();
+
+    vec![
+        unique("account", "idx_users_account"),
+        unique("email", "idx_users_email"),
+        unique("phone", "idx_users_phone"),
+        create_time,
+        deleted,
+    ]
+}
+
+async fn collect(mut cursor: Cursor<UserDocument>) -> Result<Vec<User>, Error> {
+    let mut users = vec![];
+    while cursor.advance().await? {
+        users.push(User::from(cursor.deserialize_current()?));
+    }
+    Ok(users)
+}
+
+/// 转义正则表达式的元字符
+fn escape_regex(keyword: &str) -> String {
+    let mut escaped = String::with_capacity(keyword.len());
+    for c in keyword.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 前缀匹配, `ignore_case`为`true`时忽略大小写
+fn prefix(keyword: &str, ignore_case: bool) -> Regex {
+    Regex {
+        pattern: format!("^{}", escape_regex(keyword)),
+        options: if ignore_case { "i" } else { "" }.to_string(),
+    }
+}
+
+fn to_bson<T: Into<Bson>>(value: Option<T>) -> Bson {
+    value.map(Into::into).unwrap_or(Bson::Null)
+}
+
+#[async_trait]
+impl UserRepo for UserMongoDb {
+    async fn find_by_id(&self, id: &str) -> Result<Option<User>, Error> {
+        self.find_one(doc! { "_id": id, "is_delete": false }).await
+    }
+
+    async fn find_by_ids(&self, ids: Vec<String>) -> Result<Vec<User>, Error> {
+        let cursor = self
+            .collection
+            .find(doc! { "_id": { "$in": ids }, "is_delete": false })
+            .await?;
+        collect(cursor).await
+    }
+
+    async fn find_by_account(&self, account: &str) -> Result<Option<User>, Error> {
+        self.find_one(doc! { "account": account, "is_delete": false })
+            .await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        self.find_one(doc! { "email": email, "is_delete": false })
+            .await
+    }
+
+    async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        self.find_one(doc! { "phone": phone, "is_delete": false })
+            .await
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<User>, Error> {
+        self.find_one(doc! { "name": name, "is_delete": false })
+            .await
+    }
+
+    async fn search(
+        &self,
+        keyword: &str,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        let prefix = prefix(keyword, true);
+        let filter = doc! {
+            "is_delete": false,
+            "$or": [{ "name": prefix.clone() }, { "account": prefix }],
+        };
+        self.page(filter, cursor, limit).await
+    }
+
+    async fn list(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        let mut query = doc! { "is_delete": false };
+        if let Some(keyword) = &filter.keyword {
+            let ignore_case = prefix(keyword, true);
+            query.insert(
+                "$or",
+                vec![
+                    doc! { "name": ignore_case.clone() },
+                    doc! { "account": ignore_case.clone() },
+                    doc! { "email": ignore_case },
+                    doc! { "phone": prefix(keyword, false) },
+                ],
+            );
+        }
+        if let Some(role) = filter.role {
+            query.insert("role", role_name(role));
+        }
+        if let Some(banned) = filter.banned {
+            query.insert("banned", banned);
+        }
+        self.page(query, cursor, limit).await
+    }
+
+    async fn find_by_account_or_email(
+        &self,
+        account: &str,
+        email: &str,
+    ) -> Result<Option<User>, Error> {
+        self.find_one(doc! {
+            "$or": [{ "account": account }, { "email": email }],
+            "is_delete": false,
+        })
+        .await
+    }
+
+    async fn insert(&self, user: User) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let document = UserDocument {
+            role: role_name(user.role()).to_string(),
+            id: user.id,
+            name: user.name,
+            account: user.account,
+            password: user.password,
+            avatar: user.avatar,
+            gender: user.gender,
+            age: user.age,
+            phone: user.phone,
+            email: user.email,
+            address: user.address,
+            region: user.region,
+            birthday: user.birthday,
+            signature: user.signature,
+            create_time: now,
+            update_time: now,
+            ..Default::default()
+        };
+        self.collection.insert_one(document).await?;
+        Ok(())
+    }
+
+    async fn update_password(&self, id: &str, password: &str) -> Result<(), Error> {
+        self.update_one(doc! { "_id": id }, doc! { "password": password })
+            .await
+    }
+
+    async fn update_profile(
+        &self,
+        id: &str,
+        user: &User,
+        fields: &[ProfileField],
+    ) -> Result<User, Error> {
+        let mut set = doc! { "update_time": chrono::Utc::now().timestamp_millis() };
+        for field in fields {
+            let value = match field {
+                ProfileField::Name => Bson::from(user.name.as_str()),
+                ProfileField::Avatar => Bson::from(user.avatar.as_str()),
+                ProfileField::Gender => Bson::from(user.gender.as_str()),
+                ProfileField::Age => Bson::from(user.age),
+                ProfileField::Signature => Bson::from(user.signature.as_str()),
+                ProfileField::Address => to_bson(user.address.as_deref()),
+                ProfileField::Region => to_bson(user.region.as_deref()),
+                ProfileField::Birthday => to_bson(user.birthday),
+            };
+            set.insert(field.column(), value);
+        }
+
+        let document = self
+            .collection
+            .find_one_and_update(doc! { "_id": id }, doc! { "$set": set })
+            .return_document(ReturnDocument::After)
+            .await?
+            .ok_or_else(|| Error::from(sqlx::Error::RowNotFound))?;
+        Ok(User::from(document))
+    }
+
+    async fn ban(&self, id: &str, reason: &str, until: i64) -> Result<(), Error> {
+        self.update_one(
+            doc! { "_id": id, "is_delete": false },
+            doc! { "banned": true, "ban_reason": reason, "ban_until": until },
+        )
+        .await
+    }
+
+    async fn unban(&self, id: &str) -> Result<(), Error> {
+        self.update_one(
+            doc! { "_id": id, "is_delete": false },
+            doc! { "banned": false, "ban_reason": "", "ban_until": 0_i64 },
+        )
+        .await
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.collection
+            .update_one(
+                doc! { "_id": id, "is_delete": false },
+                doc! { "$set": { "is_delete": true, "delete_time": now, "update_time": now } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn find_deleted_by_account(&self, account: &str) -> Result<Option<DeletedUser>, Error> {
+        let document = self
+            .collection
+            .find_one(doc! { "account": account, "is_delete": true, "purge_time": Bson::Null })
+            .sort(doc! { "delete_time": -1 })
+            .await?;
+
+        let Some(document) = document else {
+            return Ok(None);
+        };
+        let delete_time = document.delete_time.unwrap_or_default();
+        Ok(Some(DeletedUser {
+            user: User::from(document),
+            delete_time,
+        }))
+    }
+
+    async fn restore(&self, id: &str) -> Result<(), Error> {
+        self.update_one(
+            doc! { "_id": id, "is_delete": true, "purge_time": Bson::Null },
+            doc! { "is_delete": false, "delete_time": Bson::Null },
+        )
+        .await
+    }
+
+    async fn purge_deleted(
+        &self,
+        before: i64,
+        limit: i64,
+        mode: PurgeMode,
+    ) -> Result<Vec<String>, Error> {
+        let filter = doc! {
+            "is_delete": true,
+            "purge_time": Bson::Null,
+            "delete_time": { "$lt": before },
+        };
+        // MongoDB的批量更新不支持limit, 先查出本批的id
+        let mut cursor = self
+            .collection
+            .clone_with_type::<Document>()
+            .find(filter.clone())
+            .projection(doc! { "_id": 1 })
+            .limit(limit)
+            .await?;
+        let mut ids = vec![];
+        while cursor.advance().await? {
+            let document = cursor.deserialize_current()?;
+            if let Ok(id) = document.get_str("_id") {
+                ids.push(id.to_string());
+            }
+        }
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        let mut filter = filter;
+        filter.insert("_id", doc! { "$in": ids.clone() });
+        match mode {
+            // 清空个人信息, 账号改为不可登录的占位值, 释放原账号/邮箱/手机号
+            PurgeMode::Anonymize => {
+                let now = chrono::Utc::now().timestamp_millis();
+                let pipeline = vec![doc! { "$set": {
+                    "name": "",
+                    "account": { "$concat": ["deleted:", "$_id"] },
+                    "password": "",
+                    "avatar": "",
+                    "gender": "",
+                    "age": 0,
+                    "phone": Bson::Null,
+                    "email": Bson::Null,
+                    "address": Bson::Null,
+                    "region": Bson::Null,
+                    "birthday": Bson::Null,
+                    "signature": "",
+                    "purge_time": now,
+                    "update_time": now,
+                } }];
+                self.collection.update_many(filter, pipeline).await?;
+            }
+            PurgeMode::Delete => {
+                self.collection.delete_many(filter).await?;
+            }
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::user::UserRole;
+    use common::LoadableConfig;
+    use nanoid::nanoid;
+
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("lucas"), "lucas");
+        assert_eq!(escape_regex("a.b*c"), "a\\.b\\*c");
+        assert_eq!(prefix("+86", false).pattern, "^\\+86");
+    }
+
+    #[test]
+    fn test_document_to_user() {
+        let document = UserDocument {
+            id: "id".to_string(),
+            account: "lucas".to_string(),
+            role: "admin".to_string(),
+            phone: Some("+8613800138000".to_string()),
+            ..Default::default()
+        };
+        let bson = mongodb::bson::to_document(&document).unwrap();
+        let decoded: UserDocument = mongodb::bson::from_document(bson).unwrap();
+        assert_eq!(decoded, document);
+
+        let user = User::from(decoded);
+        assert_eq!(user.role(), UserRole::Admin);
+        assert_eq!(user.phone.as_deref(), Some("+8613800138000"));
+        assert_eq!(user.email, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires mongodb"]
+    async fn test_insert_find_and_delete() {
+        let config = Config::load("etc/user.yml");
+        let user_repo = UserMongoDb::from_config(&config).await;
+        let id = nanoid!();
+        let account = nanoid!();
+        let user = User {
+            id: id.clone(),
+            name: "test-name".to_string(),
+            account: account.clone(),
+            password: "123".to_string(),
+            role: UserRole::Support as i32,
+            ..Default::default()
+        };
+        user_repo.insert(user.clone()).await.unwrap();
+
+        // 未注销的账号不能重复
+        let duplicated = User {
+            id: nanoid!(),
+            ..user.clone()
+        };
+        assert!(user_repo.insert(duplicated).await.is_err());
+
+        let found = user_repo.find_by_account(&account).await.unwrap().unwrap();
+        assert_eq!(found.id, id);
+        assert_eq!(found.role(), UserRole::Support);
+
+        let users = user_repo.search(&account, None, 10).await.unwrap();
+        assert_eq!(users.len(), 1);
+
+        user_repo.ban(&id, "spam", 0).await.unwrap();
+        let found = user_repo.find_by_id(&id).await.unwrap().unwrap();
+        assert!(found.banned);
+
+        user_repo.delete(&id).await.unwrap();
+        assert!(user_repo.find_by_id(&id).await.unwrap().is_none());
+        let deleted = user_repo
+            .find_deleted_by_account(&account)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deleted.user.id, id);
+
+        user_repo.restore(&id).await.unwrap();
+        assert!(user_repo.find_by_id(&id).await.unwrap().is_some());
+        user_repo.delete(&id).await.unwrap();
+    }
+}
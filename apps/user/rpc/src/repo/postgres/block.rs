@@ -0,0 +1,110 @@
+use crate::error::Error;
+use crate::repo::{BlockRepo, UserBlock};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Debug)]
+pub struct BlockPostgres {
+    pool: PgPool,
+}
+
+impl BlockPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BlockRepo for BlockPostgres {
+    async fn insert(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "INSERT INTO user_blocks (user_id, blocked_user_id, create_time)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, blocked_user_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(blocked_user_id)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let result =
+            sqlx::query("DELETE FROM user_blocks WHERE user_id = $1 AND blocked_user_id = $2")
+                .bind(user_id)
+                .bind(blocked_user_id)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<UserBlock>, Error> {
+        let blocks = sqlx::query_as(
+            "SELECT * FROM user_blocks
+            WHERE user_id = $1 AND ($2::bigint IS NULL OR id < $2)
+            ORDER BY id DESC LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(before_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(blocks)
+    }
+
+    async fn find_blocked_ids(&self, user_id: &str) -> Result<Vec<String>, Error> {
+        let ids = sqlx::query_scalar("SELECT blocked_user_id FROM user_blocks WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM user_blocks WHERE user_id = $1 OR blocked_user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use common::LoadableConfig;
+
+    #[tokio::test]
+    #[ignore = "requires postgres"]
+    async fn test_block_postgres() {
+        let config = Config::load("etc/user.yml");
+        let pool = PgPool::connect(&config.postgres.url()).await.unwrap();
+        let repo = BlockPostgres::new(pool);
+        let user_id = nanoid::nanoid!();
+
+        assert!(repo.insert(&user_id, "a").await.unwrap());
+        assert!(!repo.insert(&user_id, "a").await.unwrap());
+        assert!(repo.insert(&user_id, "b").await.unwrap());
+
+        let blocks = repo.list(&user_id, None, 1).await.unwrap();
+        assert_eq!(blocks[0].blocked_user_id, "b");
+        let blocks = repo.list(&user_id, Some(blocks[0].id), 10).await.unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].blocked_user_id, "a");
+
+        assert!(repo.delete(&user_id, "a").await.unwrap());
+        assert!(!repo.delete(&user_id, "a").await.unwrap());
+        assert_eq!(repo.find_blocked_ids(&user_id).await.unwrap(), vec!["b"]);
+
+        repo.delete_by_user(&user_id).await.unwrap();
+        assert!(repo.find_blocked_ids(&user_id).await.unwrap().is_empty());
+    }
+}
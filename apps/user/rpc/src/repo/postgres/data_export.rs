@@ -0,0 +1,207 @@
+use crate::error::Error;
+use crate::pb::user::DataExportStatus;
+use crate::repo::{DataExport, DataExportRepo};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Row};
+
+#[derive(Debug)]
+pub struct DataExportPostgres {
+    pool: PgPool,
+}
+
+impl DataExportPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DataExportRepo for DataExportPostgres {
+    async fn insert(&self, export: &DataExport) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO data_exports
+                (id, user_id, status, file_size, error, create_time, update_time, expire_time)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&export.id)
+        .bind(&export.user_id)
+        .bind(export.status.as_str_name())
+        .bind(export.file_size)
+        .bind(&export.error)
+        .bind(export.create_time)
+        .bind(export.update_time)
+        .bind(export.expire_time)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find(&self, id: &str) -> Result<Option<DataExport>, Error> {
+        let export = sqlx::query_as("SELECT * FROM data_exports WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(export)
+    }
+
+    async fn find_latest(&self, user_id: &str) -> Result<Option<DataExport>, Error> {
+        let export = sqlx::query_as(
+            "SELECT * FROM data_exports WHERE user_id = $1 ORDER BY create_time DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(export)
+    }
+
+    async fn claim(&self, stale_before: i64, limit: i64) -> Result<Vec<DataExport>, Error> {
+        // SKIP LOCKED: 多个实例同时领取时跳过已被其他实例锁定的记录
+        let exports = sqlx::query_as(
+            "UPDATE data_exports SET status = $1, update_time = $2
+            WHERE id IN (
+                SELECT id FROM data_exports
+                WHERE status = $3 OR (status = $1 AND update_time < $4)
+                ORDER BY create_time LIMIT $5
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *",
+        )
+        .bind(DataExportStatus::Running.as_str_name())
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(DataExportStatus::Pending.as_str_name())
+        .bind(stale_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(exports)
+    }
+
+    async fn mark_ready(&self, id: &str, file_size: i64, expire_time: i64) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE data_exports
+            SET status = $2, file_size = $3, expire_time = $4, update_time = $5
+            WHERE id = $1",
+        )
+        .bind(id)
+        .bind(DataExportStatus::Ready.as_str_name())
+        .bind(file_size)
+        .bind(expire_time)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &str, error: &str) -> Result<(), Error> {
+        sqlx::query(
+            "UPDATE data_exports SET status = $2, error = $3, update_time = $4 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(DataExportStatus::Failed.as_str_name())
+        .bind(error)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn expire(&self, now: i64, limit: i64) -> Result<Vec<String>, Error> {
+        let ids = sqlx::query_scalar(
+            "UPDATE data_exports SET status = $1, update_time = $2
+            WHERE id IN (
+                SELECT id FROM data_exports
+                WHERE status = $3 AND expire_time <= $2
+                ORDER BY expire_time LIMIT $4
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id",
+        )
+        .bind(DataExportStatus::Expired.as_str_name())
+        .bind(now)
+        .bind(DataExportStatus::Ready.as_str_name())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<Vec<String>, Error> {
+        let ids = sqlx::query_scalar("DELETE FROM data_exports WHERE user_id = $1 RETURNING id")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+}
+
+impl FromRow<'_, PgRow> for DataExport {
+    fn from_row(row: &'_ PgRow) -> Result<Self, sqlx::Error> {
+        let status: String = row.try_get("status")?;
+        Ok(DataExport {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            status: DataExportStatus::from_str_name(&status).ok_or_else(|| {
+                sqlx::Error::Decode(format!("unknown data export status `{}`", status).into())
+            })?,
+            file_size: row.try_get("file_size")?,
+            error: row.try_get("error")?,
+            create_time: row.try_get("create_time")?,
+            update_time: row.try_get("update_time")?,
+            expire_time: row.try_get("expire_time")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use common::LoadableConfig;
+
+    #[tokio::test]
+    #[ignore = "requires postgres"]
+    async fn test_data_export_postgres() {
+        let config = Config::load("etc/user.yml");
+        let pool = PgPool::connect(&config.postgres.url()).await.unwrap();
+        let repo = DataExportPostgres::new(pool);
+        let user_id = nanoid::nanoid!();
+        let now = chrono::Utc::now().timestamp_millis();
+        let export = DataExport {
+            id: nanoid::nanoid!(),
+            user_id: user_id.clone(),
+            status: DataExportStatus::Pending,
+            file_size: 0,
+            error: String::new(),
+            create_time: now,
+            update_time: now,
+            expire_time: 0,
+        };
+        repo.insert(&export).await.unwrap();
+        assert_eq!(
+            repo.find_latest(&user_id).await.unwrap(),
+            Some(export.clone())
+        );
+
+        let claimed = repo.claim(now - 60_000, 1000).await.unwrap();
+        let claimed = claimed.iter().find(|e| e.id == export.id).unwrap();
+        assert_eq!(claimed.status, DataExportStatus::Running);
+        // 处理中且没有超时的导出不会被重复领取
+        let claimed = repo.claim(now - 60_000, 1000).await.unwrap();
+        assert!(claimed.iter().all(|e| e.id != export.id));
+
+        repo.mark_ready(&export.id, 100, now).await.unwrap();
+        let found = repo.find(&export.id).await.unwrap().unwrap();
+        assert_eq!(found.status, DataExportStatus::Ready);
+        assert_eq!(found.file_size, 100);
+
+        assert!(repo.expire(now, 1000).await.unwrap().contains(&export.id));
+        let found = repo.find(&export.id).await.unwrap().unwrap();
+        assert_eq!(found.status, DataExportStatus::Expired);
+
+        assert_eq!(
+            repo.delete_by_user(&user_id).await.unwrap(),
+            vec![export.id]
+        );
+    }
+}
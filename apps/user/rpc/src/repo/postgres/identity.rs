@@ -0,0 +1,74 @@
+use crate::error::Error;
+use crate::repo::{IdentityRepo, UserIdentity};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Debug)]
+pub struct IdentityPostgres {
+    pool: PgPool,
+}
+
+impl IdentityPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdentityRepo for IdentityPostgres {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error> {
+        let identity = sqlx::query_as(
+            "SELECT provider, subject, user_id, email, create_time
+            FROM user_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(identity)
+    }
+
+    async fn find_by_user(&self, user_id: &str) -> Result<Vec<UserIdentity>, Error> {
+        let identities = sqlx::query_as(
+            "SELECT provider, subject, user_id, email, create_time
+            FROM user_identities WHERE user_id = $1 ORDER BY create_time",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(identities)
+    }
+
+    async fn insert(&self, identity: &UserIdentity) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO user_identities (provider, subject, user_id, email, create_time)
+            VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(&identity.user_id)
+        .bind(&identity.email)
+        .bind(identity.create_time)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str, provider: &str) -> Result<bool, Error> {
+        let result =
+            sqlx::query("DELETE FROM user_identities WHERE user_id = $1 AND provider = $2")
+                .bind(user_id)
+                .bind(provider)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM user_identities WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -1 +1,7 @@
+pub(crate) mod block;
+pub(crate) mod data_export;
+pub(crate) mod identity;
+pub(crate) mod privacy;
+pub(crate) mod security_event;
+pub(crate) mod totp;
 pub(crate) mod user;
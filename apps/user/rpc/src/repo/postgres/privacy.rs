@@ -0,0 +1,124 @@
+use crate::error::Error;
+use crate::pb::user::{FriendRequestPolicy, PrivacyScope};
+use crate::repo::{PrivacyRepo, UserPrivacy};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Row};
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct PrivacyPostgres {
+    pool: PgPool,
+}
+
+impl PrivacyPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn scope_name(scope: PrivacyScope) -> &'static str {
+    match scope {
+        PrivacyScope::Everyone => "everyone",
+        PrivacyScope::Friends => "friends",
+        PrivacyScope::Nobody => "nobody",
+    }
+}
+
+fn parse_scope(name: &str) -> PrivacyScope {
+    match name {
+        "friends" => PrivacyScope::Friends,
+        "nobody" => PrivacyScope::Nobody,
+        _ => PrivacyScope::Everyone,
+    }
+}
+
+fn policy_name(policy: FriendRequestPolicy) -> &'static str {
+    match policy {
+        FriendRequestPolicy::Everyone => "everyone",
+        FriendRequestPolicy::Nobody => "nobody",
+    }
+}
+
+fn parse_policy(name: &str) -> FriendRequestPolicy {
+    match name {
+        "nobody" => FriendRequestPolicy::Nobody,
+        _ => FriendRequestPolicy::Everyone,
+    }
+}
+
+impl FromRow<'_, PgRow> for UserPrivacy {
+    fn from_row(row: &'_ PgRow) -> Result<Self, sqlx::Error> {
+        Ok(UserPrivacy {
+            user_id: row.try_get("user_id")?,
+            searchable_by_phone: row.try_get("searchable_by_phone")?,
+            searchable_by_email: row.try_get("searchable_by_email")?,
+            searchable_by_account: row.try_get("searchable_by_account")?,
+            profile_visibility: parse_scope(row.try_get("profile_visibility")?),
+            friend_request: parse_policy(row.try_get("friend_request")?),
+        })
+    }
+}
+
+#[async_trait]
+impl PrivacyRepo for PrivacyPostgres {
+    async fn find(&self, user_id: &str) -> Result<UserPrivacy, Error> {
+        let privacy = sqlx::query_as("SELECT * FROM user_privacy WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(privacy.unwrap_or_else(|| UserPrivacy::new(user_id)))
+    }
+
+    async fn find_by_users(&self, user_ids: &[String]) -> Result<Vec<UserPrivacy>, Error> {
+        let rows: Vec<UserPrivacy> =
+            sqlx::query_as("SELECT * FROM user_privacy WHERE user_id = ANY($1)")
+                .bind(user_ids)
+                .fetch_all(&self.pool)
+                .await?;
+        let mut saved: HashMap<String, UserPrivacy> = rows
+            .into_iter()
+            .map(|privacy| (privacy.user_id.clone(), privacy))
+            .collect();
+        let privacies = user_ids
+            .iter()
+            .map(|id| saved.remove(id).unwrap_or_else(|| UserPrivacy::new(id)))
+            .collect();
+        Ok(privacies)
+    }
+
+    async fn save(&self, privacy: &UserPrivacy) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        sqlx::query(
+            "INSERT INTO user_privacy
+            (user_id, searchable_by_phone, searchable_by_email, searchable_by_account,
+             profile_visibility, friend_request, create_time, update_time)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+            ON CONFLICT (user_id) DO UPDATE
+            SET searchable_by_phone = EXCLUDED.searchable_by_phone,
+                searchable_by_email = EXCLUDED.searchable_by_email,
+                searchable_by_account = EXCLUDED.searchable_by_account,
+                profile_visibility = EXCLUDED.profile_visibility,
+                friend_request = EXCLUDED.friend_request,
+                update_time = EXCLUDED.update_time",
+        )
+        .bind(&privacy.user_id)
+        .bind(privacy.searchable_by_phone)
+        .bind(privacy.searchable_by_email)
+        .bind(privacy.searchable_by_account)
+        .bind(scope_name(privacy.profile_visibility))
+        .bind(policy_name(privacy.friend_request))
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM user_privacy WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::error::Error;
+use crate::pb::user::SecurityEventType;
+use crate::repo::{SecurityEvent, SecurityEventRepo};
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool, Row};
+
+#[derive(Debug)]
+pub struct SecurityEventPostgres {
+    pool: PgPool,
+}
+
+impl SecurityEventPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SecurityEventRepo for SecurityEventPostgres {
+    async fn insert(&self, event: &SecurityEvent) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO security_events
+                (user_id, account, event_type, ip, device, reason, create_time)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&event.user_id)
+        .bind(&event.account)
+        .bind(event.event_type.as_str_name())
+        .bind(&event.ip)
+        .bind(&event.device)
+        .bind(&event.reason)
+        .bind(event.create_time)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<SecurityEvent>, Error> {
+        let events = sqlx::query_as(
+            "SELECT * FROM security_events
+            WHERE user_id = $1 AND ($2::bigint IS NULL OR id < $2)
+            ORDER BY id DESC LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(before_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM security_events WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+impl FromRow<'_, PgRow> for SecurityEvent {
+    fn from_row(row: &'_ PgRow) -> Result<Self, sqlx::Error> {
+        let event_type: String = row.try_get("event_type")?;
+        Ok(SecurityEvent {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            account: row.try_get("account")?,
+            // 未知的类型来自更新版本的服务, 不影响查询
+            event_type: SecurityEventType::from_str_name(&event_type)
+                .unwrap_or(SecurityEventType::Unspecified),
+            ip: row.try_get("ip")?,
+            device: row.try_get("device")?,
+            reason: row.try_get("reason")?,
+            create_time: row.try_get("create_time")?,
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use crate::error::Error;
+use crate::repo::{TotpRepo, UserTotp};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[derive(Debug)]
+pub struct TotpPostgres {
+    pool: PgPool,
+}
+
+impl TotpPostgres {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TotpRepo for TotpPostgres {
+    async fn find(&self, user_id: &str) -> Result<Option<UserTotp>, Error> {
+        let totp = sqlx::query_as(
+            "SELECT user_id, secret, enabled, recovery_codes, last_used_step
+            FROM user_totp WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(totp)
+    }
+
+    async fn save_pending(&self, user_id: &str, secret: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        // 已启用的记录不会被覆盖
+        sqlx::query(
+            "INSERT INTO user_totp (user_id, secret, create_time, update_time)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, recovery_codes = '{}', last_used_step = 0,
+                update_time = EXCLUDED.update_time
+            WHERE user_totp.enabled = FALSE",
+        )
+        .bind(user_id)
+        .bind(secret)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn enable(
+        &self,
+        user_id: &str,
+        recovery_codes: &[String],
+        used_step: i64,
+    ) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "UPDATE user_totp
+            SET enabled = TRUE, recovery_codes = $2, last_used_step = $3, update_time = $4
+            WHERE user_id = $1 AND enabled = FALSE",
+        )
+        .bind(user_id)
+        .bind(recovery_codes)
+        .bind(used_step)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn use_step(&self, user_id: &str, step: i64) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE user_totp SET last_used_step = $2
+            WHERE user_id = $1 AND last_used_step < $2",
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn use_recovery_code(&self, user_id: &str, code_hash: &str) -> Result<bool, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "UPDATE user_totp
+            SET recovery_codes = array_remove(recovery_codes, $2), update_time = $3
+            WHERE user_id = $1 AND enabled = TRUE AND $2 = ANY(recovery_codes)",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
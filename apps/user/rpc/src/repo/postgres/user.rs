@@ -1,11 +1,12 @@
-use crate::config::Config;
+use crate::config::{Config, PurgeMode};
 use crate::error::Error;
 
 use crate::pb::user::User;
-use crate::repo::UserRepo;
+use crate::repo::{DeletedUser, ProfileField, SearchCursor, UserFilter, UserRepo};
+use crate::utils::{parse_role, role_name};
 use async_trait::async_trait;
 use sqlx::postgres::PgRow;
-use sqlx::{FromRow, PgPool, Row};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Row};
 use std::fmt::Debug;
 
 #[derive(Debug)]
@@ -29,7 +30,7 @@ impl UserPostgres {
 #[async_trait]
 impl UserRepo for UserPostgres {
     async fn find_by_id(&self, id: &str) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where id = $1")
+        let user = sqlx::query_as("select * from users where id = $1 and is_delete = false")
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
@@ -37,14 +38,16 @@ impl UserRepo for UserPostgres {
     }
 
     async fn find_by_ids(&self, ids: Vec<String>) -> Result<Vec<User>, Error> {
-        let sql = format!(r"select * from users where id in ('{}')", ids.join(r"','"));
-        let rows = sqlx::query_as(&sql).fetch_all(&self.pool).await?;
+        let rows = sqlx::query_as("select * from users where id = ANY($1) and is_delete = false")
+            .bind(&ids)
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(rows)
     }
 
     async fn find_by_account(&self, account: &str) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where account = $1")
+        let user = sqlx::query_as("select * from users where account = $1 and is_delete = false")
             .bind(account)
             .fetch_optional(&self.pool)
             .await?;
@@ -52,7 +55,7 @@ impl UserRepo for UserPostgres {
     }
 
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where email = $1")
+        let user = sqlx::query_as("select * from users where email = $1 and is_delete = false")
             .bind(email)
             .fetch_optional(&self.pool)
             .await?;
@@ -60,7 +63,7 @@ impl UserRepo for UserPostgres {
     }
 
     async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where phone = $1")
+        let user = sqlx::query_as("select * from users where phone = $1 and is_delete = false")
             .bind(phone)
             .fetch_optional(&self.pool)
             .await?;
@@ -68,23 +71,102 @@ impl UserRepo for UserPostgres {
     }
 
     async fn find_by_name(&self, name: &str) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where name = $1")
+        let user = sqlx::query_as("select * from users where name = $1 and is_delete = false")
             .bind(name)
             .fetch_optional(&self.pool)
             .await?;
         Ok(user)
     }
 
+    async fn search(
+        &self,
+        keyword: &str,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        // 只做前缀匹配
+        let prefix = format!("{}%", escape_like(keyword));
+        let (create_time, id) = match cursor {
+            Some(cursor) => (Some(cursor.create_time), Some(cursor.id)),
+            None => (None, None),
+        };
+
+        let users = sqlx::query_as(
+            "select * from users
+            where is_delete = false
+              and (name ilike $1 or account ilike $1 or name % $2 or account % $2)
+              and ($3::bigint is null or (create_time, id) < ($3, $4))
+            order by create_time desc, id desc
+            limit $5",
+        )
+        .bind(prefix)
+        .bind(keyword)
+        .bind(create_time)
+        .bind(id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(users)
+    }
+
+    async fn list(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("select * from users where is_delete = false");
+        if let Some(keyword) = &filter.keyword {
+            let prefix = format!("{}%", escape_like(keyword));
+            builder
+                .push(" and (name ilike ")
+                .push_bind(prefix.clone())
+                .push(" or account ilike ")
+                .push_bind(prefix.clone())
+                .push(" or email ilike ")
+                .push_bind(prefix.clone())
+                .push(" or phone like ")
+                .push_bind(prefix)
+                .push(")");
+        }
+        if let Some(role) = filter.role {
+            builder.push(" and role = ").push_bind(role_name(role));
+        }
+        if let Some(banned) = filter.banned {
+            builder.push(" and banned = ").push_bind(banned);
+        }
+        if let Some(cursor) = cursor {
+            builder
+                .push(" and (create_time, id) < (")
+                .push_bind(cursor.create_time)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        builder
+            .push(" order by create_time desc, id desc limit ")
+            .push_bind(limit);
+
+        let users = builder
+            .build_query_as::<User>()
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(users)
+    }
+
     async fn find_by_account_or_email(
         &self,
         account: &str,
         email: &str,
     ) -> Result<Option<User>, Error> {
-        let user = sqlx::query_as("select * from users where account = $1 or email = $2")
-            .bind(account)
-            .bind(email)
-            .fetch_optional(&self.pool)
-            .await?;
+        let user = sqlx::query_as(
+            "select * from users where (account = $1 or email = $2) and is_delete = false",
+        )
+        .bind(account)
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
         Ok(user)
     }
 
@@ -93,7 +175,7 @@ impl UserRepo for UserPostgres {
         let mut tx = self.pool.begin().await?;
         let result = sqlx::query_as(
             "INSERT INTO users
-            (id, name, account, password, avatar, gender, age, phone, email, address, region, salt, signature, create_time, update_time)
+            (id, name, account, password, avatar, gender, age, phone, email, address, region, signature, role, create_time, update_time)
             VALUES
             ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) RETURNING *")
             .bind(&user.id)
@@ -107,8 +189,8 @@ impl UserRepo for UserPostgres {
             .bind(&user.email)
             .bind(&user.address)
             .bind(&user.region)
-            .bind(&user.salt)
             .bind(&user.signature)
+            .bind(role_name(user.role()))
             .bind(now)
             .bind(now)
             .fetch_one(&mut *tx)
@@ -118,17 +200,181 @@ impl UserRepo for UserPostgres {
         Ok(result)
     }
 
-    async fn delete(&self, id: &str) -> Result<(), Error> {
-        sqlx::query("delete from users where id = $1")
+    async fn update_password(&self, id: &str, password: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query("UPDATE users SET password = $1, update_time = $2 WHERE id = $3")
+            .bind(password)
+            .bind(now)
             .bind(id)
             .execute(&self.pool)
             .await?;
-        // let _ = sqlx::query("UPDATE users SET is_delete = TRUE WHERE id = $1")
-        //     .bind(id)
-        //     .execute(&self.pool)
-        //     .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+
+    async fn update_profile(
+        &self,
+        id: &str,
+        user: &User,
+        fields: &[ProfileField],
+    ) -> Result<User, Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE users SET ");
+        for field in fields {
+            builder.push(field.column()).push(" = ");
+            match field {
+                ProfileField::Name => builder.push_bind(&user.name),
+                ProfileField::Avatar => builder.push_bind(&user.avatar),
+                ProfileField::Gender => builder.push_bind(&user.gender),
+                ProfileField::Age => builder.push_bind(user.age),
+                ProfileField::Signature => builder.push_bind(&user.signature),
+                ProfileField::Address => builder.push_bind(&user.address),
+                ProfileField::Region => builder.push_bind(&user.region),
+                ProfileField::Birthday => builder.push_bind(user.birthday),
+            };
+            builder.push(", ");
+        }
+        builder
+            .push("update_time = ")
+            .push_bind(now)
+            .push(" WHERE id = ")
+            .push_bind(id)
+            .push(" RETURNING *");
+
+        let user = builder
+            .build_query_as::<User>()
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(user)
+    }
+
+    async fn ban(&self, id: &str, reason: &str, until: i64) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "UPDATE users SET banned = TRUE, ban_reason = $1, ban_until = $2, update_time = $3
+            WHERE id = $4 AND is_delete = FALSE",
+        )
+        .bind(reason)
+        .bind(until)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+
+    async fn unban(&self, id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "UPDATE users SET banned = FALSE, ban_reason = '', ban_until = 0, update_time = $1
+            WHERE id = $2 AND is_delete = FALSE",
+        )
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        sqlx::query(
+            "UPDATE users SET is_delete = TRUE, delete_time = $1, update_time = $1
+            WHERE id = $2 AND is_delete = FALSE",
+        )
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_deleted_by_account(&self, account: &str) -> Result<Option<DeletedUser>, Error> {
+        let row = sqlx::query(
+            "select * from users where account = $1 and is_delete = true and purge_time is null
+            order by delete_time desc limit 1",
+        )
+        .bind(account)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(DeletedUser {
+            user: User::from_row(&row)?,
+            delete_time: row.try_get("delete_time")?,
+        }))
+    }
+
+    async fn restore(&self, id: &str) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = sqlx::query(
+            "UPDATE users SET is_delete = FALSE, delete_time = NULL, update_time = $1
+            WHERE id = $2 AND is_delete = TRUE AND purge_time IS NULL",
+        )
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::from(sqlx::Error::RowNotFound));
+        }
         Ok(())
     }
+
+    async fn purge_deleted(
+        &self,
+        before: i64,
+        limit: i64,
+        mode: PurgeMode,
+    ) -> Result<Vec<String>, Error> {
+        let ids: Vec<(String,)> = match mode {
+            // 清空个人信息, 账号改为不可登录的占位值, 释放原账号/邮箱/手机号
+            PurgeMode::Anonymize => {
+                let now = chrono::Utc::now().timestamp_millis();
+                sqlx::query_as(
+                    "UPDATE users SET name = '', account = 'deleted:' || id, password = '',
+                        avatar = '', gender = '', age = 0, phone = NULL, email = NULL,
+                        address = NULL, region = NULL, birthday = NULL, signature = '',
+                        purge_time = $3, update_time = $3
+                    WHERE id IN (
+                        SELECT id FROM users
+                        WHERE is_delete = TRUE AND purge_time IS NULL AND delete_time < $1
+                        LIMIT $2
+                    ) RETURNING id",
+                )
+                .bind(before)
+                .bind(limit)
+                .bind(now)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            PurgeMode::Delete => {
+                sqlx::query_as(
+                    "DELETE FROM users
+                    WHERE id IN (
+                        SELECT id FROM users
+                        WHERE is_delete = TRUE AND purge_time IS NULL AND delete_time < $1
+                        LIMIT $2
+                    ) RETURNING id",
+                )
+                .bind(before)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
 }
 
 impl FromRow<'_, PgRow> for User {
@@ -148,21 +394,35 @@ impl FromRow<'_, PgRow> for User {
             birthday: row.try_get("birthday")?,
             create_time: row.try_get("create_time")?,
             update_time: row.try_get("update_time")?,
-            salt: row.try_get("salt")?,
             signature: row.try_get("signature")?,
+            role: parse_role(row.try_get("role")?) as i32,
+            banned: row.try_get("banned")?,
+            ban_reason: row.try_get("ban_reason")?,
+            ban_until: row.try_get("ban_until")?,
         })
     }
 }
 
+/// 转义LIKE通配符
+fn escape_like(keyword: &str) -> String {
+    keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::Config;
+    use crate::pb::user::UserRole;
+    use common::LoadableConfig;
     use nanoid::nanoid;
 
     #[tokio::test]
+    #[ignore = "requires postgres"]
     async fn test_find_by_account() -> anyhow::Result<()> {
-        let config = Config::new(r"etc/user.yml");
+        let config = Config::load("etc/user.yml");
         let user_repo = UserPostgres::from_config(&config).await;
         let account = nanoid!();
         let user = User {
@@ -207,8 +467,9 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "requires postgres"]
     async fn test_insert_and_find_by_id_and_delete() -> anyhow::Result<()> {
-        let config = Config::new(r"etc/user.yml");
+        let config = Config::load("etc/user.yml");
         let user_repo = UserPostgres::from_config(&config).await;
         let id = nanoid!();
         let user = User {
@@ -245,4 +506,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[ignore = "requires postgres"]
+    async fn test_list_and_ban() -> anyhow::Result<()> {
+        let config = Config::load("etc/user.yml");
+        let user_repo = UserPostgres::from_config(&config).await;
+        let id = nanoid!();
+        let account = format!("admin_{}", nanoid!(8));
+        let user = User {
+            id: id.clone(),
+            name: "test-name".to_string(),
+            account: account.clone(),
+            role: UserRole::Support as i32,
+            ..Default::default()
+        };
+        user_repo.insert(user).await.expect("insert user success");
+
+        // 按账号前缀和角色过滤
+        let filter = UserFilter {
+            keyword: Some(account.clone()),
+            role: Some(UserRole::Support),
+            banned: Some(false),
+        };
+        let users = user_repo.list(&filter, None, 10).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].role(), UserRole::Support);
+
+        // 封禁后按封禁状态过滤
+        user_repo.ban(&id, "spam", 0).await.unwrap();
+        assert!(user_repo.list(&filter, None, 10).await.unwrap().is_empty());
+        let banned = UserFilter {
+            banned: Some(true),
+            ..filter.clone()
+        };
+        let users = user_repo.list(&banned, None, 10).await.unwrap();
+        assert_eq!(users.len(), 1);
+        assert!(users[0].banned);
+        assert_eq!(users[0].ban_reason, "spam");
+
+        user_repo.unban(&id).await.unwrap();
+        let user = user_repo.find_by_id(&id).await.unwrap().unwrap();
+        assert!(!user.banned);
+        assert_eq!(user.ban_reason, "");
+
+        user_repo.delete(&id).await.unwrap();
+        Ok(())
+    }
 }
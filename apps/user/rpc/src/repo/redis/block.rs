@@ -0,0 +1,186 @@
+use crate::config::UserCacheConfig;
+use crate::error::Error;
+use crate::repo::{BlockRepo, UserBlock};
+use async_trait::async_trait;
+use rand::Rng;
+use redis::AsyncCommands;
+use std::time::Duration;
+use tracing::warn;
+
+const USER_BLOCKS_KEY: &str = "user_blocks";
+/// 集合中的占位成员, 没有拉黑任何人时也缓存, 防止缓存穿透. 用户id不会为空
+const PLACEHOLDER: &str = "";
+
+/// 带Redis读缓存的BlockRepo装饰器
+///
+/// 每个用户拉黑的全部用户缓存为一个集合, 供拉黑关系检查使用;
+/// 写操作与CachedUserRepo一致, 先更新数据库, 再删除缓存, 可选延迟双删.
+#[derive(Debug)]
+pub struct CachedBlockRepo {
+    inner: Box<dyn BlockRepo>,
+    client: redis::Client,
+    config: UserCacheConfig,
+}
+
+impl CachedBlockRepo {
+    pub fn new(inner: Box<dyn BlockRepo>, client: redis::Client, config: UserCacheConfig) -> Self {
+        Self {
+            inner,
+            client,
+            config,
+        }
+    }
+
+    fn key(user_id: &str) -> String {
+        format!("{}:{}", USER_BLOCKS_KEY, user_id)
+    }
+
+    /// 过期时间加上随机抖动, 避免大量key同时过期
+    fn ttl(&self) -> i64 {
+        let jitter = match self.config.jitter {
+            0 => 0,
+            jitter => rand::thread_rng().gen_range(0..=jitter),
+        };
+        (self.config.ttl + jitter) as i64
+    }
+
+    async fn get_cached(&self, user_id: &str) -> Result<Option<Vec<String>>, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let members: Vec<String> = conn.smembers(Self::key(user_id)).await?;
+        if members.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            members
+                .into_iter()
+                .filter(|member| member != PLACEHOLDER)
+                .collect(),
+        ))
+    }
+
+    async fn set_cached(&self, user_id: &str, ids: &[String]) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = Self::key(user_id);
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .del(&key)
+            .ignore()
+            .sadd(&key, PLACEHOLDER)
+            .ignore();
+        if !ids.is_empty() {
+            pipe.sadd(&key, ids).ignore();
+        }
+        pipe.expire(&key, self.ttl())
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 删除缓存, 配置了延迟双删时, 在延迟后再删除一次
+    async fn invalidate(&self, user_id: &str) {
+        let key = Self::key(user_id);
+        if let Err(e) = Self::delete_key(&self.client, &key).await {
+            warn!("delete block cache failed, key: {}, {:?}", key, e);
+        }
+
+        if self.config.delayed_delete_ms > 0 {
+            let client = self.client.clone();
+            let delay = Duration::from_millis(self.config.delayed_delete_ms);
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Err(e) = Self::delete_key(&client, &key).await {
+                    warn!("delayed delete block cache failed, key: {}, {:?}", key, e);
+                }
+            });
+        }
+    }
+
+    async fn delete_key(client: &redis::Client, key: &str) -> Result<(), Error> {
+        let mut conn = client.get_multiplexed_async_connection().await?;
+        conn.del::<_, ()>(key).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BlockRepo for CachedBlockRepo {
+    async fn insert(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let inserted = self.inner.insert(user_id, blocked_user_id).await?;
+        self.invalidate(user_id).await;
+        Ok(inserted)
+    }
+
+    async fn delete(&self, user_id: &str, blocked_user_id: &str) -> Result<bool, Error> {
+        let deleted = self.inner.delete(user_id, blocked_user_id).await?;
+        self.invalidate(user_id).await;
+        Ok(deleted)
+    }
+
+    async fn list(
+        &self,
+        user_id: &str,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<UserBlock>, Error> {
+        self.inner.list(user_id, before_id, limit).await
+    }
+
+    async fn find_blocked_ids(&self, user_id: &str) -> Result<Vec<String>, Error> {
+        // 缓存不可用时直接回源
+        match self.get_cached(user_id).await {
+            Ok(Some(ids)) => return Ok(ids),
+            Ok(None) => {}
+            Err(e) => {
+                warn!("get block cache failed, {:?}", e);
+                return self.inner.find_blocked_ids(user_id).await;
+            }
+        }
+
+        let ids = self.inner.find_blocked_ids(user_id).await?;
+        if let Err(e) = self.set_cached(user_id, &ids).await {
+            warn!("set block cache failed, {:?}", e);
+        }
+        Ok(ids)
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<(), Error> {
+        // 被拉黑的记录分散在其他用户的缓存中, 由缓存过期清理
+        self.inner.delete_by_user(user_id).await?;
+        self.invalidate(user_id).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::repo::memory::block::MemoryBlockRepo;
+    use common::LoadableConfig;
+
+    #[tokio::test]
+    #[ignore = "requires redis"]
+    async fn test_cached_block_repo() {
+        let config = Config::load("etc/user.yml");
+        let client = redis::Client::open(config.redis.url()).unwrap();
+        let repo = CachedBlockRepo::new(
+            Box::new(MemoryBlockRepo::new()),
+            client,
+            config.user_cache.clone(),
+        );
+        let user_id = format!("test:{}", nanoid::nanoid!());
+
+        // 没有拉黑任何人时缓存占位成员
+        assert!(repo.find_blocked_ids(&user_id).await.unwrap().is_empty());
+        assert_eq!(repo.get_cached(&user_id).await.unwrap(), Some(vec![]));
+
+        repo.insert(&user_id, "a").await.unwrap();
+        assert_eq!(repo.get_cached(&user_id).await.unwrap(), None);
+        assert_eq!(repo.find_blocked_ids(&user_id).await.unwrap(), vec!["a"]);
+        assert_eq!(
+            repo.get_cached(&user_id).await.unwrap(),
+            Some(vec!["a".to_string()])
+        );
+    }
+}
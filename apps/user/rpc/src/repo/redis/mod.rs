@@ -1,13 +1,153 @@
 use crate::config::Config;
 use crate::error::Error;
+use crate::event::{UserChange, UserChangeKind, USER_EVENTS_CHANNEL};
+use crate::pb::user::{PresenceStatus, UserPresence};
 use crate::repo::Cache;
 use async_trait::async_trait;
 use redis::AsyncCommands;
 
+pub(crate) mod block;
+pub(crate) mod user;
+
 const REGISTER_CODE_KEY: &str = "register_code";
-const REGISTER_CODE_TTL_SECONDS: i64 = 300;
+const REGISTER_CODE_TTL_SECONDS: u64 = 300;
+
+const PASSWORD_RESET_CODE_KEY: &str = "password_reset_code";
+const PASSWORD_RESET_CODE_TTL_SECONDS: u64 = 600;
+
+const SMS_CODE_KEY: &str = "sms_code";
+const SMS_CODE_TTL_SECONDS: u64 = 300;
+
+const LOGIN_CHALLENGE_KEY: &str = "login_challenge";
+
+const OIDC_STATE_KEY: &str = "oidc_state";
+
+const SESSIONS_REVOKED_AT_KEY: &str = "sessions_revoked_at";
+/// 小于该值的注销时间是旧版本保存的秒, 10^12毫秒为2001年
+const MILLIS_THRESHOLD: i64 = 1_000_000_000_000;
+
+/// 用户的在线设备, 有序集合, 成员为`{platform}:{device_id}`, 分数为过期时间(毫秒)
+const PRESENCE_DEVICES_KEY: &str = "presence_devices";
+/// 在线用户, 有序集合, 分数为所有设备中最晚的过期时间; 按平台统计时使用`presence_online:{platform}`
+const PRESENCE_ONLINE_KEY: &str = "presence_online";
+/// 用户设置的在线状态, 未设置时为在线
+const PRESENCE_STATUS_KEY: &str = "presence_status";
+/// 用户最后在线时间(毫秒)
+const PRESENCE_LAST_SEEN_KEY: &str = "presence_last_seen";
+
+const RATE_LIMIT_KEY: &str = "rate_limit";
+const FAILED_ATTEMPTS_KEY: &str = "failed_attempts";
+const LOCKOUT_KEY: &str = "lockout";
+
+/// 基于有序集合的滑动窗口, 返回 {是否放行, 需要等待的毫秒数}
+const SLIDING_WINDOW_SCRIPT: &str = r"
+local key = KEYS[1]
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+local member = ARGV[4]
 
-const USER_ONLINE_SET: &str = "user_online_set";
+redis.call('ZREMRANGEBYSCORE', key, 0, now - window)
+local count = redis.call('ZCARD', key)
+if count < limit then
+    redis.call('ZADD', key, now, member)
+    redis.call('PEXPIRE', key, window)
+    return {1, 0}
+end
+
+local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+return {0, tonumber(oldest[2]) + window - now}
+";
+
+/// 设备心跳: 刷新设备过期时间, 更新在线集合; 隐身时不更新最后在线时间.
+/// 所在平台从离线变为在线时发布通知, 隐身时不发布
+const HEARTBEAT_SCRIPT: &str = r"
+local uid = ARGV[1]
+local now = tonumber(ARGV[3])
+local expire_at = now + tonumber(ARGV[4])
+local was_online = tonumber(redis.call('ZSCORE', KEYS[3], uid) or '0') > now
+
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now)
+redis.call('ZADD', KEYS[1], expire_at, ARGV[2])
+redis.call('PEXPIRE', KEYS[1], ARGV[4])
+redis.call('ZADD', KEYS[2], 'GT', expire_at, uid)
+redis.call('ZADD', KEYS[3], 'GT', expire_at, uid)
+if redis.call('HGET', KEYS[4], uid) ~= ARGV[5] then
+    redis.call('HSET', KEYS[5], uid, now)
+    if not was_online then
+        redis.call('PUBLISH', ARGV[6], cjson.encode({kind = 'presence', user_id = uid}))
+    end
+end
+return 1
+";
+
+/// 设备下线: 移除指定设备(未指定时移除全部设备), 用剩余设备重新计算在线集合中的过期时间.
+/// 用户或某个平台因此离线时发布通知, 隐身时不发布
+const OFFLINE_SCRIPT: &str = r"
+local uid = ARGV[1]
+local now = tonumber(ARGV[2])
+local removed = {}
+if #ARGV > 5 then
+    for i = 6, #ARGV do
+        if redis.call('ZREM', KEYS[1], ARGV[i]) == 1 then
+            table.insert(removed, ARGV[i])
+        end
+    end
+else
+    removed = redis.call('ZRANGE', KEYS[1], 0, -1)
+    redis.call('DEL', KEYS[1])
+end
+if #removed == 0 then
+    return 0
+end
+
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now)
+local remaining = redis.call('ZRANGE', KEYS[1], 0, -1, 'WITHSCORES')
+local function refresh(key, platform)
+    local max = nil
+    for i = 1, #remaining, 2 do
+        if platform == nil or string.sub(remaining[i], 1, #platform + 1) == platform .. ':' then
+            local score = tonumber(remaining[i + 1])
+            if max == nil or score > max then
+                max = score
+            end
+        end
+    end
+    if max == nil then
+        return redis.call('ZREM', key, uid) == 1
+    end
+    redis.call('ZADD', key, max, uid)
+    return false
+end
+
+local changed = refresh(KEYS[2], nil)
+for _, member in ipairs(removed) do
+    local platform = string.match(member, '^([^:]+):')
+    if platform and refresh(ARGV[3] .. platform, platform) then
+        changed = true
+    end
+end
+if redis.call('HGET', KEYS[3], uid) ~= ARGV[4] then
+    redis.call('HSET', KEYS[4], uid, now)
+    if changed then
+        redis.call('PUBLISH', ARGV[5], cjson.encode({kind = 'presence', user_id = uid}))
+    end
+end
+return #removed
+";
+
+/// 移除在线集合中已过期的用户并发布通知, 隐身用户不发布; 返回被移除的用户
+const EXPIRE_PRESENCE_SCRIPT: &str = r"
+local now = tonumber(ARGV[1])
+local expired = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', now, 'LIMIT', 0, tonumber(ARGV[2]))
+for _, uid in ipairs(expired) do
+    redis.call('ZREM', KEYS[1], uid)
+    if redis.call('HGET', KEYS[2], uid) ~= ARGV[3] then
+        redis.call('PUBLISH', ARGV[4], cjson.encode({kind = 'presence', user_id = uid}))
+    end
+end
+return expired
+";
 
 #[derive(Debug)]
 pub struct RedisCache {
@@ -22,52 +162,383 @@ impl RedisCache {
         let client = redis::Client::open(config.redis.url()).expect("open redis client success");
         Self { client }
     }
+
+    /// 验证码按用途划分key空间, 每个账号单独一个key, 互不影响过期时间
+    async fn get_code(&self, scope: &str, target: &str) -> Result<String, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", scope, target);
+        let result = conn.get(key).await?;
+        Ok(result)
+    }
+
+    async fn save_code(
+        &self,
+        scope: &str,
+        target: &str,
+        code: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", scope, target);
+        conn.set_ex::<_, _, ()>(key, code, ttl_seconds).await?;
+        Ok(())
+    }
+
+    async fn offline(&self, user_id: &str, members: &[String]) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::Script::new(OFFLINE_SCRIPT)
+            .key(format!("{}:{}", PRESENCE_DEVICES_KEY, user_id))
+            .key(PRESENCE_ONLINE_KEY)
+            .key(PRESENCE_STATUS_KEY)
+            .key(PRESENCE_LAST_SEEN_KEY)
+            .arg(user_id)
+            .arg(chrono::Utc::now().timestamp_millis())
+            .arg(format!("{}:", PRESENCE_ONLINE_KEY))
+            .arg(PresenceStatus::Invisible as i32)
+            .arg(USER_EVENTS_CHANNEL)
+            .arg(members)
+            .invoke_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 清理已过期的在线用户后统计数量
+    async fn count_online(&self, key: &str) -> Result<i64, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let (count,): (i64,) = redis::pipe()
+            .zrembyscore(key, "-inf", now)
+            .ignore()
+            .zcard(key)
+            .query_async(&mut conn)
+            .await?;
+        Ok(count)
+    }
+
+    async fn delete_code(&self, scope: &str, target: &str) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", scope, target);
+        conn.del::<_, ()>(key).await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Cache for RedisCache {
     async fn get_user_register_code(&self, account: &str) -> Result<String, Error> {
+        self.get_code(REGISTER_CODE_KEY, account).await
+    }
+
+    async fn save_user_register_code(&self, account: &str, code: &str) -> Result<(), Error> {
+        self.save_code(REGISTER_CODE_KEY, account, code, REGISTER_CODE_TTL_SECONDS)
+            .await
+    }
+
+    async fn delete_user_register_code(&self, account: &str) -> Result<(), Error> {
+        self.delete_code(REGISTER_CODE_KEY, account).await
+    }
+
+    async fn get_password_reset_code(&self, email: &str) -> Result<String, Error> {
+        self.get_code(PASSWORD_RESET_CODE_KEY, email).await
+    }
+
+    async fn save_password_reset_code(&self, email: &str, code: &str) -> Result<(), Error> {
+        self.save_code(
+            PASSWORD_RESET_CODE_KEY,
+            email,
+            code,
+            PASSWORD_RESET_CODE_TTL_SECONDS,
+        )
+        .await
+    }
+
+    async fn delete_password_reset_code(&self, email: &str) -> Result<(), Error> {
+        self.delete_code(PASSWORD_RESET_CODE_KEY, email).await
+    }
+
+    async fn get_sms_code(&self, scene: &str, phone: &str) -> Result<String, Error> {
+        self.get_code(SMS_CODE_KEY, &format!("{}:{}", scene, phone))
+            .await
+    }
+
+    async fn save_sms_code(&self, scene: &str, phone: &str, code: &str) -> Result<(), Error> {
+        let target = format!("{}:{}", scene, phone);
+        self.save_code(SMS_CODE_KEY, &target, code, SMS_CODE_TTL_SECONDS)
+            .await
+    }
+
+    async fn delete_sms_code(&self, scene: &str, phone: &str) -> Result<(), Error> {
+        self.delete_code(SMS_CODE_KEY, &format!("{}:{}", scene, phone))
+            .await
+    }
+
+    async fn save_login_challenge(
+        &self,
+        token: &str,
+        user_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        self.save_code(LOGIN_CHALLENGE_KEY, token, user_id, ttl_seconds)
+            .await
+    }
+
+    async fn get_login_challenge(&self, token: &str) -> Result<Option<String>, Error> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let result = conn.hget(REGISTER_CODE_KEY, account).await?;
+        let key = format!("{}:{}", LOGIN_CHALLENGE_KEY, token);
+        let result = conn.get(key).await?;
         Ok(result)
     }
 
-    async fn save_user_register_code(&self, account: &str, code: &str) -> Result<(), Error> {
+    async fn delete_login_challenge(&self, token: &str) -> Result<(), Error> {
+        self.delete_code(LOGIN_CHALLENGE_KEY, token).await
+    }
+
+    async fn save_oidc_state(
+        &self,
+        state: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        self.save_code(OIDC_STATE_KEY, state, value, ttl_seconds)
+            .await
+    }
+
+    async fn take_oidc_state(&self, state: &str) -> Result<Option<String>, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", OIDC_STATE_KEY, state);
+        let result = conn.get_del(key).await?;
+        Ok(result)
+    }
+
+    async fn revoke_user_sessions(&self, user_id: &str) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        conn.hset::<_, _, _, ()>(SESSIONS_REVOKED_AT_KEY, user_id, now)
+            .await?;
+        // 所有设备下线
+        self.offline(user_id, &[]).await
+    }
+
+    async fn get_user_sessions_revoked_at(&self, user_id: &str) -> Result<Option<i64>, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Option<i64> = conn.hget(SESSIONS_REVOKED_AT_KEY, user_id).await?;
+        // 旧版本保存的是秒
+        Ok(result.map(|revoked_at| {
+            if revoked_at < MILLIS_THRESHOLD {
+                revoked_at * 1000
+            } else {
+                revoked_at
+            }
+        }))
+    }
+
+    async fn heartbeat(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::Script::new(HEARTBEAT_SCRIPT)
+            .key(format!("{}:{}", PRESENCE_DEVICES_KEY, user_id))
+            .key(PRESENCE_ONLINE_KEY)
+            .key(format!("{}:{}", PRESENCE_ONLINE_KEY, platform))
+            .key(PRESENCE_STATUS_KEY)
+            .key(PRESENCE_LAST_SEEN_KEY)
+            .arg(user_id)
+            .arg(format!("{}:{}", platform, device_id))
+            .arg(chrono::Utc::now().timestamp_millis())
+            .arg(ttl_seconds * 1000)
+            .arg(PresenceStatus::Invisible as i32)
+            .arg(USER_EVENTS_CHANNEL)
+            .invoke_async::<()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn device_offline(
+        &self,
+        user_id: &str,
+        platform: &str,
+        device_id: &str,
+    ) -> Result<(), Error> {
+        self.offline(user_id, &[format!("{}:{}", platform, device_id)])
+            .await
+    }
+
+    async fn set_presence_status(
+        &self,
+        user_id: &str,
+        status: PresenceStatus,
+    ) -> Result<(), Error> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let change = UserChange::new(UserChangeKind::Presence, user_id);
+        let payload = serde_json::to_string(&change)
+            .map_err(|e| Error::internal_with_details(e.to_string()))?;
         let mut pipe = redis::pipe();
-        pipe.hset(REGISTER_CODE_KEY, account, code)
-            .expire(REGISTER_CODE_KEY, REGISTER_CODE_TTL_SECONDS)
-            .query_async(&mut conn)
+        pipe.atomic();
+        if status == PresenceStatus::Online {
+            pipe.hdel(PRESENCE_STATUS_KEY, user_id).ignore();
+        } else {
+            pipe.hset(PRESENCE_STATUS_KEY, user_id, status as i32)
+                .ignore();
+        }
+        pipe.publish(USER_EVENTS_CHANNEL, payload)
+            .ignore()
+            .query_async::<()>(&mut conn)
             .await?;
         Ok(())
     }
 
-    async fn delete_user_register_code(&self, account: &str) -> Result<(), Error> {
+    async fn get_users_presence(&self, user_ids: &[String]) -> Result<Vec<UserPresence>, Error> {
+        if user_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            pipe.zrangebyscore(format!("{}:{}", PRESENCE_DEVICES_KEY, user_id), now, "+inf");
+        }
+        let devices: Vec<Vec<String>> = pipe.query_async(&mut conn).await?;
+        let (statuses, last_seens): (Vec<Option<i32>>, Vec<Option<i64>>) = redis::pipe()
+            .cmd("HMGET")
+            .arg(PRESENCE_STATUS_KEY)
+            .arg(user_ids)
+            .cmd("HMGET")
+            .arg(PRESENCE_LAST_SEEN_KEY)
+            .arg(user_ids)
+            .query_async(&mut conn)
+            .await?;
+
+        let presences = user_ids
+            .iter()
+            .zip(devices)
+            .zip(statuses.into_iter().zip(last_seens))
+            .map(|((user_id, devices), (status, last_seen))| {
+                let mut platforms: Vec<String> = devices
+                    .iter()
+                    .filter_map(|device| device.split_once(':'))
+                    .map(|(platform, _)| platform.to_string())
+                    .collect();
+                platforms.sort();
+                platforms.dedup();
+
+                let status = if devices.is_empty() {
+                    PresenceStatus::Offline as i32
+                } else {
+                    status.unwrap_or(PresenceStatus::Online as i32)
+                };
+                UserPresence {
+                    user_id: user_id.clone(),
+                    status,
+                    last_seen: last_seen.unwrap_or_default(),
+                    platforms,
+                }
+            })
+            .collect();
+        Ok(presences)
+    }
+
+    async fn get_user_online_count(&self) -> Result<i64, Error> {
+        // 过期的用户由expire_presence移除并发布通知, 这里只统计不清理
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let count = conn.zcount(PRESENCE_ONLINE_KEY, now, "+inf").await?;
+        Ok(count)
+    }
+
+    async fn get_platform_online_count(&self, platform: &str) -> Result<i64, Error> {
+        self.count_online(&format!("{}:{}", PRESENCE_ONLINE_KEY, platform))
+            .await
+    }
+
+    async fn expire_presence(&self, limit: usize) -> Result<Vec<String>, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let expired = redis::Script::new(EXPIRE_PRESENCE_SCRIPT)
+            .key(PRESENCE_ONLINE_KEY)
+            .key(PRESENCE_STATUS_KEY)
+            .arg(chrono::Utc::now().timestamp_millis())
+            .arg(limit)
+            .arg(PresenceStatus::Invisible as i32)
+            .arg(USER_EVENTS_CHANNEL)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(expired)
+    }
+
+    async fn check_rate_limit(
+        &self,
+        key: &str,
+        limit: u64,
+        window_seconds: u64,
+    ) -> Result<Option<u64>, Error> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        conn.hdel(REGISTER_CODE_KEY, account).await?;
+        let key = format!("{}:{}", RATE_LIMIT_KEY, key);
+        let now = chrono::Utc::now().timestamp_millis();
+        let member = format!("{}-{}", now, nanoid::nanoid!(8));
+
+        let (allowed, wait_millis): (i64, i64) = redis::Script::new(SLIDING_WINDOW_SCRIPT)
+            .key(key)
+            .arg(now)
+            .arg(window_seconds * 1000)
+            .arg(limit)
+            .arg(member)
+            .invoke_async(&mut conn)
+            .await?;
+
+        if allowed == 1 {
+            return Ok(None);
+        }
+        // 向上取整到秒, 至少等待1秒
+        Ok(Some((wait_millis.max(0) as u64).div_ceil(1000).max(1)))
+    }
+
+    async fn incr_failed_attempts(&self, key: &str, window_seconds: u64) -> Result<u64, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", FAILED_ATTEMPTS_KEY, key);
+        let count: u64 = conn.incr(&key, 1).await?;
+        // 第一次失败时开始计时, 窗口结束后计数自动清零
+        if count == 1 {
+            conn.expire::<_, ()>(&key, window_seconds as i64).await?;
+        }
+        Ok(count)
+    }
+
+    async fn clear_failed_attempts(&self, key: &str) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let key = format!("{}:{}", FAILED_ATTEMPTS_KEY, key);
+        conn.del::<_, ()>(key).await?;
         Ok(())
     }
 
-    async fn set_user_login(&self, user_id: &str) -> Result<(), Error> {
+    async fn set_lockout(&self, key: &str, seconds: u64) -> Result<(), Error> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        conn.sadd(USER_ONLINE_SET, user_id).await?;
+        let key = format!("{}:{}", LOCKOUT_KEY, key);
+        conn.set_ex::<_, _, ()>(key, 1, seconds).await?;
         Ok(())
     }
 
-    async fn get_user_online_count(&self) -> Result<i64, Error> {
+    async fn get_lockout_ttl(&self, key: &str) -> Result<Option<u64>, Error> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let result: i64 = conn.scard(USER_ONLINE_SET).await?;
-        Ok(result)
+        let key = format!("{}:{}", LOCKOUT_KEY, key);
+        // key不存在时返回-2, 没有过期时间时返回-1
+        let ttl: i64 = conn.ttl(key).await?;
+        Ok((ttl > 0).then_some(ttl as u64))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::LoadableConfig;
 
     #[tokio::test]
+    #[ignore = "requires redis"]
     async fn test_redis_cache_save_and_get_and_delete_user_register_code() -> anyhow::Result<()> {
-        let config = Config::new("etc/user.yml");
+        let config = Config::load("etc/user.yml");
         let cache = RedisCache::from_config(&config);
 
         let code = "123456";
@@ -92,4 +563,58 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[ignore = "requires redis"]
+    async fn test_redis_cache_check_rate_limit() -> anyhow::Result<()> {
+        let config = Config::load("etc/user.yml");
+        let cache = RedisCache::from_config(&config);
+        let key = format!("test:{}", nanoid::nanoid!());
+
+        for _ in 0..2 {
+            let retry_after = cache.check_rate_limit(&key, 2, 60).await;
+            assert!(matches!(retry_after, Ok(None)));
+        }
+
+        // 超过限制后需要等待
+        let retry_after = cache.check_rate_limit(&key, 2, 60).await;
+        assert!(matches!(retry_after, Ok(Some(1..=60))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "requires redis"]
+    async fn test_redis_cache_presence() -> anyhow::Result<()> {
+        let config = Config::load("etc/user.yml");
+        let cache = RedisCache::from_config(&config);
+        let user_id = format!("test:{}", nanoid::nanoid!());
+
+        assert!(cache.heartbeat(&user_id, "ios", "d1", 60).await.is_ok());
+        assert!(cache.heartbeat(&user_id, "web", "d2", 60).await.is_ok());
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].status, PresenceStatus::Online as i32);
+        assert_eq!(presences[0].platforms, vec!["ios", "web"]);
+        assert!(presences[0].last_seen > 0);
+
+        // 一个设备下线后仍然在线
+        assert!(cache.device_offline(&user_id, "ios", "d1").await.is_ok());
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].platforms, vec!["web"]);
+
+        assert!(cache.device_offline(&user_id, "web", "d2").await.is_ok());
+        let presences = cache
+            .get_users_presence(std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(presences[0].status, PresenceStatus::Offline as i32);
+
+        Ok(())
+    }
 }
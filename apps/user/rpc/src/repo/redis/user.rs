@@ -0,0 +1,302 @@
+use crate::config::{PurgeMode, UserCacheConfig};
+use crate::error::Error;
+use crate::pb::user::User;
+use crate::repo::{DeletedUser, ProfileField, SearchCursor, UserFilter, UserRepo};
+use async_trait::async_trait;
+use prost::Message;
+use rand::Rng;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::warn;
+
+const USER_INFO_KEY: &str = "user_info";
+/// 空值缓存的占位符, 防止缓存穿透. 合法的User编码不会等于该值
+const NULL_VALUE: &[u8] = b"nil";
+
+/// 带Redis读缓存的UserRepo装饰器
+///
+/// `find_by_id`/`find_by_ids`优先读缓存, 未命中时回源并写入缓存;
+/// 写操作按照"先更新数据库, 再删除缓存"的方式保证一致性, 可选延迟双删.
+#[derive(Debug)]
+pub struct CachedUserRepo {
+    inner: Box<dyn UserRepo>,
+    client: redis::Client,
+    config: UserCacheConfig,
+}
+
+impl CachedUserRepo {
+    pub fn new(inner: Box<dyn UserRepo>, client: redis::Client, config: UserCacheConfig) -> Self {
+        Self {
+            inner,
+            client,
+            config,
+        }
+    }
+
+    fn key(id: &str) -> String {
+        format!("{}:{}", USER_INFO_KEY, id)
+    }
+
+    /// 过期时间加上随机抖动, 避免大量key同时过期
+    fn ttl(&self, ttl: u64) -> u64 {
+        if self.config.jitter == 0 {
+            return ttl;
+        }
+        ttl + rand::thread_rng().gen_range(0..=self.config.jitter)
+    }
+
+    fn decode(value: &[u8]) -> Result<Option<User>, Error> {
+        if value == NULL_VALUE {
+            return Ok(None);
+        }
+        let user = User::decode(value)
+            .map_err(|e| Error::internal_with_details(format!("decode user cache, {}", e)))?;
+        Ok(Some(user))
+    }
+
+    async fn get_cached(&self, ids: &[String]) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let keys: Vec<String> = ids.iter().map(|id| Self::key(id)).collect();
+        let values: Vec<Option<Vec<u8>>> = conn.mget(keys).await?;
+        Ok(values)
+    }
+
+    /// 回写缓存, 不存在的用户写入空值
+    async fn set_cached(&self, ids: &[String], users: &HashMap<&str, &User>) -> Result<(), Error> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut pipe = redis::pipe();
+        for id in ids {
+            match users.get(id.as_str()) {
+                Some(user) => pipe.set_ex(
+                    Self::key(id),
+                    user.encode_to_vec(),
+                    self.ttl(self.config.ttl),
+                ),
+                None => pipe.set_ex(Self::key(id), NULL_VALUE, self.ttl(self.config.null_ttl)),
+            };
+        }
+        pipe.query_async::<()>(&mut conn).await?;
+        Ok(())
+    }
+
+    /// 删除缓存, 配置了延迟双删时, 在延迟后再删除一次,
+    /// 清理并发读请求在数据库更新期间回写的旧值
+    async fn invalidate(&self, id: &str) {
+        let key = Self::key(id);
+        if let Err(e) = Self::delete_key(&self.client, &key).await {
+            warn!("delete user cache failed, key: {}, {:?}", key, e);
+        }
+
+        if self.config.delayed_delete_ms > 0 {
+            let client = self.client.clone();
+            let delay = Duration::from_millis(self.config.delayed_delete_ms);
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Err(e) = Self::delete_key(&client, &key).await {
+                    warn!("delayed delete user cache failed, key: {}, {:?}", key, e);
+                }
+            });
+        }
+    }
+
+    async fn delete_key(client: &redis::Client, key: &str) -> Result<(), Error> {
+        let mut conn = client.get_multiplexed_async_connection().await?;
+        conn.del::<_, ()>(key).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserRepo for CachedUserRepo {
+    async fn find_by_id(&self, id: &str) -> Result<Option<User>, Error> {
+        let users = self.find_by_ids(vec![id.to_string()]).await?;
+        Ok(users.into_iter().next())
+    }
+
+    async fn find_by_ids(&self, ids: Vec<String>) -> Result<Vec<User>, Error> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // 缓存不可用时直接回源
+        let cached = match self.get_cached(&ids).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("get user cache failed, {:?}", e);
+                return self.inner.find_by_ids(ids).await;
+            }
+        };
+
+        let mut users = HashMap::with_capacity(ids.len());
+        let mut missed = vec![];
+        for (id, value) in ids.iter().zip(cached) {
+            match value {
+                Some(value) => {
+                    if let Some(user) = Self::decode(&value)? {
+                        users.insert(id.clone(), user);
+                    }
+                }
+                None => missed.push(id.clone()),
+            }
+        }
+
+        if !missed.is_empty() {
+            let found = self.inner.find_by_ids(missed.clone()).await?;
+            let found_map: HashMap<&str, &User> =
+                found.iter().map(|u| (u.id.as_str(), u)).collect();
+            if let Err(e) = self.set_cached(&missed, &found_map).await {
+                warn!("set user cache failed, {:?}", e);
+            }
+            users.extend(found.into_iter().map(|user| (user.id.clone(), user)));
+        }
+        Ok(in_request_order(&ids, users))
+    }
+
+    async fn find_by_account(&self, account: &str) -> Result<Option<User>, Error> {
+        self.inner.find_by_account(account).await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        self.inner.find_by_email(email).await
+    }
+
+    async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        self.inner.find_by_phone(phone).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<User>, Error> {
+        self.inner.find_by_name(name).await
+    }
+
+    async fn search(
+        &self,
+        keyword: &str,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        self.inner.search(keyword, cursor, limit).await
+    }
+
+    async fn list(
+        &self,
+        filter: &UserFilter,
+        cursor: Option<SearchCursor>,
+        limit: i64,
+    ) -> Result<Vec<User>, Error> {
+        self.inner.list(filter, cursor, limit).await
+    }
+
+    async fn find_by_account_or_email(
+        &self,
+        account: &str,
+        email: &str,
+    ) -> Result<Option<User>, Error> {
+        self.inner.find_by_account_or_email(account, email).await
+    }
+
+    async fn insert(&self, user: User) -> Result<(), Error> {
+        let id = user.id.clone();
+        self.inner.insert(user).await?;
+        // 清除可能存在的空值缓存
+        self.invalidate(&id).await;
+        Ok(())
+    }
+
+    async fn update_password(&self, id: &str, password: &str) -> Result<(), Error> {
+        self.inner.update_password(id, password).await?;
+        self.invalidate(id).await;
+        Ok(())
+    }
+
+    async fn update_profile(
+        &self,
+        id: &str,
+        user: &User,
+        fields: &[ProfileField],
+    ) -> Result<User, Error> {
+        let user = self.inner.update_profile(id, user, fields).await?;
+        self.invalidate(id).await;
+        Ok(user)
+    }
+
+    async fn ban(&self, id: &str, reason: &str, until: i64) -> Result<(), Error> {
+        self.inner.ban(id, reason, until).await?;
+        self.invalidate(id).await;
+        Ok(())
+    }
+
+    async fn unban(&self, id: &str) -> Result<(), Error> {
+        self.inner.unban(id).await?;
+        self.invalidate(id).await;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        self.inner.delete(id).await?;
+        self.invalidate(id).await;
+        Ok(())
+    }
+
+    async fn find_deleted_by_account(&self, account: &str) -> Result<Option<DeletedUser>, Error> {
+        self.inner.find_deleted_by_account(account).await
+    }
+
+    async fn restore(&self, id: &str) -> Result<(), Error> {
+        self.inner.restore(id).await?;
+        self.invalidate(id).await;
+        Ok(())
+    }
+
+    async fn purge_deleted(
+        &self,
+        before: i64,
+        limit: i64,
+        mode: PurgeMode,
+    ) -> Result<Vec<String>, Error> {
+        let ids = self.inner.purge_deleted(before, limit, mode).await?;
+        for id in &ids {
+            self.invalidate(id).await;
+        }
+        Ok(ids)
+    }
+}
+
+/// 缓存命中和回源的用户按请求的id顺序返回, 重复的id只返回一次
+fn in_request_order(ids: &[String], mut users: HashMap<String, User>) -> Vec<User> {
+    ids.iter().filter_map(|id| users.remove(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_cached_user() {
+        assert_eq!(CachedUserRepo::decode(NULL_VALUE).unwrap(), None);
+
+        // 缓存中需要保留密码, 供登录和修改密码校验
+        let user = User {
+            id: "id".to_string(),
+            password: "password".to_string(),
+            ..Default::default()
+        };
+        let decoded = CachedUserRepo::decode(&user.encode_to_vec()).unwrap();
+        assert_eq!(decoded, Some(user));
+    }
+
+    #[test]
+    fn test_in_request_order() {
+        let user = |id: &str| User {
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let users = HashMap::from([("b".to_string(), user("b")), ("a".to_string(), user("a"))]);
+        let ids = ["a", "c", "b", "a"].map(String::from);
+        let ids: Vec<String> = in_request_order(&ids, users)
+            .into_iter()
+            .map(|user| user.id)
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::logic::{
+    admin_list_users_logic, ban_user_logic, force_reset_password_logic, revoke_user_sessions_logic,
+    unban_user_logic,
+};
+use crate::pb::user::admin_user_service_server::AdminUserService;
+use crate::pb::user::{
+    AdminListUsersRequest, AdminListUsersResponse, BanUserRequest, BanUserResponse,
+    ForceResetPasswordRequest, ForceResetPasswordResponse, RevokeUserSessionsRequest,
+    RevokeUserSessionsResponse, UnbanUserRequest, UnbanUserResponse,
+};
+use crate::service_context::ServiceContext;
+use std::sync::Arc;
+use tonic::{async_trait, Request, Response, Status};
+
+/// 管理接口, 与UserService共用同一个ServiceContext和监听地址
+pub struct AdminUserRpcServer {
+    svc: Arc<ServiceContext>,
+}
+
+impl AdminUserRpcServer {
+    pub fn new(svc: Arc<ServiceContext>) -> Self {
+        Self { svc }
+    }
+}
+
+#[async_trait]
+impl AdminUserService for AdminUserRpcServer {
+    async fn list_users(
+        &self,
+        request: Request<AdminListUsersRequest>,
+    ) -> Result<Response<AdminListUsersResponse>, Status> {
+        admin_list_users_logic(&self.svc, request).await
+    }
+
+    async fn ban_user(
+        &self,
+        request: Request<BanUserRequest>,
+    ) -> Result<Response<BanUserResponse>, Status> {
+        ban_user_logic(&self.svc, request).await
+    }
+
+    async fn unban_user(
+        &self,
+        request: Request<UnbanUserRequest>,
+    ) -> Result<Response<UnbanUserResponse>, Status> {
+        unban_user_logic(&self.svc, request).await
+    }
+
+    async fn force_reset_password(
+        &self,
+        request: Request<ForceResetPasswordRequest>,
+    ) -> Result<Response<ForceResetPasswordResponse>, Status> {
+        force_reset_password_logic(&self.svc, request).await
+    }
+
+    async fn revoke_user_sessions(
+        &self,
+        request: Request<RevokeUserSessionsRequest>,
+    ) -> Result<Response<RevokeUserSessionsResponse>, Status> {
+        revoke_user_sessions_logic(&self.svc, request).await
+    }
+}
@@ -0,0 +1,351 @@
+//! 服务端拦截器链, 作为tower中间件在路由之前处理http请求
+
+use crate::error::Error;
+use crate::service_context::ServiceContext;
+use crate::utils::jwt;
+use http::header::HeaderValue;
+use http::{request, response};
+use nanoid::nanoid;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::async_trait;
+use tonic::metadata::MetadataMap;
+use tonic::Status;
+use tower::{Layer, Service};
+use tracing::info;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 不需要登录即可调用的方法, 登录前的注册/登录/找回密码流程
+const PUBLIC_METHODS: &[&str] = &[
+    "/user.UserService/Ping",
+    "/user.UserService/Register",
+    "/user.UserService/Login",
+    "/user.UserService/SendRegisterCode",
+    "/user.UserService/SendSmsCode",
+    "/user.UserService/PhoneRegister",
+    "/user.UserService/PhoneLogin",
+    "/user.UserService/VerifySecondFactor",
+    // 登录时不需要登录, 绑定到已登录用户时需要携带token
+    "/user.UserService/OidcAuthorize",
+    "/user.UserService/OidcLogin",
+    "/user.UserService/SendPasswordResetCode",
+    "/user.UserService/ResetPassword",
+    "/user.UserService/RestoreAccount",
+    // 凭下载凭证下载, 供user-api的下载链接使用
+    "/user.UserService/DownloadDataExport",
+];
+
+/// 需要登录的服务, 健康检查和服务反射不需要登录
+const PROTECTED_SERVICES: &[&str] = &["/user.UserService/", "/user.AdminUserService/"];
+
+fn is_protected(path: &str) -> bool {
+    PROTECTED_SERVICES
+        .iter()
+        .any(|service| path.starts_with(service))
+        && !PUBLIC_METHODS.contains(&path)
+}
+
+/// 一次调用的上下文, 在拦截器之间传递
+#[derive(Debug)]
+pub struct Call {
+    /// 例如`/user.UserService/Login`
+    pub path: String,
+    pub request_id: String,
+    /// 已登录的用户
+    pub user_id: Option<String>,
+    pub start: Instant,
+}
+
+#[async_trait]
+pub trait Interceptor: Send + Sync {
+    /// 调用服务前执行, 返回错误时不再执行后面的拦截器, 直接返回该错误
+    async fn before(&self, call: &mut Call, request: &mut request::Parts) -> Result<(), Status>;
+
+    /// 调用服务后按相反的顺序执行, 只有`before`成功的拦截器会执行
+    fn after(&self, _call: &Call, _response: &mut response::Parts) {}
+}
+
+/// 按顺序执行拦截器的中间件
+#[derive(Clone)]
+pub struct InterceptorLayer {
+    interceptors: Arc<Vec<Box<dyn Interceptor>>>,
+}
+
+impl InterceptorLayer {
+    pub fn new(interceptors: Vec<Box<dyn Interceptor>>) -> Self {
+        Self {
+            interceptors: Arc::new(interceptors),
+        }
+    }
+}
+
+impl<S> Layer<S> for InterceptorLayer {
+    type Service = InterceptorService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        InterceptorService {
+            inner,
+            interceptors: self.interceptors.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct InterceptorService<S> {
+    inner: S,
+    interceptors: Arc<Vec<Box<dyn Interceptor>>>,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for InterceptorService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    ReqBody: Send + 'static,
+    ResBody: Default + Send + 'static,
+{
+    type Response = http::Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // 使用已经就绪的服务, 克隆的服务留给下一次调用
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let interceptors = self.interceptors.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let mut call = Call {
+                path: parts.uri.path().to_string(),
+                request_id: String::new(),
+                user_id: None,
+                start: Instant::now(),
+            };
+
+            let mut passed = 0;
+            let mut rejected = None;
+            for interceptor in interceptors.iter() {
+                if let Err(status) = interceptor.before(&mut call, &mut parts).await {
+                    rejected = Some(status);
+                    break;
+                }
+                passed += 1;
+            }
+            let response = match rejected {
+                Some(status) => status.into_http(),
+                None => inner.call(http::Request::from_parts(parts, body)).await?,
+            };
+
+            let (mut parts, body) = response.into_parts();
+            for interceptor in interceptors[..passed].iter().rev() {
+                interceptor.after(&call, &mut parts);
+            }
+            Ok(http::Response::from_parts(parts, body))
+        })
+    }
+}
+
+/// 沿用客户端传入的`x-request-id`, 没有时生成一个, 并在响应中返回
+#[derive(Debug, Default)]
+pub struct RequestIdInterceptor;
+
+fn valid_request_id(value: &HeaderValue) -> Option<String> {
+    let value = value.to_str().ok()?;
+    let valid =
+        !value.is_empty() && value.len() <= 128 && value.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| value.to_string())
+}
+
+#[async_trait]
+impl Interceptor for RequestIdInterceptor {
+    async fn before(&self, call: &mut Call, request: &mut request::Parts) -> Result<(), Status> {
+        let request_id = request
+            .headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(valid_request_id)
+            .unwrap_or_else(|| nanoid!());
+        // 只包含可见字符, 总是可以转换为请求头
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            request.headers.insert(REQUEST_ID_HEADER, value);
+        }
+        call.request_id = request_id;
+        Ok(())
+    }
+
+    fn after(&self, call: &Call, response: &mut response::Parts) {
+        if let Ok(value) = HeaderValue::from_str(&call.request_id) {
+            response.headers.insert(REQUEST_ID_HEADER, value);
+        }
+    }
+}
+
+/// 校验需要登录的方法的bearer token, 通过后把`jwt::Claims`放入请求的extensions,
+/// 由logic通过`jwt::caller`读取调用方. 公开的方法携带有效token时同样放入claims
+pub struct AuthInterceptor {
+    svc: Arc<ServiceContext>,
+}
+
+impl AuthInterceptor {
+    pub fn new(svc: Arc<ServiceContext>) -> Self {
+        Self { svc }
+    }
+}
+
+#[async_trait]
+impl Interceptor for AuthInterceptor {
+    async fn before(&self, call: &mut Call, request: &mut request::Parts) -> Result<(), Status> {
+        let is_public = PUBLIC_METHODS.contains(&call.path.as_str());
+        if !is_public && !is_protected(&call.path) {
+            return Ok(());
+        }
+        let metadata = tonic::Request::from_parts(
+            MetadataMap::from_headers(request.headers.clone()),
+            Default::default(),
+            (),
+        );
+        let token = jwt::bearer_token(&metadata);
+        let claims = match token {
+            Some(token) => jwt::verify_token(&self.svc, token).await,
+            None => Err(Error::invalid_token("missing bearer token")),
+        };
+        let claims = match claims {
+            Ok(claims) => claims,
+            // 公开的方法携带的token无效时按未登录处理
+            Err(_) if is_public => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        call.user_id = Some(claims.user_id.clone());
+        request.extensions.insert(claims);
+        Ok(())
+    }
+}
+
+/// 记录每次调用的方法, 结果和耗时
+#[derive(Debug, Default)]
+pub struct AccessLogInterceptor;
+
+#[async_trait]
+impl Interceptor for AccessLogInterceptor {
+    async fn before(&self, _call: &mut Call, _request: &mut request::Parts) -> Result<(), Status> {
+        Ok(())
+    }
+
+    fn after(&self, call: &Call, response: &mut response::Parts) {
+        // 出错时grpc-status在响应头中, 成功时在trailers中
+        let grpc_status = response
+            .headers
+            .get("grpc-status")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("0");
+        info!(
+            request_id = %call.request_id,
+            user_id = call.user_id.as_deref().unwrap_or_default(),
+            "{} grpc-status: {} elapsed: {}ms",
+            call.path,
+            grpc_status,
+            call.start.elapsed().as_millis()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::tests::test_context;
+    use crate::pb::user::User;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_is_protected() {
+        assert!(!is_protected("/user.UserService/Login"));
+        assert!(!is_protected("/grpc.health.v1.Health/Check"));
+        assert!(is_protected("/user.UserService/FindUser"));
+        assert!(is_protected("/user.AdminUserService/BanUser"));
+    }
+
+    /// 经过拦截器链调用服务, 服务返回已登录用户的id
+    async fn call(svc: &Arc<ServiceContext>, request: http::Request<()>) -> http::Response<String> {
+        let inner = tower::service_fn(|request: http::Request<()>| async move {
+            let user_id = request
+                .extensions()
+                .get::<jwt::Claims>()
+                .map(|claims| claims.user_id.clone())
+                .unwrap_or_default();
+            Ok::<_, Infallible>(http::Response::new(user_id))
+        });
+        InterceptorLayer::new(vec![
+            Box::new(RequestIdInterceptor),
+            Box::new(AccessLogInterceptor),
+            Box::new(AuthInterceptor::new(svc.clone())),
+        ])
+        .layer(inner)
+        .oneshot(request)
+        .await
+        .unwrap()
+    }
+
+    fn request(path: &str, token: Option<&str>) -> http::Request<()> {
+        let mut builder = http::Request::builder().uri(path);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_interceptors() {
+        let svc = Arc::new(test_context());
+        let user = User {
+            id: "lucas".to_string(),
+            ..Default::default()
+        };
+        let token = jwt::gen_token(&user, &svc.config.jwt.secret).unwrap().token;
+
+        // 公开的方法不需要token, 生成request id
+        let response = call(&svc, request("/user.UserService/Login", None)).await;
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+
+        // 公开的方法携带有效token时同样可以读取到claims, 无效时按未登录处理
+        let response = call(
+            &svc,
+            request("/user.UserService/OidcAuthorize", Some(&token)),
+        )
+        .await;
+        assert_eq!(response.body(), "lucas");
+        let response = call(
+            &svc,
+            request("/user.UserService/OidcAuthorize", Some("invalid")),
+        )
+        .await;
+        assert!(!response.headers().contains_key("grpc-status"));
+        assert_eq!(response.body(), "");
+
+        let response = call(&svc, request("/user.UserService/FindUser", None)).await;
+        assert_eq!(response.headers()["grpc-status"], "16");
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+
+        let response = call(&svc, request("/user.UserService/FindUser", Some("invalid"))).await;
+        assert_eq!(response.headers()["grpc-status"], "16");
+
+        // 校验通过后服务可以读取到claims, 沿用客户端的request id
+        let mut request = request("/user.UserService/FindUser", Some(&token));
+        request
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER, HeaderValue::from_static("req-1"));
+        let response = call(&svc, request).await;
+        assert!(!response.headers().contains_key("grpc-status"));
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-1");
+        assert_eq!(response.body(), "lucas");
+    }
+}
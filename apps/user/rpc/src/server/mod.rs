@@ -1,25 +1,68 @@
 use crate::config::Config;
+use crate::job::data_export_job::data_export_job;
+use crate::job::health_check_job::health_check_job;
+use crate::job::presence_expire_job::presence_expire_job;
+use crate::job::purge_account_job::purge_account_job;
 use crate::logic::{
-    find_user_logic, get_user_info_logic, get_user_online_count_logic, login_logic, ping_logic,
-    register_logic, send_register_code_logic,
+    batch_get_users_logic, block_user_logic, can_send_friend_request_logic, change_password_logic,
+    confirm_totp_logic, deactivate_account_logic, device_offline_logic, disable_totp_logic,
+    download_data_export_logic, enroll_totp_logic, find_user_logic, get_data_export_logic,
+    get_privacy_settings_logic, get_user_info_logic, get_user_online_count_logic,
+    get_users_presence_logic, heartbeat_logic, is_blocked_logic, link_external_identity_logic,
+    list_blocked_users_logic, list_external_identities_logic, list_security_events_logic,
+    login_logic, oidc_authorize_logic, oidc_login_logic, phone_login_logic, phone_register_logic,
+    ping_logic, register_logic, request_data_export_logic, reset_password_logic,
+    restore_account_logic, search_users_logic, send_password_reset_code_logic,
+    send_register_code_logic, send_sms_code_logic, set_presence_status_logic, unblock_user_logic,
+    unlink_external_identity_logic, update_privacy_settings_logic, update_user_profile_logic,
+    verify_second_factor_logic, watch_users_logic,
 };
 use crate::pb;
+use crate::pb::reflection::server_reflection_server::ServerReflectionServer;
+use crate::pb::user::admin_user_service_server::AdminUserServiceServer;
 use crate::pb::user::user_service_server::{UserService, UserServiceServer};
 use crate::pb::user::{
-    FindUserRequest, FindUserResponse, GetUserInfoRequest, GetUserInfoResponse, LoginRequest,
-    LoginResponse, RegisterRequest, RegisterResponse, SendRegisterCodeRequest,
-    SendRegisterCodeResponse, UserOnlineCountRequest, UserOnlineCountResponse,
+    BatchGetUsersRequest, BatchGetUsersResponse, BlockUserRequest, BlockUserResponse,
+    CanSendFriendRequestRequest, CanSendFriendRequestResponse, ChangePasswordRequest,
+    ChangePasswordResponse, ConfirmTotpRequest, ConfirmTotpResponse, DataExportChunk,
+    DeactivateAccountRequest, DeactivateAccountResponse, DeviceOfflineRequest,
+    DeviceOfflineResponse, DisableTotpRequest, DisableTotpResponse, DownloadDataExportRequest,
+    EnrollTotpRequest, EnrollTotpResponse, FindUserRequest, FindUserResponse, GetDataExportRequest,
+    GetDataExportResponse, GetPrivacySettingsRequest, GetPrivacySettingsResponse,
+    GetUserInfoRequest, GetUserInfoResponse, GetUsersPresenceRequest, GetUsersPresenceResponse,
+    HeartbeatRequest, HeartbeatResponse, IsBlockedRequest, IsBlockedResponse,
+    LinkExternalIdentityRequest, LinkExternalIdentityResponse, ListBlockedUsersRequest,
+    ListBlockedUsersResponse, ListExternalIdentitiesRequest, ListExternalIdentitiesResponse,
+    ListSecurityEventsRequest, ListSecurityEventsResponse, LoginRequest, LoginResponse,
+    OidcAuthorizeRequest, OidcAuthorizeResponse, OidcLoginRequest, PhoneLoginRequest,
+    PhoneRegisterRequest, PhoneRegisterResponse, RegisterRequest, RegisterResponse,
+    RequestDataExportRequest, RequestDataExportResponse, ResetPasswordRequest,
+    ResetPasswordResponse, RestoreAccountRequest, RestoreAccountResponse, SearchUsersRequest,
+    SearchUsersResponse, SendPasswordResetCodeRequest, SendPasswordResetCodeResponse,
+    SendRegisterCodeRequest, SendRegisterCodeResponse, SendSmsCodeRequest, SendSmsCodeResponse,
+    SetPresenceStatusRequest, SetPresenceStatusResponse, UnblockUserRequest, UnblockUserResponse,
+    UnlinkExternalIdentityRequest, UnlinkExternalIdentityResponse, UpdatePrivacySettingsRequest,
+    UpdatePrivacySettingsResponse, UpdateUserProfileRequest, UpdateUserProfileResponse, UserEvent,
+    UserOnlineCountRequest, UserOnlineCountResponse, VerifySecondFactorRequest, WatchUsersRequest,
 };
 use crate::service_context::ServiceContext;
 use common::service_register::etcd::EtcdServiceRegister;
 use common::service_register::{ServiceInstance, ServiceRegister};
-use nanoid::nanoid;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::Server;
 use tonic::{async_trait, Request, Response, Status};
 use tracing::info;
 
+pub(crate) mod admin;
+mod interceptor;
+mod reflection;
+use admin::AdminUserRpcServer;
+use interceptor::{AccessLogInterceptor, AuthInterceptor, InterceptorLayer, RequestIdInterceptor};
+use reflection::ReflectionRpcServer;
+
 pub struct UserRpcServer {
-    svc: ServiceContext,
+    svc: Arc<ServiceContext>,
     service_register: EtcdServiceRegister,
 }
 
@@ -29,18 +72,19 @@ impl UserRpcServer {
             .await
             .expect("EtcdServiceRegister success");
         Self {
-            svc: ServiceContext::new(config.clone()).await,
+            svc: Arc::new(ServiceContext::new(config.clone()).await),
             service_register,
         }
     }
 
     pub async fn start(config: Config) -> anyhow::Result<()> {
+        config.validate()?;
         let mut user_service_rpc = UserRpcServer::new(config.clone()).await;
         // 注册服务发现
         user_service_rpc
             .service_register
             .register(ServiceInstance {
-                id: nanoid!(),
+                id: user_service_rpc.svc.id_generator.next_string()?,
                 name: config.etcd.key.clone(),
                 endpoints: vec![config.listen_on.clone()],
                 version: "0.1".to_string(),
@@ -49,11 +93,39 @@ impl UserRpcServer {
             .await
             .expect("register success");
 
+        let svc = user_service_rpc.svc.clone();
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        // 后台任务
+        tokio::spawn(purge_account_job(svc.clone()));
+        tokio::spawn(presence_expire_job(svc.clone()));
+        tokio::spawn(data_export_job(svc.clone()));
+        tokio::spawn(health_check_job(svc.clone(), health_reporter));
+
+        // 拦截器按顺序执行, 访问日志在鉴权之前, 被拒绝的调用也会记录
+        let interceptors = InterceptorLayer::new(vec![
+            Box::new(RequestIdInterceptor),
+            Box::new(AccessLogInterceptor),
+            Box::new(AuthInterceptor::new(svc.clone())),
+        ]);
+        let reflection_service = config.server.reflection.then(|| {
+            ServerReflectionServer::new(
+                ReflectionRpcServer::new(&[
+                    pb::FILE_DESCRIPTOR_SET,
+                    tonic_health::pb::FILE_DESCRIPTOR_SET,
+                ])
+                .expect("decode file descriptor set success"),
+            )
+        });
+        let admin_service = AdminUserServiceServer::new(AdminUserRpcServer::new(svc));
         let service = UserServiceServer::new(user_service_rpc);
         info!("listen on: {}", config.listen_on.clone());
 
         Server::builder()
+            .layer(interceptors)
+            .add_service(health_service)
+            .add_optional_service(reflection_service)
             .add_service(service)
+            .add_service(admin_service)
             .serve(
                 config
                     .listen_on
@@ -116,4 +188,260 @@ impl UserService for UserRpcServer {
     ) -> Result<Response<FindUserResponse>, Status> {
         find_user_logic(&self.svc, request).await
     }
+
+    async fn batch_get_users(
+        &self,
+        request: Request<BatchGetUsersRequest>,
+    ) -> Result<Response<BatchGetUsersResponse>, Status> {
+        batch_get_users_logic(&self.svc, request).await
+    }
+
+    async fn get_privacy_settings(
+        &self,
+        request: Request<GetPrivacySettingsRequest>,
+    ) -> Result<Response<GetPrivacySettingsResponse>, Status> {
+        get_privacy_settings_logic(&self.svc, request).await
+    }
+
+    async fn update_privacy_settings(
+        &self,
+        request: Request<UpdatePrivacySettingsRequest>,
+    ) -> Result<Response<UpdatePrivacySettingsResponse>, Status> {
+        update_privacy_settings_logic(&self.svc, request).await
+    }
+
+    async fn can_send_friend_request(
+        &self,
+        request: Request<CanSendFriendRequestRequest>,
+    ) -> Result<Response<CanSendFriendRequestResponse>, Status> {
+        can_send_friend_request_logic(&self.svc, request).await
+    }
+
+    async fn block_user(
+        &self,
+        request: Request<BlockUserRequest>,
+    ) -> Result<Response<BlockUserResponse>, Status> {
+        block_user_logic(&self.svc, request).await
+    }
+
+    async fn unblock_user(
+        &self,
+        request: Request<UnblockUserRequest>,
+    ) -> Result<Response<UnblockUserResponse>, Status> {
+        unblock_user_logic(&self.svc, request).await
+    }
+
+    async fn list_blocked_users(
+        &self,
+        request: Request<ListBlockedUsersRequest>,
+    ) -> Result<Response<ListBlockedUsersResponse>, Status> {
+        list_blocked_users_logic(&self.svc, request).await
+    }
+
+    async fn is_blocked(
+        &self,
+        request: Request<IsBlockedRequest>,
+    ) -> Result<Response<IsBlockedResponse>, Status> {
+        is_blocked_logic(&self.svc, request).await
+    }
+
+    async fn send_sms_code(
+        &self,
+        request: Request<SendSmsCodeRequest>,
+    ) -> Result<Response<SendSmsCodeResponse>, Status> {
+        send_sms_code_logic(&self.svc, request).await
+    }
+
+    async fn phone_register(
+        &self,
+        request: Request<PhoneRegisterRequest>,
+    ) -> Result<Response<PhoneRegisterResponse>, Status> {
+        phone_register_logic(&self.svc, request).await
+    }
+
+    async fn phone_login(
+        &self,
+        request: Request<PhoneLoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        phone_login_logic(&self.svc, request).await
+    }
+
+    async fn enroll_totp(
+        &self,
+        request: Request<EnrollTotpRequest>,
+    ) -> Result<Response<EnrollTotpResponse>, Status> {
+        enroll_totp_logic(&self.svc, request).await
+    }
+
+    async fn confirm_totp(
+        &self,
+        request: Request<ConfirmTotpRequest>,
+    ) -> Result<Response<ConfirmTotpResponse>, Status> {
+        confirm_totp_logic(&self.svc, request).await
+    }
+
+    async fn disable_totp(
+        &self,
+        request: Request<DisableTotpRequest>,
+    ) -> Result<Response<DisableTotpResponse>, Status> {
+        disable_totp_logic(&self.svc, request).await
+    }
+
+    async fn verify_second_factor(
+        &self,
+        request: Request<VerifySecondFactorRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        verify_second_factor_logic(&self.svc, request).await
+    }
+
+    async fn oidc_authorize(
+        &self,
+        request: Request<OidcAuthorizeRequest>,
+    ) -> Result<Response<OidcAuthorizeResponse>, Status> {
+        oidc_authorize_logic(&self.svc, request).await
+    }
+
+    async fn oidc_login(
+        &self,
+        request: Request<OidcLoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        oidc_login_logic(&self.svc, request).await
+    }
+
+    async fn link_external_identity(
+        &self,
+        request: Request<LinkExternalIdentityRequest>,
+    ) -> Result<Response<LinkExternalIdentityResponse>, Status> {
+        link_external_identity_logic(&self.svc, request).await
+    }
+
+    async fn unlink_external_identity(
+        &self,
+        request: Request<UnlinkExternalIdentityRequest>,
+    ) -> Result<Response<UnlinkExternalIdentityResponse>, Status> {
+        unlink_external_identity_logic(&self.svc, request).await
+    }
+
+    async fn list_external_identities(
+        &self,
+        request: Request<ListExternalIdentitiesRequest>,
+    ) -> Result<Response<ListExternalIdentitiesResponse>, Status> {
+        list_external_identities_logic(&self.svc, request).await
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        heartbeat_logic(&self.svc, request).await
+    }
+
+    async fn device_offline(
+        &self,
+        request: Request<DeviceOfflineRequest>,
+    ) -> Result<Response<DeviceOfflineResponse>, Status> {
+        device_offline_logic(&self.svc, request).await
+    }
+
+    async fn set_presence_status(
+        &self,
+        request: Request<SetPresenceStatusRequest>,
+    ) -> Result<Response<SetPresenceStatusResponse>, Status> {
+        set_presence_status_logic(&self.svc, request).await
+    }
+
+    async fn get_users_presence(
+        &self,
+        request: Request<GetUsersPresenceRequest>,
+    ) -> Result<Response<GetUsersPresenceResponse>, Status> {
+        get_users_presence_logic(&self.svc, request).await
+    }
+
+    type WatchUsersStream = ReceiverStream<Result<UserEvent, Status>>;
+
+    async fn watch_users(
+        &self,
+        request: Request<WatchUsersRequest>,
+    ) -> Result<Response<Self::WatchUsersStream>, Status> {
+        watch_users_logic(self.svc.clone(), request).await
+    }
+
+    async fn list_security_events(
+        &self,
+        request: Request<ListSecurityEventsRequest>,
+    ) -> Result<Response<ListSecurityEventsResponse>, Status> {
+        list_security_events_logic(&self.svc, request).await
+    }
+
+    async fn search_users(
+        &self,
+        request: Request<SearchUsersRequest>,
+    ) -> Result<Response<SearchUsersResponse>, Status> {
+        search_users_logic(&self.svc, request).await
+    }
+
+    async fn send_password_reset_code(
+        &self,
+        request: Request<SendPasswordResetCodeRequest>,
+    ) -> Result<Response<SendPasswordResetCodeResponse>, Status> {
+        send_password_reset_code_logic(&self.svc, request).await
+    }
+
+    async fn reset_password(
+        &self,
+        request: Request<ResetPasswordRequest>,
+    ) -> Result<Response<ResetPasswordResponse>, Status> {
+        reset_password_logic(&self.svc, request).await
+    }
+
+    async fn update_user_profile(
+        &self,
+        request: Request<UpdateUserProfileRequest>,
+    ) -> Result<Response<UpdateUserProfileResponse>, Status> {
+        update_user_profile_logic(&self.svc, request).await
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<ChangePasswordResponse>, Status> {
+        change_password_logic(&self.svc, request).await
+    }
+
+    async fn deactivate_account(
+        &self,
+        request: Request<DeactivateAccountRequest>,
+    ) -> Result<Response<DeactivateAccountResponse>, Status> {
+        deactivate_account_logic(&self.svc, request).await
+    }
+
+    async fn restore_account(
+        &self,
+        request: Request<RestoreAccountRequest>,
+    ) -> Result<Response<RestoreAccountResponse>, Status> {
+        restore_account_logic(&self.svc, request).await
+    }
+
+    async fn request_data_export(
+        &self,
+        request: Request<RequestDataExportRequest>,
+    ) -> Result<Response<RequestDataExportResponse>, Status> {
+        request_data_export_logic(&self.svc, request).await
+    }
+
+    async fn get_data_export(
+        &self,
+        request: Request<GetDataExportRequest>,
+    ) -> Result<Response<GetDataExportResponse>, Status> {
+        get_data_export_logic(&self.svc, request).await
+    }
+
+    type DownloadDataExportStream = ReceiverStream<Result<DataExportChunk, Status>>;
+
+    async fn download_data_export(
+        &self,
+        request: Request<DownloadDataExportRequest>,
+    ) -> Result<Response<Self::DownloadDataExportStream>, Status> {
+        download_data_export_logic(&self.svc, request).await
+    }
 }
@@ -0,0 +1,282 @@
+use crate::pb::reflection::server_reflection_request::MessageRequest;
+use crate::pb::reflection::server_reflection_response::MessageResponse;
+use crate::pb::reflection::server_reflection_server::ServerReflection;
+use crate::pb::reflection::{
+    ErrorResponse, ExtensionNumberResponse, FileDescriptorResponse, ListServiceResponse,
+    ServerReflectionRequest, ServerReflectionResponse, ServiceResponse,
+};
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Code, Request, Response, Status, Streaming};
+
+fn not_found(message: String) -> ErrorResponse {
+    ErrorResponse {
+        error_code: Code::NotFound as i32,
+        error_message: message,
+    }
+}
+
+/// 描述文件的索引
+#[derive(Debug, Default)]
+struct Descriptors {
+    /// 文件名 -> 文件描述
+    files: HashMap<String, FileDescriptorProto>,
+    /// 完整的符号名(消息/枚举/服务/方法) -> 文件名
+    symbols: HashMap<String, String>,
+    services: Vec<String>,
+}
+
+impl Descriptors {
+    fn new(sets: &[&[u8]]) -> anyhow::Result<Self> {
+        let mut descriptors = Descriptors::default();
+        for set in sets {
+            for file in FileDescriptorSet::decode(*set)?.file {
+                descriptors.add_file(file);
+            }
+        }
+        descriptors.services.sort();
+        Ok(descriptors)
+    }
+
+    fn add_file(&mut self, file: FileDescriptorProto) {
+        let name = file.name().to_string();
+        if self.files.contains_key(&name) {
+            return;
+        }
+        let package = file.package();
+        let qualify = |symbol: &str| {
+            if package.is_empty() {
+                symbol.to_string()
+            } else {
+                format!("{}.{}", package, symbol)
+            }
+        };
+
+        for message in &file.message_type {
+            self.add_message(&qualify(message.name()), message, &name);
+        }
+        for enum_type in &file.enum_type {
+            self.symbols.insert(qualify(enum_type.name()), name.clone());
+        }
+        for service in &file.service {
+            let service_name = qualify(service.name());
+            for method in &service.method {
+                self.symbols
+                    .insert(format!("{}.{}", service_name, method.name()), name.clone());
+            }
+            self.symbols.insert(service_name.clone(), name.clone());
+            self.services.push(service_name);
+        }
+        self.files.insert(name, file);
+    }
+
+    fn add_message(&mut self, full_name: &str, message: &DescriptorProto, file: &str) {
+        self.symbols.insert(full_name.to_string(), file.to_string());
+        for nested in &message.nested_type {
+            self.add_message(&format!("{}.{}", full_name, nested.name()), nested, file);
+        }
+        for enum_type in &message.enum_type {
+            self.symbols.insert(
+                format!("{}.{}", full_name, enum_type.name()),
+                file.to_string(),
+            );
+        }
+    }
+
+    /// 序列化文件及其依赖, 依赖在前
+    fn encode_with_dependencies(&self, name: &str) -> Result<Vec<Vec<u8>>, ErrorResponse> {
+        let mut encoded = Vec::new();
+        let mut visited = HashSet::new();
+        self.visit(name, &mut visited, &mut encoded)?;
+        Ok(encoded)
+    }
+
+    fn visit(
+        &self,
+        name: &str,
+        visited: &mut HashSet<String>,
+        encoded: &mut Vec<Vec<u8>>,
+    ) -> Result<(), ErrorResponse> {
+        if !visited.insert(name.to_string()) {
+            return Ok(());
+        }
+        let file = self
+            .files
+            .get(name)
+            .ok_or_else(|| not_found(format!("file `{}` not found", name)))?;
+        for dependency in &file.dependency {
+            self.visit(dependency, visited, encoded)?;
+        }
+        encoded.push(file.encode_to_vec());
+        Ok(())
+    }
+
+    fn handle(&self, request: &MessageRequest) -> Result<MessageResponse, ErrorResponse> {
+        let response = match request {
+            MessageRequest::FileByFilename(name) => {
+                MessageResponse::FileDescriptorResponse(FileDescriptorResponse {
+                    file_descriptor_proto: self.encode_with_dependencies(name)?,
+                })
+            }
+            MessageRequest::FileContainingSymbol(symbol) => {
+                let file = self
+                    .symbols
+                    .get(symbol)
+                    .ok_or_else(|| not_found(format!("symbol `{}` not found", symbol)))?;
+                MessageResponse::FileDescriptorResponse(FileDescriptorResponse {
+                    file_descriptor_proto: self.encode_with_dependencies(file)?,
+                })
+            }
+            // proto3不使用扩展
+            MessageRequest::FileContainingExtension(_) => {
+                return Err(not_found("extensions are not supported".to_string()))
+            }
+            MessageRequest::AllExtensionNumbersOfType(name) => {
+                if !self.symbols.contains_key(name) {
+                    return Err(not_found(format!("type `{}` not found", name)));
+                }
+                MessageResponse::AllExtensionNumbersResponse(ExtensionNumberResponse {
+                    base_type_name: name.clone(),
+                    extension_number: Vec::new(),
+                })
+            }
+            MessageRequest::ListServices(_) => {
+                MessageResponse::ListServicesResponse(ListServiceResponse {
+                    service: self
+                        .services
+                        .iter()
+                        .map(|name| ServiceResponse { name: name.clone() })
+                        .collect(),
+                })
+            }
+        };
+        Ok(response)
+    }
+}
+
+/// grpc服务反射, 供grpcurl等工具查询服务和消息的定义
+#[derive(Debug, Clone)]
+pub struct ReflectionRpcServer {
+    descriptors: Arc<Descriptors>,
+}
+
+impl ReflectionRpcServer {
+    /// `sets`为编码后的FileDescriptorSet
+    pub fn new(sets: &[&[u8]]) -> anyhow::Result<Self> {
+        Ok(Self {
+            descriptors: Arc::new(Descriptors::new(sets)?),
+        })
+    }
+
+    fn respond(&self, request: ServerReflectionRequest) -> ServerReflectionResponse {
+        let message_response = match &request.message_request {
+            Some(message_request) => self.descriptors.handle(message_request),
+            None => Err(ErrorResponse {
+                error_code: Code::InvalidArgument as i32,
+                error_message: "message_request is empty".to_string(),
+            }),
+        }
+        .unwrap_or_else(MessageResponse::ErrorResponse);
+        ServerReflectionResponse {
+            valid_host: request.host.clone(),
+            original_request: Some(request),
+            message_response: Some(message_response),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ServerReflection for ReflectionRpcServer {
+    type ServerReflectionInfoStream =
+        Pin<Box<dyn Stream<Item = Result<ServerReflectionResponse, Status>> + Send>>;
+
+    async fn server_reflection_info(
+        &self,
+        request: Request<Streaming<ServerReflectionRequest>>,
+    ) -> Result<Response<Self::ServerReflectionInfoStream>, Status> {
+        let server = self.clone();
+        let mut inbound = request.into_inner();
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tokio::spawn(async move {
+            while let Some(request) = inbound.next().await {
+                let response = request.map(|request| server.respond(request));
+                // 客户端断开连接
+                if tx.send(response).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> ReflectionRpcServer {
+        ReflectionRpcServer::new(&[
+            crate::pb::FILE_DESCRIPTOR_SET,
+            tonic_health::pb::FILE_DESCRIPTOR_SET,
+        ])
+        .unwrap()
+    }
+
+    fn respond(request: MessageRequest) -> MessageResponse {
+        server()
+            .respond(ServerReflectionRequest {
+                host: String::new(),
+                message_request: Some(request),
+            })
+            .message_response
+            .unwrap()
+    }
+
+    #[test]
+    fn test_list_services() {
+        let MessageResponse::ListServicesResponse(response) =
+            respond(MessageRequest::ListServices(String::new()))
+        else {
+            panic!("unexpected response");
+        };
+        let services: Vec<&str> = response.service.iter().map(|s| s.name.as_str()).collect();
+        assert!(services.contains(&"user.UserService"));
+        assert!(services.contains(&"user.AdminUserService"));
+        assert!(services.contains(&"grpc.health.v1.Health"));
+    }
+
+    #[test]
+    fn test_file_containing_symbol() {
+        let symbols = &server().descriptors.symbols;
+        assert_eq!(symbols["user.UserService.Login"], "user.proto");
+        assert_eq!(symbols["user.User"], "user.proto");
+
+        let MessageResponse::FileDescriptorResponse(response) = respond(
+            MessageRequest::FileContainingSymbol("user.User".to_string()),
+        ) else {
+            panic!("unexpected response");
+        };
+        let files: Vec<FileDescriptorProto> = response
+            .file_descriptor_proto
+            .iter()
+            .map(|bytes| FileDescriptorProto::decode(bytes.as_slice()).unwrap())
+            .collect();
+        // 依赖的文件在前
+        assert_eq!(files.last().unwrap().name(), "user.proto");
+        assert!(files
+            .iter()
+            .any(|file| file.name() == "google/protobuf/field_mask.proto"));
+
+        let MessageResponse::ErrorResponse(error) = respond(MessageRequest::FileContainingSymbol(
+            "user.Unknown".to_string(),
+        )) else {
+            panic!("unexpected response");
+        };
+        assert_eq!(error.error_code, Code::NotFound as i32);
+    }
+}
@@ -1,25 +1,178 @@
-use crate::config::Config;
+use crate::config::{Config, UserStoreKind};
+use crate::event::redis::RedisEventBus;
+use crate::event::UserEventBus;
+use crate::export::ExportSource;
+use crate::friend::{FriendChecker, NoFriendChecker};
+use crate::health::{HealthCheck, PostgresHealthCheck, RedisHealthCheck};
+use crate::mail;
+use crate::mail::MailSender;
+use crate::migration;
+use crate::oidc::IdentityProviders;
+use crate::repo::mongodb::user::UserMongoDb;
+use crate::repo::postgres::block::BlockPostgres;
+use crate::repo::postgres::data_export::DataExportPostgres;
+use crate::repo::postgres::identity::IdentityPostgres;
+use crate::repo::postgres::privacy::PrivacyPostgres;
+use crate::repo::postgres::security_event::SecurityEventPostgres;
+use crate::repo::postgres::totp::TotpPostgres;
 use crate::repo::postgres::user::UserPostgres;
+use crate::repo::redis::block::CachedBlockRepo;
+use crate::repo::redis::user::CachedUserRepo;
 use crate::repo::redis::RedisCache;
-use crate::repo::{Cache, UserRepo};
+use crate::repo::{
+    BlockRepo, Cache, DataExportRepo, IdentityRepo, PrivacyRepo, SecurityEventRepo, TotpRepo,
+    UserRepo,
+};
+use crate::sms;
+use crate::sms::SmsSender;
+use common::id::etcd::acquire_worker_id_from_config;
+use common::id::{IdGenerator, WorkerId};
+use sqlx::PgPool;
+use tracing::warn;
 
+/// 服务依赖, 字段均为trait对象, 测试时可以替换为其他实现
 pub struct ServiceContext {
     pub config: Config,
     pub user_repo: Box<dyn UserRepo>,
+    pub totp_repo: Box<dyn TotpRepo>,
+    pub identity_repo: Box<dyn IdentityRepo>,
+    pub security_event_repo: Box<dyn SecurityEventRepo>,
+    pub privacy_repo: Box<dyn PrivacyRepo>,
+    pub block_repo: Box<dyn BlockRepo>,
+    pub data_export_repo: Box<dyn DataExportRepo>,
     pub cache: Box<dyn Cache>,
+    pub sms_sender: Box<dyn SmsSender>,
+    pub mail_sender: Box<dyn MailSender>,
+    pub identity_providers: IdentityProviders,
+    pub friend_checker: Box<dyn FriendChecker>,
+    /// 生成用户等实体的id, worker id由etcd分配
+    pub id_generator: IdGenerator,
+    /// 用户资料和在线状态的变化通知
+    pub user_events: Box<dyn UserEventBus>,
+    /// 依赖服务的可用性检查, 结果用于健康检查服务
+    pub health_checks: Vec<Box<dyn HealthCheck>>,
+    /// 个人数据导出时收集的其他业务数据, 消息和联系人等服务接入时添加
+    pub export_sources: Vec<Box<dyn ExportSource>>,
 }
 
 impl ServiceContext {
     pub async fn new(config: Config) -> ServiceContext {
-        let user_repo = Box::new(UserPostgres::from_config(&config).await);
+        let pool = PgPool::connect(&config.postgres.url())
+            .await
+            .expect("connect to postgres success");
+        migration::prepare(&pool, config.migration.auto_migrate)
+            .await
+            .expect("database schema is compatible");
+        let mut user_repo: Box<dyn UserRepo> = match config.user_store {
+            UserStoreKind::Postgres => Box::new(UserPostgres::new(pool.clone())),
+            UserStoreKind::Mongodb => Box::new(UserMongoDb::from_config(&config).await),
+        };
+        let mut block_repo: Box<dyn BlockRepo> = Box::new(BlockPostgres::new(pool.clone()));
+        if config.user_cache.enabled {
+            let client =
+                redis::Client::open(config.redis.url()).expect("open redis client success");
+            user_repo = Box::new(CachedUserRepo::new(
+                user_repo,
+                client.clone(),
+                config.user_cache.clone(),
+            ));
+            block_repo = Box::new(CachedBlockRepo::new(
+                block_repo,
+                client,
+                config.user_cache.clone(),
+            ));
+        }
+        let health_checks: Vec<Box<dyn HealthCheck>> = vec![
+            Box::new(PostgresHealthCheck::new(pool.clone())),
+            Box::new(RedisHealthCheck::new(
+                redis::Client::open(config.redis.url()).expect("open redis client success"),
+            )),
+        ];
+        let totp_repo = Box::new(TotpPostgres::new(pool.clone()));
+        let identity_repo = Box::new(IdentityPostgres::new(pool.clone()));
+        let privacy_repo = Box::new(PrivacyPostgres::new(pool.clone()));
+        let data_export_repo = Box::new(DataExportPostgres::new(pool.clone()));
+        let security_event_repo = Box::new(SecurityEventPostgres::new(pool));
         let cache = Box::new(RedisCache::from_config(&config));
+        let worker_id = match acquire_worker_id_from_config(&config.etcd, &config.etcd.key).await {
+            Ok(worker_id) => worker_id,
+            Err(e) => match config.id.fallback_worker_id {
+                Some(id) => {
+                    warn!(
+                        "acquire worker id failed: {:?}, use fallback worker id {}",
+                        e, id
+                    );
+                    WorkerId::fixed(id)
+                }
+                None => panic!("acquire worker id failed: {:?}", e),
+            },
+        };
+        let id_generator = IdGenerator::new(worker_id).expect("worker id is valid");
+        let user_events = Box::new(RedisEventBus::new(
+            redis::Client::open(config.redis.url()).expect("open redis client success"),
+        ));
+        let sms_sender = sms::from_config(&config.sms);
+        let mail_sender = mail::from_config(&config.mail);
+        let identity_providers = IdentityProviders::from_config(&config.oidc);
 
-        let ctx = ServiceContext {
+        ServiceContext {
             config,
             user_repo,
+            totp_repo,
+            identity_repo,
+            security_event_repo,
+            privacy_repo,
+            block_repo,
+            data_export_repo,
             cache,
-        };
+            sms_sender,
+            mail_sender,
+            identity_providers,
+            friend_checker: Box::new(NoFriendChecker),
+            id_generator,
+            user_events,
+            health_checks,
+            export_sources: Vec::new(),
+        }
+    }
+
+    /// 使用内存实现构造, 不依赖外部服务. 需要替换某个依赖时使用结构体更新语法:
+    /// `ServiceContext { sms_sender, ..ServiceContext::in_memory(config) }`
+    #[cfg(test)]
+    pub fn in_memory(config: Config) -> ServiceContext {
+        use crate::event::LocalEventBus;
+        use crate::mail::mock::MockMailSender;
+        use crate::repo::memory::block::MemoryBlockRepo;
+        use crate::repo::memory::data_export::MemoryDataExportRepo;
+        use crate::repo::memory::identity::MemoryIdentityRepo;
+        use crate::repo::memory::privacy::MemoryPrivacyRepo;
+        use crate::repo::memory::security_event::MemorySecurityEventRepo;
+        use crate::repo::memory::totp::MemoryTotpRepo;
+        use crate::repo::memory::user::MemoryUserRepo;
+        use crate::repo::memory::MemoryCache;
+        use crate::sms::mock::MockSmsSender;
 
-        ctx
+        // 在线状态的变化由MemoryCache发送, 与RedisCache发布到频道一致
+        let cache = MemoryCache::new();
+        let user_events = LocalEventBus::new(cache.events());
+        ServiceContext {
+            config,
+            user_repo: Box::new(MemoryUserRepo::new()),
+            totp_repo: Box::new(MemoryTotpRepo::new()),
+            identity_repo: Box::new(MemoryIdentityRepo::new()),
+            security_event_repo: Box::new(MemorySecurityEventRepo::new()),
+            privacy_repo: Box::new(MemoryPrivacyRepo::new()),
+            block_repo: Box::new(MemoryBlockRepo::new()),
+            data_export_repo: Box::new(MemoryDataExportRepo::new()),
+            cache: Box::new(cache),
+            sms_sender: Box::new(MockSmsSender::default()),
+            mail_sender: Box::new(MockMailSender::default()),
+            identity_providers: IdentityProviders::default(),
+            friend_checker: Box::new(NoFriendChecker),
+            id_generator: IdGenerator::new(WorkerId::fixed(0)).unwrap(),
+            user_events: Box::new(user_events),
+            health_checks: Vec::new(),
+            export_sources: Vec::new(),
+        }
     }
 }
@@ -0,0 +1,58 @@
+use crate::error::Error;
+use crate::sms::SmsSender;
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+/// 把短信追加写入文件, 每行为`{时间戳} {手机号} {验证码}`, 用于联调
+#[derive(Debug)]
+pub struct FileSmsSender {
+    path: String,
+}
+
+impl FileSmsSender {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SmsSender for FileSmsSender {
+    async fn send_code(&self, phone: &str, code: &str) -> Result<(), Error> {
+        let line = format!(
+            "{} {} {}\n",
+            chrono::Utc::now().timestamp_millis(),
+            phone,
+            code
+        );
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| Error::internal_with_details(format!("open sms file, {}", e)))?;
+        // tokio的File在drop时不会等待写入完成, 需要显式flush
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| Error::internal_with_details(format!("write sms file, {}", e)))?;
+        file.flush()
+            .await
+            .map_err(|e| Error::internal_with_details(format!("flush sms file, {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sms_sender() {
+        let path = std::env::temp_dir().join(format!("sms-{}.log", nanoid::nanoid!()));
+        let sender = FileSmsSender::new(path.to_string_lossy());
+        sender.send_code("+8613800000000", "123456").await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.trim_end().ends_with("+8613800000000 123456"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+use crate::error::Error;
+use crate::sms::SmsSender;
+use async_trait::async_trait;
+use std::sync::Mutex;
+use tracing::info;
+
+/// 不真正发送短信, 只打印日志并保存已发送的验证码
+#[derive(Debug, Default)]
+pub struct MockSmsSender {
+    sent: Mutex<Vec<(String, String)>>,
+}
+
+#[cfg(test)]
+impl MockSmsSender {
+    /// 返回最近一次发送到`phone`的验证码
+    pub fn last_code(&self, phone: &str) -> Option<String> {
+        let sent = self.sent.lock().unwrap();
+        sent.iter()
+            .rev()
+            .find(|(p, _)| p == phone)
+            .map(|(_, code)| code.clone())
+    }
+}
+
+#[async_trait]
+impl SmsSender for MockSmsSender {
+    async fn send_code(&self, phone: &str, code: &str) -> Result<(), Error> {
+        info!("mock sms, phone: {}, code: {}", phone, code);
+        self.sent
+            .lock()
+            .unwrap()
+            .push((phone.to_string(), code.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_sms_sender() {
+        let sender = MockSmsSender::default();
+        assert_eq!(sender.last_code("+8613800000000"), None);
+
+        sender.send_code("+8613800000000", "111111").await.unwrap();
+        sender.send_code("+8613800000000", "222222").await.unwrap();
+        assert_eq!(
+            sender.last_code("+8613800000000"),
+            Some("222222".to_string())
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use crate::config::{SmsConfig, SmsSenderKind};
+use crate::error::Error;
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+pub(crate) mod file;
+pub(crate) mod mock;
+
+/// 短信发送, 接入短信服务商时实现该trait
+#[async_trait]
+pub trait SmsSender: Sync + Send + Debug {
+    /// 发送验证码到E.164格式的手机号
+    async fn send_code(&self, phone: &str, code: &str) -> Result<(), Error>;
+}
+
+pub fn from_config(config: &SmsConfig) -> Box<dyn SmsSender> {
+    match config.sender {
+        SmsSenderKind::Mock => Box::new(mock::MockSmsSender::default()),
+        SmsSenderKind::File => Box::new(file::FileSmsSender::new(&config.file_path)),
+    }
+}
@@ -0,0 +1,29 @@
+use crate::pb::user::SecurityEventType;
+use crate::repo::SecurityEvent;
+use crate::service_context::ServiceContext;
+use crate::utils::ClientInfo;
+use tracing::warn;
+
+/// 记录安全事件, 写入失败只打印日志, 不影响请求本身
+pub async fn record(
+    svc: &ServiceContext,
+    client: &ClientInfo,
+    event_type: SecurityEventType,
+    user_id: Option<&str>,
+    account: &str,
+    reason: &str,
+) {
+    let event = SecurityEvent {
+        id: 0,
+        user_id: user_id.map(str::to_string),
+        account: account.to_string(),
+        event_type,
+        ip: client.ip.clone(),
+        device: client.device.clone(),
+        reason: reason.to_string(),
+        create_time: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Err(e) = svc.security_event_repo.insert(&event).await {
+        warn!("record security event failed, {:?}, {:?}", event, e);
+    }
+}
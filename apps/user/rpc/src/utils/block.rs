@@ -0,0 +1,73 @@
+use crate::error::Error;
+use crate::service_context::ServiceContext;
+use std::collections::HashSet;
+
+/// 两个用户之间的拉黑关系
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockRelation {
+    /// 用户拉黑了对方
+    pub blocking: bool,
+    /// 用户被对方拉黑
+    pub blocked_by: bool,
+}
+
+impl BlockRelation {
+    /// 任意一方拉黑了对方
+    pub fn any(&self) -> bool {
+        self.blocking || self.blocked_by
+    }
+}
+
+pub async fn relation(
+    svc: &ServiceContext,
+    user_id: &str,
+    other_user_id: &str,
+) -> Result<BlockRelation, Error> {
+    let blocking = svc
+        .block_repo
+        .find_blocked_ids(user_id)
+        .await?
+        .iter()
+        .any(|id| id == other_user_id);
+    let blocked_by = svc
+        .block_repo
+        .find_blocked_ids(other_user_id)
+        .await?
+        .iter()
+        .any(|id| id == user_id);
+    Ok(BlockRelation {
+        blocking,
+        blocked_by,
+    })
+}
+
+/// `others`中与`user_id`之间存在拉黑关系的用户, 任意一方拉黑了对方即可
+pub async fn blocked_between(
+    svc: &ServiceContext,
+    user_id: &str,
+    others: &[String],
+) -> Result<HashSet<String>, Error> {
+    let blocking: HashSet<String> = svc
+        .block_repo
+        .find_blocked_ids(user_id)
+        .await?
+        .into_iter()
+        .collect();
+    let mut blocked = HashSet::new();
+    for other in others {
+        if blocked.contains(other) {
+            continue;
+        }
+        if blocking.contains(other)
+            || svc
+                .block_repo
+                .find_blocked_ids(other)
+                .await?
+                .iter()
+                .any(|id| id == user_id)
+        {
+            blocked.insert(other.clone());
+        }
+    }
+    Ok(blocked)
+}
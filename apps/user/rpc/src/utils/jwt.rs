@@ -1,16 +1,29 @@
 use crate::error::Error;
-use crate::pb::user::User;
-use jsonwebtoken::{EncodingKey, Header};
+use crate::pb::user::{User, UserRole};
+use crate::service_context::ServiceContext;
+use crate::utils;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use tonic::Request;
+
+/// 携带token的metadata, 格式为`Bearer <token>`
+pub const AUTHORIZATION_METADATA_KEY: &str = "authorization";
+const BEARER_PREFIX: &str = "Bearer ";
 
 pub const REFRESH_EXPIRES: i64 = 24 * 60 * 60;
 const EXPIRES: i64 = 60 * 60 * 4;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub user_id: String,
+    /// 签发时的角色, 旧版本签发的token没有该字段
+    #[serde(default)]
+    pub role: String,
     pub exp: i64,
     pub iat: i64,
+    /// 签发时间(毫秒), 与会话注销时间比较, 旧版本签发的token没有该字段
+    #[serde(default)]
+    pub iat_ms: i64,
 }
 
 #[derive(Debug)]
@@ -21,19 +34,29 @@ pub struct JwtToken {
 }
 
 impl Claims {
-    pub fn new(user_id: String) -> Self {
-        let now = chrono::Utc::now().timestamp();
-        let exp = now + EXPIRES;
+    pub fn new(user_id: String, role: UserRole) -> Self {
+        let now = chrono::Utc::now();
         Self {
             user_id,
-            exp,
-            iat: now,
+            role: utils::role_name(role).to_string(),
+            exp: now.timestamp() + EXPIRES,
+            iat: now.timestamp(),
+            iat_ms: now.timestamp_millis(),
+        }
+    }
+
+    /// 签发时间(毫秒), 旧版本的token只有秒级的`iat`
+    pub fn issued_at_millis(&self) -> i64 {
+        if self.iat_ms > 0 {
+            self.iat_ms
+        } else {
+            self.iat * 1000
         }
     }
 }
 
 pub fn gen_token(user: &User, secret: &str) -> Result<JwtToken, Error> {
-    let mut claims = Claims::new(user.id.clone());
+    let mut claims = Claims::new(user.id.clone(), user.role());
     let token = jsonwebtoken::encode(
         &Header::default(),
         &claims,
@@ -54,3 +77,171 @@ pub fn gen_token(user: &User, secret: &str) -> Result<JwtToken, Error> {
         refresh_token,
     })
 }
+
+pub fn decode_token(token: &str, secret: &str) -> Result<Claims, Error> {
+    let data = jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|e| Error::invalid_token(format!("jwt error, {}", e)))?;
+    Ok(data.claims)
+}
+
+/// 校验token, 并拒绝在会话注销之前签发的token
+pub async fn verify_token(svc: &ServiceContext, token: &str) -> Result<Claims, Error> {
+    let claims = decode_token(token, &svc.config.jwt.secret)?;
+    let revoked_at = svc
+        .cache
+        .get_user_sessions_revoked_at(&claims.user_id)
+        .await?;
+    if revoked_at.is_some_and(|revoked_at| claims.issued_at_millis() < revoked_at) {
+        return Err(Error::invalid_token("token has been revoked"));
+    }
+    Ok(claims)
+}
+
+/// 读取请求中的bearer token
+pub fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get(AUTHORIZATION_METADATA_KEY)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix(BEARER_PREFIX))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// 校验调用方的token和角色, 返回调用方用户
+///
+/// 角色以数据库中的为准, 降级或封禁后已签发的token立即失去权限
+pub async fn authorize<T>(
+    svc: &ServiceContext,
+    request: &Request<T>,
+    roles: &[UserRole],
+) -> Result<User, Error> {
+    let token = bearer_token(request).ok_or_else(|| Error::invalid_token("missing token"))?;
+    let claims = verify_token(svc, token).await?;
+    let Some(user) = svc.user_repo.find_by_id(&claims.user_id).await? else {
+        return Err(Error::invalid_token("user not found"));
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    if !roles.contains(&user.role()) || utils::is_banned(&user, now) {
+        return Err(Error::permission_denied("permission denied"));
+    }
+    Ok(user)
+}
+
+/// 读取拦截器校验token后放入请求的claims
+pub fn caller<T>(request: &Request<T>) -> Result<Claims, Error> {
+    request
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| Error::invalid_token("missing token"))
+}
+
+/// 自助接口操作的用户, 只能是调用方本人
+///
+/// 请求中的`user_id`为空时为调用方, 不是调用方时拒绝
+pub fn caller_id<T>(request: &Request<T>, user_id: &str) -> Result<String, Error> {
+    let claims = caller(request)?;
+    if !user_id.is_empty() && user_id != claims.user_id {
+        return Err(Error::permission_denied("permission denied"));
+    }
+    Ok(claims.user_id)
+}
+
+/// 同`caller_id`, 管理员可以操作其他用户, 角色以数据库中的为准
+pub async fn caller_or_admin<T>(
+    svc: &ServiceContext,
+    request: &Request<T>,
+    user_id: &str,
+) -> Result<String, Error> {
+    let claims = caller(request)?;
+    if user_id.is_empty() || user_id == claims.user_id {
+        return Ok(claims.user_id);
+    }
+    let is_admin = svc
+        .user_repo
+        .find_by_id(&claims.user_id)
+        .await?
+        .is_some_and(|user| {
+            user.role() == UserRole::Admin
+                && !utils::is_banned(&user, chrono::Utc::now().timestamp_millis())
+        });
+    if !is_admin {
+        return Err(Error::permission_denied("permission denied"));
+    }
+    Ok(user_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_carries_role() {
+        let user = User {
+            id: "id".to_string(),
+            role: UserRole::Admin as i32,
+            ..Default::default()
+        };
+        let token = gen_token(&user, "secret").unwrap();
+        let claims = decode_token(&token.token, "secret").unwrap();
+        assert_eq!(claims.user_id, "id");
+        assert_eq!(claims.role, "admin");
+        assert!(decode_token(&token.token, "other").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_revoked_token() {
+        let svc = crate::logic::tests::test_context();
+        let user = User {
+            id: "id".to_string(),
+            ..Default::default()
+        };
+        // 同一秒内注销之前签发的token也要拒绝
+        let token = gen_token(&user, &svc.config.jwt.secret).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        svc.cache.revoke_user_sessions("id").await.unwrap();
+        assert!(verify_token(&svc, &token.token).await.is_err());
+
+        tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+        let token = gen_token(&user, &svc.config.jwt.secret).unwrap();
+        assert!(verify_token(&svc, &token.token).await.is_ok());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut request = Request::new(());
+        assert_eq!(bearer_token(&request), None);
+
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_METADATA_KEY, "Basic abc".parse().unwrap());
+        assert_eq!(bearer_token(&request), None);
+
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_METADATA_KEY, "Bearer abc".parse().unwrap());
+        assert_eq!(bearer_token(&request), Some("abc"));
+    }
+
+    #[test]
+    fn test_caller_id() {
+        let code = |result: Result<String, Error>| tonic::Status::from(result.unwrap_err()).code();
+        let mut request = Request::new(());
+        assert_eq!(code(caller_id(&request, "")), tonic::Code::Unauthenticated);
+
+        request
+            .extensions_mut()
+            .insert(Claims::new("lucas".to_string(), UserRole::User));
+        assert_eq!(caller_id(&request, "").unwrap(), "lucas");
+        assert_eq!(caller_id(&request, "lucas").unwrap(), "lucas");
+        assert_eq!(
+            code(caller_id(&request, "luna")),
+            tonic::Code::PermissionDenied
+        );
+    }
+}
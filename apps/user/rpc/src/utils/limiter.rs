@@ -0,0 +1,50 @@
+use crate::error::Error;
+use crate::service_context::ServiceContext;
+
+/// 检查是否处于锁定状态, 锁定时返回的错误带有剩余的锁定秒数
+pub async fn ensure_not_locked(svc: &ServiceContext, key: &str) -> Result<(), Error> {
+    if let Some(ttl) = svc.cache.get_lockout_ttl(key).await? {
+        return Err(Error::too_many_requests(
+            "too many failed attempts, temporarily locked",
+            ttl,
+        ));
+    }
+    Ok(())
+}
+
+/// 滑动窗口限流, 超出限制时返回需要等待的秒数
+pub async fn check_rate_limit(
+    svc: &ServiceContext,
+    key: &str,
+    limit: u64,
+    window_seconds: u64,
+) -> Result<(), Error> {
+    if let Some(retry_after) = svc
+        .cache
+        .check_rate_limit(key, limit, window_seconds)
+        .await?
+    {
+        return Err(Error::too_many_requests("too many requests", retry_after));
+    }
+    Ok(())
+}
+
+/// 记录一次失败, 达到最大失败次数后锁定
+pub async fn record_failure(svc: &ServiceContext, key: &str) -> Result<(), Error> {
+    let config = &svc.config.rate_limit;
+    let failed = svc
+        .cache
+        .incr_failed_attempts(key, config.failed_attempts_window)
+        .await?;
+
+    if failed >= config.max_failed_attempts {
+        svc.cache.set_lockout(key, config.lockout_duration).await?;
+        svc.cache.clear_failed_attempts(key).await?;
+    }
+    Ok(())
+}
+
+/// 成功后清除失败计数
+pub async fn clear_failures(svc: &ServiceContext, key: &str) -> Result<(), Error> {
+    svc.cache.clear_failed_attempts(key).await
+}
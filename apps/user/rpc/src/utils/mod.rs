@@ -1,35 +1,349 @@
+use crate::config::PasswordConfig;
 use crate::error::Error;
+use crate::pb::user::{User, UserRole};
+use crate::service_context::ServiceContext;
 use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use ipnet::IpNet;
+use rand::Rng;
+use std::net::IpAddr;
+use tonic::Request;
 
+pub(crate) mod audit;
+pub(crate) mod block;
 pub(crate) mod jwt;
+pub(crate) mod limiter;
+pub(crate) mod totp;
+pub(crate) mod view;
 
-pub fn generate_salt() -> String {
-    SaltString::generate(&mut OsRng).to_string()
+/// 转发客户端真实IP的metadata, 由user-api等网关设置
+const CLIENT_IP_METADATA_KEYS: [&str; 2] = ["x-real-ip", "x-forwarded-for"];
+/// 客户端设备描述, 没有时使用user-agent
+const CLIENT_DEVICE_METADATA_KEYS: [&str; 2] = ["x-device", "user-agent"];
+/// 设备描述的最大长度
+const MAX_DEVICE_LEN: usize = 256;
+
+fn generate_salt() -> SaltString {
+    SaltString::generate(&mut OsRng)
+}
+
+/// 根据配置构造Argon2id (v19) 哈希器
+fn argon2(config: &PasswordConfig) -> Result<Argon2<'static>, Error> {
+    let params = Params::new(
+        config.memory_kib,
+        config.iterations,
+        config.parallelism,
+        None,
+    )
+    .map_err(|e| Error::internal_with_details(format!("invalid argon2 params, {}", e)))?;
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
 }
 
 /// 使用Argon2算法对密码进行安全哈希处理
 ///
-/// 该函数使用加密安全的盐值和Argon2id算法对用户密码进行哈希，
-/// 提供抗暴力破解和彩虹表攻击的保护。
+/// 该函数使用加密安全的随机盐值和Argon2id算法对用户密码进行哈希，
+/// 提供抗暴力破解和彩虹表攻击的保护。盐值和参数都保存在PHC字符串中。
 ///
 /// # 参数
+/// - `config`: Argon2参数配置
 /// - `password`: 用户原始密码字节数组
-/// - `salt`: 加密盐值（建议使用generate_salt生成）
 ///
 /// # 返回值
 /// - `Result<String, Error>`: 成功返回PHC格式的哈希字符串，失败返回错误信息
-pub fn hash_password(password: &[u8], salt: &str) -> Result<String, Error> {
-    // 使用默认的Argon2配置
-    // 这个配置可以更改为适合您具体安全需求和性能要求的设置
-
-    // Argon2 with default params (Argon2id v19)
-    let argon2 = Argon2::default();
-
+pub fn hash_password(config: &PasswordConfig, password: &[u8]) -> Result<String, Error> {
     // Hash password to PHC string ($argon2id$v=19$...)
-    Ok(argon2
-        .hash_password(password, &SaltString::from_b64(salt).unwrap())
+    Ok(argon2(config)?
+        .hash_password(password, &generate_salt())
         .map_err(|e| Error::internal_with_details(e.to_string()))?
         .to_string())
 }
+
+/// 校验密码, 使用PHC字符串中保存的算法和参数, 与当前配置无关
+pub fn verify_password(password: &[u8], password_hash: &str) -> Result<bool, Error> {
+    let password_hash = PasswordHash::new(password_hash)
+        .map_err(|e| Error::internal_with_details(format!("密码哈希错误 {}", e)))?;
+    Ok(Argon2::default()
+        .verify_password(password, &password_hash)
+        .is_ok())
+}
+
+/// 判断密码哈希是否使用了过时的算法或参数, 需要重新哈希
+pub fn password_needs_rehash(config: &PasswordConfig, password_hash: &str) -> bool {
+    let Ok(password_hash) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    if password_hash.algorithm != Algorithm::Argon2id.ident()
+        || password_hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+    match Params::try_from(&password_hash) {
+        Ok(params) => {
+            params.m_cost() != config.memory_kib
+                || params.t_cost() != config.iterations
+                || params.p_cost() != config.parallelism
+        }
+        Err(_) => true,
+    }
+}
+
+/// 生成`length`位的数字验证码, 使用操作系统的安全随机数
+pub fn gen_verify_code(length: u32) -> String {
+    let max = 10u64.pow(length);
+    let num = OsRng.gen_range(0..max);
+    format!("{:0width$}", num, width = length as usize)
+}
+
+/// 把手机号规范化为E.164格式(`+`国际区号+号码, 最多15位数字)
+///
+/// 忽略空格, `-`, `.`和括号; `00`开头视为国际前缀;
+/// 没有国际区号时去掉国内长途前缀`0`并加上默认区号
+pub fn normalize_phone(phone: &str, default_country_code: &str) -> Result<String, Error> {
+    let phone: String = phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+
+    let digits = if let Some(rest) = phone.strip_prefix('+') {
+        rest.to_string()
+    } else if let Some(rest) = phone.strip_prefix("00") {
+        rest.to_string()
+    } else {
+        format!("{}{}", default_country_code, phone.trim_start_matches('0'))
+    };
+
+    let valid = (8..=15).contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && !digits.starts_with('0');
+    if !valid {
+        return Err(Error::invalid_phone("invalid phone number"));
+    }
+    Ok(format!("+{}", digits))
+}
+
+/// 请求来源, 用于记录安全事件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientInfo {
+    pub ip: String,
+    pub device: String,
+}
+
+impl ClientInfo {
+    pub fn from_request<T>(svc: &ServiceContext, request: &Request<T>) -> Self {
+        let device = CLIENT_DEVICE_METADATA_KEYS
+            .iter()
+            .filter_map(|key| request.metadata().get(*key))
+            .filter_map(|v| v.to_str().ok())
+            .map(|v| v.trim())
+            .find(|v| !v.is_empty())
+            .map(|v| v.chars().take(MAX_DEVICE_LEN).collect())
+            .unwrap_or_default();
+        Self {
+            ip: client_ip(request, &svc.config.rate_limit.trusted_proxies),
+            device,
+        }
+    }
+}
+
+/// 角色名称, 用于数据库和token
+pub fn role_name(role: UserRole) -> &'static str {
+    match role {
+        UserRole::User => "user",
+        UserRole::Admin => "admin",
+        UserRole::Support => "support",
+    }
+}
+
+/// 解析角色名称, 未知的角色按普通用户处理
+pub fn parse_role(name: &str) -> UserRole {
+    match name {
+        "admin" => UserRole::Admin,
+        "support" => UserRole::Support,
+        _ => UserRole::User,
+    }
+}
+
+/// 生成新用户的id, 按生成时间排序
+pub fn gen_user_id(svc: &ServiceContext) -> Result<String, Error> {
+    svc.id_generator
+        .next_string()
+        .map_err(|e| Error::internal_with_details(e.to_string()))
+}
+
+/// 用户是否处于封禁中, 到期的封禁视为已解除
+pub fn is_banned(user: &User, now: i64) -> bool {
+    user.banned && (user.ban_until == 0 || user.ban_until > now)
+}
+
+/// 客户端IP, 只有对端是可信的代理时才读取代理转发的地址, 避免客户端伪造请求头绕过按IP的限流
+pub fn client_ip<T>(request: &Request<T>, trusted_proxies: &[IpNet]) -> String {
+    let peer = request.remote_addr().map(|addr| addr.ip());
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+
+    if peer.as_ref().is_some_and(is_trusted) {
+        let header = |key: &str| {
+            request
+                .metadata()
+                .get(key)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        if let Some(ip) =
+            header(CLIENT_IP_METADATA_KEYS[0]).and_then(|v| v.trim().parse::<IpAddr>().ok())
+        {
+            return ip.to_string();
+        }
+        // 从右往左跳过可信的代理, 第一个不可信的地址为客户端, 更左边的可以被客户端伪造
+        if let Some(forwarded) = header(CLIENT_IP_METADATA_KEYS[1]) {
+            let hops: Vec<IpAddr> = forwarded
+                .split(',')
+                .filter_map(|v| v.trim().parse().ok())
+                .collect();
+            if let Some(ip) = hops
+                .iter()
+                .rev()
+                .find(|ip| !is_trusted(ip))
+                .or(hops.first())
+            {
+                return ip.to_string();
+            }
+        }
+    }
+
+    peer.map(|ip| ip.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use tonic::transport::server::TcpConnectInfo;
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let config = PasswordConfig::default();
+        let hash = hash_password(&config, b"password").unwrap();
+
+        assert!(verify_password(b"password", &hash).unwrap());
+        assert!(!verify_password(b"wrong-password", &hash).unwrap());
+        assert!(!password_needs_rehash(&config, &hash));
+    }
+
+    #[test]
+    fn test_password_needs_rehash_after_params_changed() {
+        let old_config = PasswordConfig {
+            memory_kib: 8 * 1024,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let hash = hash_password(&old_config, b"password").unwrap();
+
+        let config = PasswordConfig::default();
+        assert!(password_needs_rehash(&config, &hash));
+        // 旧参数生成的哈希依然可以校验通过
+        assert!(verify_password(b"password", &hash).unwrap());
+    }
+
+    #[test]
+    fn test_gen_verify_code() {
+        for _ in 0..100 {
+            let code = gen_verify_code(6);
+            assert_eq!(code.len(), 6);
+            assert!(code.chars().all(|c| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn test_normalize_phone() {
+        for phone in [
+            "13800138000",
+            "138-0013-8000",
+            "+86 138 0013 8000",
+            "0086 13800138000",
+        ] {
+            assert_eq!(normalize_phone(phone, "86").unwrap(), "+8613800138000");
+        }
+        assert_eq!(
+            normalize_phone("(020) 7946 0958", "44").unwrap(),
+            "+442079460958"
+        );
+
+        for phone in [
+            "",
+            "+",
+            "+0123456789",
+            "+86138abc38000",
+            "+1234567890123456",
+        ] {
+            assert!(normalize_phone(phone, "86").is_err());
+        }
+    }
+
+    #[test]
+    fn test_client_ip_from_trusted_proxy() {
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+        let request_from = |peer: &str| {
+            let mut request = Request::new(());
+            request.extensions_mut().insert(TcpConnectInfo {
+                local_addr: None,
+                remote_addr: Some(SocketAddr::new(peer.parse().unwrap(), 50051)),
+            });
+            request.metadata_mut().insert(
+                "x-forwarded-for",
+                "1.1.1.1, 2.2.2.2, 10.0.0.2".parse().unwrap(),
+            );
+            request
+        };
+        assert_eq!(client_ip(&Request::new(()), &trusted), "");
+
+        // 不可信的对端伪造的请求头被忽略
+        let mut request = request_from("3.3.3.3");
+        request
+            .metadata_mut()
+            .insert("x-real-ip", "4.4.4.4".parse().unwrap());
+        assert_eq!(client_ip(&request, &trusted), "3.3.3.3");
+        assert_eq!(client_ip(&request, &[]), "3.3.3.3");
+
+        // 跳过可信的代理, 取最右边不可信的地址
+        let mut request = request_from("10.0.0.1");
+        assert_eq!(client_ip(&request, &trusted), "2.2.2.2");
+        request
+            .metadata_mut()
+            .insert("x-real-ip", "4.4.4.4".parse().unwrap());
+        assert_eq!(client_ip(&request, &trusted), "4.4.4.4");
+    }
+
+    #[test]
+    fn test_is_banned() {
+        let mut user = User::default();
+        assert!(!is_banned(&user, 1000));
+
+        user.banned = true;
+        assert!(is_banned(&user, 1000));
+
+        user.ban_until = 2000;
+        assert!(is_banned(&user, 1000));
+        assert!(!is_banned(&user, 2000));
+    }
+
+    #[test]
+    fn test_client_info_device() {
+        let svc = crate::logic::tests::test_context();
+        let mut request = Request::new(());
+        assert_eq!(ClientInfo::from_request(&svc, &request).device, "");
+
+        request
+            .metadata_mut()
+            .insert("user-agent", "grpc-go/1.60".parse().unwrap());
+        assert_eq!(
+            ClientInfo::from_request(&svc, &request).device,
+            "grpc-go/1.60"
+        );
+
+        request
+            .metadata_mut()
+            .insert("x-device", "iPhone 15".parse().unwrap());
+        assert_eq!(ClientInfo::from_request(&svc, &request).device, "iPhone 15");
+    }
+}
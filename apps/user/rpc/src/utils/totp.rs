@@ -0,0 +1,202 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// RFC 6238默认参数, 与常见的验证器App保持一致
+const DIGITS: u32 = 6;
+const PERIOD: i64 = 30;
+const SECRET_LEN: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// 恢复码字符集, 去掉了容易混淆的0/O/1/I/L
+const RECOVERY_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// 生成base32编码的随机密钥
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LEN];
+    OsRng.fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+/// 生成验证器App使用的otpauth URI
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        PERIOD
+    )
+}
+
+/// 校验验证码, 允许前后`skew`个时间步的误差, 通过时返回匹配的时间步
+pub fn verify(secret: &str, code: &str, timestamp: i64, skew: i64) -> Option<i64> {
+    let key = base32_decode(secret)?;
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let step = timestamp / PERIOD;
+    (step - skew..=step + skew).find(|&step| format_code(hotp(&key, step as u64)) == code)
+}
+
+/// 生成指定时间的验证码, 测试中模拟验证器App
+#[cfg(test)]
+pub fn generate_code(secret: &str, timestamp: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    Some(format_code(hotp(&key, (timestamp / PERIOD) as u64)))
+}
+
+/// 生成`count`个形如`XXXXX-XXXXX`的恢复码
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| {
+                    let index = OsRng.gen_range(0..RECOVERY_CODE_ALPHABET.len());
+                    RECOVERY_CODE_ALPHABET[index] as char
+                })
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// 恢复码的哈希, 忽略大小写和分隔符. 恢复码本身足够随机, 不需要慢哈希
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    Sha256::digest(normalized.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// RFC 4226 HOTP, 返回截断后的31位整数
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ])
+}
+
+fn format_code(value: u32) -> String {
+    format!(
+        "{:0width$}",
+        value % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// RFC 4648 base32编码, 不带填充
+fn base32_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            result.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        result.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    result
+}
+
+/// base32解码, 忽略大小写, 空格和填充
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(data.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in data.chars().filter(|c| !matches!(c, ' ' | '=')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&b| b as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    Some(result)
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238附录B的SHA1测试密钥
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_base32() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        assert!(base32_decode("MZXW1").is_none());
+
+        let secret = generate_secret();
+        assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_LEN);
+    }
+
+    #[test]
+    fn test_verify_rfc6238() {
+        let secret = base32_encode(RFC_SECRET);
+        // RFC中为8位验证码, 取后6位
+        assert_eq!(verify(&secret, "287082", 59, 0), Some(1));
+        assert_eq!(verify(&secret, "081804", 1111111109, 0), Some(37037036));
+        assert_eq!(verify(&secret, "005924", 1234567890, 0), Some(41152263));
+
+        // 允许一个时间步的误差
+        assert_eq!(verify(&secret, "287082", 89, 1), Some(1));
+        assert_eq!(verify(&secret, "287082", 89, 0), None);
+        assert_eq!(verify(&secret, "28708", 59, 0), None);
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes(10);
+        assert_eq!(codes.len(), 10);
+        assert!(codes.iter().all(|code| code.len() == 11));
+
+        let hash = hash_recovery_code(&codes[0]);
+        assert_eq!(hash_recovery_code(&codes[0].to_lowercase()), hash);
+        assert_eq!(hash_recovery_code(&codes[0].replace('-', "")), hash);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("Lucas IM", "lucas@example.com", "MZXW6YTBOI");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Lucas%20IM:lucas%40example.com?secret=MZXW6YTBOI&issuer=Lucas%20IM&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
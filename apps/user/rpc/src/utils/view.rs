@@ -0,0 +1,376 @@
+use crate::error::Error;
+use crate::pb::user::{PrivacyScope, User, UserRole, UserView};
+use crate::repo::UserPrivacy;
+use crate::service_context::ServiceContext;
+use crate::utils::{self, jwt};
+use std::collections::{HashMap, HashSet};
+use tonic::Request;
+
+/// 公开名片的字段
+const PUBLIC_FIELDS: &[&str] = &["id", "name", "avatar", "signature"];
+/// 好友可见的字段
+const FRIEND_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "avatar",
+    "signature",
+    "gender",
+    "age",
+    "region",
+    "birthday",
+];
+/// 本人或管理员可见的字段, 不包含密码
+const FULL_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "avatar",
+    "signature",
+    "gender",
+    "age",
+    "region",
+    "birthday",
+    "account",
+    "phone",
+    "email",
+    "address",
+    "create_time",
+    "update_time",
+    "role",
+    "banned",
+    "ban_reason",
+    "ban_until",
+];
+
+/// 发起请求的用户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewer {
+    pub user_id: String,
+    /// 管理员或客服, 可以看到所有用户的全部字段
+    pub privileged: bool,
+}
+
+impl Viewer {
+    /// 本人或管理员/客服, 不受隐私设置限制
+    pub fn bypasses_privacy(&self, user_id: &str) -> bool {
+        self.privileged || self.user_id == user_id
+    }
+}
+
+/// 查找用户的方式, 对应隐私设置中的可查找项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupBy {
+    Phone,
+    Email,
+    Account,
+}
+
+pub fn view_fields(view: UserView) -> &'static [&'static str] {
+    match view {
+        UserView::Public => PUBLIC_FIELDS,
+        UserView::Friend => FRIEND_FIELDS,
+        UserView::Full => FULL_FIELDS,
+    }
+}
+
+/// 解析需要返回的字段, 为空时返回`None`表示不限制
+pub fn parse_read_mask(paths: &[String]) -> Result<Option<HashSet<&'static str>>, Error> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    let mut fields = HashSet::with_capacity(paths.len());
+    for path in paths {
+        let Some(field) = FULL_FIELDS.iter().find(|field| **field == path) else {
+            return Err(Error::invalid_argument(format!(
+                "field `{}` can not be read",
+                path
+            )));
+        };
+        fields.insert(*field);
+    }
+    Ok(Some(fields))
+}
+
+/// 只保留`view`和`mask`都允许的字段, 其余字段置为默认值
+pub fn mask_user(user: User, view: UserView, mask: Option<&HashSet<&str>>) -> User {
+    let allowed = view_fields(view);
+    let keep =
+        |field: &str| allowed.contains(&field) && mask.is_none_or(|mask| mask.contains(field));
+    fn pick<T: Default>(keep: bool, value: T) -> T {
+        if keep {
+            value
+        } else {
+            T::default()
+        }
+    }
+
+    User {
+        // id用于对应请求, 总是返回
+        id: user.id,
+        name: pick(keep("name"), user.name),
+        account: pick(keep("account"), user.account),
+        password: String::new(),
+        avatar: pick(keep("avatar"), user.avatar),
+        gender: pick(keep("gender"), user.gender),
+        age: pick(keep("age"), user.age),
+        phone: pick(keep("phone"), user.phone),
+        email: pick(keep("email"), user.email),
+        address: pick(keep("address"), user.address),
+        region: pick(keep("region"), user.region),
+        birthday: pick(keep("birthday"), user.birthday),
+        create_time: pick(keep("create_time"), user.create_time),
+        update_time: pick(keep("update_time"), user.update_time),
+        signature: pick(keep("signature"), user.signature),
+        role: pick(keep("role"), user.role),
+        banned: pick(keep("banned"), user.banned),
+        ban_reason: pick(keep("ban_reason"), user.ban_reason),
+        ban_until: pick(keep("ban_until"), user.ban_until),
+    }
+}
+
+/// 读取请求中的调用方, 没有token时返回`None`, token无效时返回错误
+///
+/// 角色以数据库中的为准
+pub async fn viewer<T>(
+    svc: &ServiceContext,
+    request: &Request<T>,
+) -> Result<Option<Viewer>, Error> {
+    let Some(token) = jwt::bearer_token(request) else {
+        return Ok(None);
+    };
+    let claims = jwt::verify_token(svc, token).await?;
+    let Some(user) = svc.user_repo.find_by_id(&claims.user_id).await? else {
+        return Ok(None);
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let privileged =
+        matches!(user.role(), UserRole::Admin | UserRole::Support) && !utils::is_banned(&user, now);
+    Ok(Some(Viewer {
+        user_id: user.id,
+        privileged,
+    }))
+}
+
+/// 校验token并读取调用方, 与[`viewer`]不同, 用户不存在或被封禁时同样返回错误.
+/// 用于长时间的订阅中重新检查调用方
+pub async fn verify_viewer(svc: &ServiceContext, token: &str) -> Result<Viewer, Error> {
+    let claims = jwt::verify_token(svc, token).await?;
+    let Some(user) = svc.user_repo.find_by_id(&claims.user_id).await? else {
+        return Err(Error::invalid_token("user not found"));
+    };
+    if utils::is_banned(&user, chrono::Utc::now().timestamp_millis()) {
+        return Err(Error::invalid_token("user is banned"));
+    }
+    Ok(Viewer {
+        privileged: matches!(user.role(), UserRole::Admin | UserRole::Support),
+        user_id: user.id,
+    })
+}
+
+/// 按隐私设置判断调用方能否通过`by`查找到用户
+pub async fn discoverable(
+    svc: &ServiceContext,
+    viewer: Option<&Viewer>,
+    user: &User,
+    by: LookupBy,
+) -> Result<bool, Error> {
+    if viewer.is_some_and(|viewer| viewer.bypasses_privacy(&user.id)) {
+        return Ok(true);
+    }
+    let privacy = svc.privacy_repo.find(&user.id).await?;
+    let searchable = match by {
+        LookupBy::Phone => privacy.searchable_by_phone,
+        LookupBy::Email => privacy.searchable_by_email,
+        LookupBy::Account => privacy.searchable_by_account,
+    };
+    Ok(searchable)
+}
+
+/// 按隐私设置隐藏签名和地区, `allowed`为调用方与用户的关系对应的视图
+fn apply_privacy(user: &mut User, privacy: &UserPrivacy, allowed: UserView) {
+    let hidden = match privacy.profile_visibility {
+        PrivacyScope::Everyone => false,
+        PrivacyScope::Friends => allowed < UserView::Friend,
+        PrivacyScope::Nobody => allowed < UserView::Full,
+    };
+    if hidden {
+        user.signature.clear();
+        user.region = None;
+    }
+}
+
+/// 调用方可以看到的视图
+fn allowed_view(viewer: Option<&Viewer>, user_id: &str, friends: &HashSet<String>) -> UserView {
+    match viewer {
+        Some(viewer) if viewer.bypasses_privacy(user_id) => UserView::Full,
+        Some(_) if friends.contains(user_id) => UserView::Friend,
+        _ => UserView::Public,
+    }
+}
+
+/// 按调用方与用户的关系和用户的隐私设置裁剪用户资料,
+/// `requested`超出调用方可见的视图时降级
+pub async fn filter_users(
+    svc: &ServiceContext,
+    viewer: Option<&Viewer>,
+    users: Vec<User>,
+    requested: UserView,
+    mask: Option<&HashSet<&str>>,
+) -> Result<Vec<User>, Error> {
+    let friends = match viewer {
+        Some(viewer) if !viewer.privileged && requested >= UserView::Friend => {
+            let candidates: Vec<String> = users
+                .iter()
+                .filter(|user| user.id != viewer.user_id)
+                .map(|user| user.id.clone())
+                .collect();
+            if candidates.is_empty() {
+                HashSet::new()
+            } else {
+                svc.friend_checker
+                    .filter_friends(&viewer.user_id, &candidates)
+                    .await?
+            }
+        }
+        _ => HashSet::new(),
+    };
+
+    let restricted: Vec<String> = users
+        .iter()
+        .filter(|user| !viewer.is_some_and(|viewer| viewer.bypasses_privacy(&user.id)))
+        .map(|user| user.id.clone())
+        .collect();
+    let privacies: HashMap<String, UserPrivacy> = if restricted.is_empty() {
+        HashMap::new()
+    } else {
+        svc.privacy_repo
+            .find_by_users(&restricted)
+            .await?
+            .into_iter()
+            .map(|privacy| (privacy.user_id.clone(), privacy))
+            .collect()
+    };
+
+    let users = users
+        .into_iter()
+        .map(|user| {
+            let allowed = allowed_view(viewer, &user.id, &friends);
+            let privacy = privacies.get(&user.id).cloned();
+            let mut user = mask_user(user, requested.min(allowed), mask);
+            if let Some(privacy) = privacy {
+                apply_privacy(&mut user, &privacy, allowed);
+            }
+            user
+        })
+        .collect();
+    Ok(users)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        User {
+            id: "id".to_string(),
+            name: "lucas".to_string(),
+            password: "hash".to_string(),
+            age: 18,
+            phone: Some("+8613800138000".to_string()),
+            email: Some("lucas@example.com".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mask_user() {
+        let public = mask_user(user(), UserView::Public, None);
+        assert_eq!(public.name, "lucas");
+        assert_eq!(public.age, 0);
+        assert_eq!(public.phone, None);
+        assert_eq!(public.password, "");
+
+        let friend = mask_user(user(), UserView::Friend, None);
+        assert_eq!(friend.age, 18);
+        assert_eq!(friend.email, None);
+
+        let full = mask_user(user(), UserView::Full, None);
+        assert_eq!(full.email.as_deref(), Some("lucas@example.com"));
+        assert_eq!(full.password, "");
+
+        // mask不能突破视图的限制
+        let mask = parse_read_mask(&["name".to_string(), "phone".to_string()])
+            .unwrap()
+            .unwrap();
+        let masked = mask_user(user(), UserView::Public, Some(&mask));
+        assert_eq!(masked.id, "id");
+        assert_eq!(masked.name, "lucas");
+        assert_eq!(masked.phone, None);
+        let masked = mask_user(user(), UserView::Full, Some(&mask));
+        assert!(masked.phone.is_some());
+        assert_eq!(masked.age, 0);
+    }
+
+    #[test]
+    fn test_parse_read_mask() {
+        assert_eq!(parse_read_mask(&[]).unwrap(), None);
+        assert!(parse_read_mask(&["password".to_string()]).is_err());
+        assert!(parse_read_mask(&["unknown".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_apply_privacy() {
+        let user = || User {
+            signature: "hello".to_string(),
+            region: Some("Shanghai".to_string()),
+            ..Default::default()
+        };
+        let privacy = UserPrivacy {
+            profile_visibility: PrivacyScope::Friends,
+            ..UserPrivacy::new("id")
+        };
+
+        let mut stranger = user();
+        apply_privacy(&mut stranger, &privacy, UserView::Public);
+        assert_eq!(stranger.signature, "");
+        assert_eq!(stranger.region, None);
+
+        let mut friend = user();
+        apply_privacy(&mut friend, &privacy, UserView::Friend);
+        assert_eq!(friend.signature, "hello");
+
+        let privacy = UserPrivacy {
+            profile_visibility: PrivacyScope::Nobody,
+            ..privacy
+        };
+        apply_privacy(&mut friend, &privacy, UserView::Friend);
+        assert_eq!(friend.region, None);
+    }
+
+    #[test]
+    fn test_allowed_view() {
+        let friends = HashSet::from(["friend".to_string()]);
+        let viewer = Viewer {
+            user_id: "me".to_string(),
+            privileged: false,
+        };
+        assert_eq!(allowed_view(None, "me", &friends), UserView::Public);
+        assert_eq!(allowed_view(Some(&viewer), "me", &friends), UserView::Full);
+        assert_eq!(
+            allowed_view(Some(&viewer), "friend", &friends),
+            UserView::Friend
+        );
+        assert_eq!(
+            allowed_view(Some(&viewer), "other", &friends),
+            UserView::Public
+        );
+
+        let admin = Viewer {
+            privileged: true,
+            ..viewer
+        };
+        assert_eq!(
+            allowed_view(Some(&admin), "other", &friends),
+            UserView::Full
+        );
+    }
+}
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN salt VARCHAR NOT NULL DEFAULT '';
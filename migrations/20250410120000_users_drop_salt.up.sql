@@ -0,0 +1,2 @@
+-- 盐值已包含在PHC格式的password中, 不再单独保存
+ALTER TABLE users DROP COLUMN salt;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_users_deleted;
+ALTER TABLE users DROP COLUMN purge_time;
+ALTER TABLE users DROP COLUMN delete_time;
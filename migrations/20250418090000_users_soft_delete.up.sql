@@ -0,0 +1,6 @@
+-- 注销时间, 超过宽限期后清理
+ALTER TABLE users ADD COLUMN delete_time BIGINT;
+-- 清理(匿名化)时间, 清理后不能再恢复
+ALTER TABLE users ADD COLUMN purge_time BIGINT;
+
+CREATE INDEX idx_users_deleted ON users (delete_time) WHERE is_delete = TRUE AND purge_time IS NULL;
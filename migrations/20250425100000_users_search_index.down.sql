@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_users_create_time_id;
+DROP INDEX IF EXISTS idx_users_account_trgm;
+DROP INDEX IF EXISTS idx_users_name_trgm;
@@ -0,0 +1,7 @@
+-- 用户搜索: 名称/账号的前缀和模糊匹配
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_users_name_trgm ON users USING gin (name gin_trgm_ops) WHERE is_delete = FALSE;
+CREATE INDEX idx_users_account_trgm ON users USING gin (account gin_trgm_ops) WHERE is_delete = FALSE;
+-- 游标分页
+CREATE INDEX idx_users_create_time_id ON users (create_time DESC, id DESC) WHERE is_delete = FALSE;
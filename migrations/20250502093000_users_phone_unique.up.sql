@@ -0,0 +1,2 @@
+-- 手机号可用于注册和登录, 未删除的用户之间不能重复
+CREATE UNIQUE INDEX idx_users_phone ON users (phone) WHERE is_delete = FALSE AND phone IS NOT NULL;
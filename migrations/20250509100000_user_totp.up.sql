@@ -0,0 +1,15 @@
+-- 两步验证(TOTP)
+CREATE TABLE user_totp
+(
+    user_id        VARCHAR PRIMARY KEY,
+    -- base32编码的密钥
+    secret         VARCHAR  NOT NULL,
+    -- 确认绑定之前为false
+    enabled        boolean  NOT NULL DEFAULT FALSE,
+    -- 恢复码的SHA-256哈希, 使用后移除
+    recovery_codes VARCHAR[] NOT NULL DEFAULT '{}',
+    -- 最后一次使用的时间步, 防止验证码重放
+    last_used_step BIGINT   NOT NULL DEFAULT 0,
+    create_time    BIGINT   NOT NULL,
+    update_time    BIGINT   NOT NULL
+);
@@ -0,0 +1,14 @@
+-- 第三方登录身份与用户的绑定关系
+CREATE TABLE user_identities
+(
+    provider    VARCHAR NOT NULL,
+    -- 提供方内唯一的用户标识(sub)
+    subject     VARCHAR NOT NULL,
+    user_id     VARCHAR NOT NULL,
+    email       VARCHAR(64),
+    create_time BIGINT  NOT NULL,
+    PRIMARY KEY (provider, subject)
+);
+
+-- 每个用户在同一提供方只能绑定一个身份
+CREATE UNIQUE INDEX idx_user_identities_user_provider ON user_identities (user_id, provider);
@@ -0,0 +1,16 @@
+-- 安全事件: 登录, 修改密码, 注销会话, 验证码请求等
+CREATE TABLE security_events
+(
+    id          BIGSERIAL PRIMARY KEY,
+    -- 登录失败时用户可能不存在
+    user_id     VARCHAR,
+    -- 尝试登录的账号/邮箱/手机号
+    account     VARCHAR NOT NULL DEFAULT '',
+    event_type  VARCHAR NOT NULL,
+    ip          VARCHAR NOT NULL DEFAULT '',
+    device      VARCHAR NOT NULL DEFAULT '',
+    reason      VARCHAR NOT NULL DEFAULT '',
+    create_time BIGINT  NOT NULL
+);
+
+CREATE INDEX idx_security_events_user_id ON security_events (user_id, id DESC);
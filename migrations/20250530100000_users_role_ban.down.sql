@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_users_banned;
+DROP INDEX IF EXISTS idx_users_role;
+ALTER TABLE users DROP COLUMN IF EXISTS ban_until;
+ALTER TABLE users DROP COLUMN IF EXISTS ban_reason;
+ALTER TABLE users DROP COLUMN IF EXISTS banned;
+ALTER TABLE users DROP COLUMN IF EXISTS role;
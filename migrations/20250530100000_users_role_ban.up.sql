@@ -0,0 +1,10 @@
+-- 用户角色: user/admin/support
+ALTER TABLE users ADD COLUMN role VARCHAR NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'admin', 'support'));
+-- 封禁状态, ban_until为解封时间(毫秒), 0表示永久封禁
+ALTER TABLE users ADD COLUMN banned BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN ban_reason VARCHAR NOT NULL DEFAULT '';
+ALTER TABLE users ADD COLUMN ban_until BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_users_role ON users (role) WHERE role <> 'user';
+CREATE INDEX idx_users_banned ON users (ban_until) WHERE banned = TRUE;
@@ -0,0 +1,17 @@
+-- 用户隐私设置, 没有记录时使用默认值(全部公开)
+CREATE TABLE user_privacy
+(
+    user_id               VARCHAR PRIMARY KEY,
+    -- 能否通过手机号/邮箱/账号查找到该用户
+    searchable_by_phone   boolean NOT NULL DEFAULT TRUE,
+    searchable_by_email   boolean NOT NULL DEFAULT TRUE,
+    searchable_by_account boolean NOT NULL DEFAULT TRUE,
+    -- 签名和地区的可见范围: everyone/friends/nobody
+    profile_visibility    VARCHAR NOT NULL DEFAULT 'everyone'
+        CHECK (profile_visibility IN ('everyone', 'friends', 'nobody')),
+    -- 谁可以发送好友申请: everyone/nobody
+    friend_request        VARCHAR NOT NULL DEFAULT 'everyone'
+        CHECK (friend_request IN ('everyone', 'nobody')),
+    create_time           BIGINT  NOT NULL,
+    update_time           BIGINT  NOT NULL
+);
@@ -0,0 +1,12 @@
+-- 黑名单: user_id拉黑了blocked_user_id, 双方互相不可见
+CREATE TABLE user_blocks
+(
+    id              BIGSERIAL PRIMARY KEY,
+    user_id         VARCHAR NOT NULL,
+    blocked_user_id VARCHAR NOT NULL,
+    create_time     BIGINT  NOT NULL,
+    UNIQUE (user_id, blocked_user_id)
+);
+
+CREATE INDEX idx_user_blocks_user_id ON user_blocks (user_id, id DESC);
+CREATE INDEX idx_user_blocks_blocked_user_id ON user_blocks (blocked_user_id);
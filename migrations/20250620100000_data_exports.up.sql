@@ -0,0 +1,17 @@
+-- 个人数据导出, 由后台任务生成压缩包
+CREATE TABLE data_exports
+(
+    id          VARCHAR PRIMARY KEY,
+    user_id     VARCHAR NOT NULL,
+    status      VARCHAR NOT NULL,
+    -- 压缩包大小, 字节
+    file_size   BIGINT  NOT NULL DEFAULT 0,
+    error       VARCHAR NOT NULL DEFAULT '',
+    create_time BIGINT  NOT NULL,
+    update_time BIGINT  NOT NULL,
+    -- 下载有效期, 导出完成后才有值
+    expire_time BIGINT  NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_data_exports_user_id ON data_exports (user_id, create_time DESC);
+CREATE INDEX idx_data_exports_status ON data_exports (status, update_time);